@@ -0,0 +1,199 @@
+//! Git notes implementation of the NotesTransport port.
+//!
+//! Each branch's snapshot is a note under `refs/notes/panko`, attached to a
+//! per-branch key object (the hash of `panko-branch:<branch>`), so every
+//! branch maps to a stable note without touching the branch's commits.
+
+use crate::ports::NotesTransport;
+use anyhow::{bail, Context, Result};
+use git2::{
+    Cred, CredentialType, FetchOptions, ObjectType, Oid, PushOptions, Remote, RemoteCallbacks,
+    Repository, Signature,
+};
+use std::cell::RefCell;
+use std::path::Path;
+
+/// Notes ref holding panko snapshots.
+pub const NOTES_REF: &str = "refs/notes/panko";
+
+pub struct GitNotes {
+    repo: Repository,
+}
+
+impl GitNotes {
+    pub fn open(path: &Path) -> Result<Self> {
+        let repo = Repository::discover(path).context("Failed to open git repository")?;
+        Ok(Self { repo })
+    }
+
+    /// Remote to sync with automatically, if `panko.autoSync` is enabled in git config.
+    /// The remote defaults to `origin` and can be changed with `panko.notesRemote`.
+    pub fn auto_sync_remote(&self) -> Option<String> {
+        let config = self.repo.config().ok()?;
+        if !config.get_bool("panko.autoSync").unwrap_or(false) {
+            return None;
+        }
+        Some(
+            config
+                .get_string("panko.notesRemote")
+                .unwrap_or_else(|_| "origin".to_string()),
+        )
+    }
+
+    /// Object the branch's note is attached to.
+    fn note_key(branch: &str) -> Result<Oid> {
+        Ok(Oid::hash_object(
+            ObjectType::Blob,
+            format!("panko-branch:{}", branch).as_bytes(),
+        )?)
+    }
+
+    /// A configured remote by name, or a URL/path used directly.
+    fn remote(&self, name: &str) -> Result<Remote<'_>> {
+        self.repo
+            .find_remote(name)
+            .or_else(|_| self.repo.remote_anonymous(name))
+            .with_context(|| format!("Unknown remote '{}'", name))
+    }
+
+    /// Callbacks that authenticate via ssh-agent or git's credential helpers.
+    fn callbacks(&self) -> Result<RemoteCallbacks<'static>> {
+        let config = self.repo.config()?;
+        let mut attempts = 0;
+        let mut callbacks = RemoteCallbacks::new();
+        callbacks.credentials(move |url, username, allowed| {
+            // libgit2 keeps asking while credentials are rejected
+            attempts += 1;
+            if attempts > 3 {
+                return Err(git2::Error::from_str("authentication failed"));
+            }
+            if allowed.contains(CredentialType::SSH_KEY) {
+                Cred::ssh_key_from_agent(username.unwrap_or("git"))
+            } else if allowed.contains(CredentialType::USER_PASS_PLAINTEXT) {
+                Cred::credential_helper(&config, url, username)
+            } else {
+                Cred::default()
+            }
+        });
+        Ok(callbacks)
+    }
+}
+
+impl NotesTransport for GitNotes {
+    fn read_note(&self, branch: &str) -> Result<Option<String>> {
+        match self.repo.find_note(Some(NOTES_REF), Self::note_key(branch)?) {
+            Ok(note) => Ok(note.message().map(String::from)),
+            Err(e) if e.code() == git2::ErrorCode::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn write_note(&self, branch: &str, content: &str) -> Result<()> {
+        let signature = self
+            .repo
+            .signature()
+            .or_else(|_| Signature::now("panko", "panko@localhost"))?;
+        self.repo
+            .note(&signature, &signature, Some(NOTES_REF), Self::note_key(branch)?, content, true)
+            .context("Failed to write panko note")?;
+        Ok(())
+    }
+
+    fn fetch_notes(&self, remote: &str) -> Result<()> {
+        let mut remote = self.remote(remote)?;
+        let mut options = FetchOptions::new();
+        options.remote_callbacks(self.callbacks()?);
+        remote
+            .fetch(&[format!("+{0}:{0}", NOTES_REF)], Some(&mut options), None)
+            .context("Failed to fetch panko notes")?;
+        Ok(())
+    }
+
+    fn push_notes(&self, remote: &str) -> Result<()> {
+        let mut remote = self.remote(remote)?;
+        let rejection = RefCell::new(None);
+        let mut callbacks = self.callbacks()?;
+        callbacks.push_update_reference(|_, status| {
+            *rejection.borrow_mut() = status.map(String::from);
+            Ok(())
+        });
+
+        let mut options = PushOptions::new();
+        options.remote_callbacks(callbacks);
+        remote
+            .push(&[format!("{0}:{0}", NOTES_REF)], Some(&mut options))
+            .context("Failed to push panko notes")?;
+        if let Some(reason) = rejection.take() {
+            bail!("Remote rejected panko notes: {}", reason);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn make_temp_dir(prefix: &str) -> PathBuf {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should be after UNIX_EPOCH")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("panko-{}-{}-{}", prefix, std::process::id(), ts));
+        fs::create_dir_all(&dir).expect("failed to create temp dir");
+        dir
+    }
+
+    /// A clone-like repo with `origin` pointing at the bare remote.
+    fn clone_of(remote: &Path, dir: &Path) -> GitNotes {
+        let repo = Repository::init(dir).unwrap();
+        repo.remote("origin", remote.to_str().unwrap()).unwrap();
+        GitNotes { repo }
+    }
+
+    #[test]
+    fn notes_round_trip_through_a_bare_remote() {
+        let root = make_temp_dir("notes");
+        Repository::init_bare(root.join("remote.git")).unwrap();
+        let alice = clone_of(&root.join("remote.git"), &root.join("alice"));
+        let bob = clone_of(&root.join("remote.git"), &root.join("bob"));
+
+        // Nothing to fetch yet
+        bob.fetch_notes("origin").unwrap();
+        assert_eq!(bob.read_note("main").unwrap(), None);
+
+        alice.write_note("main", "{\"v\": 1}").unwrap();
+        alice.write_note("feature", "{\"v\": 2}").unwrap();
+        alice.push_notes("origin").unwrap();
+
+        bob.fetch_notes("origin").unwrap();
+        assert_eq!(bob.read_note("main").unwrap().as_deref(), Some("{\"v\": 1}"));
+        assert_eq!(bob.read_note("feature").unwrap().as_deref(), Some("{\"v\": 2}"));
+
+        // Bob publishes on top of Alice's notes; Alice's stale notes are rejected
+        bob.write_note("main", "{\"v\": 3}").unwrap();
+        bob.push_notes("origin").unwrap();
+        alice.write_note("main", "{\"v\": 4}").unwrap();
+        assert!(alice.push_notes("origin").is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn auto_sync_is_opt_in() {
+        let root = make_temp_dir("notes-config");
+        let notes = GitNotes { repo: Repository::init(&root).unwrap() };
+        assert_eq!(notes.auto_sync_remote(), None);
+
+        let mut config = notes.repo.config().unwrap();
+        config.set_bool("panko.autoSync", true).unwrap();
+        assert_eq!(notes.auto_sync_remote().as_deref(), Some("origin"));
+        config.set_str("panko.notesRemote", "team").unwrap();
+        assert_eq!(notes.auto_sync_remote().as_deref(), Some("team"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
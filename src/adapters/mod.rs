@@ -1,11 +1,13 @@
 pub mod crossterm_adapter;
 pub mod git2_adapter;
+pub mod git_notes;
 pub mod jj_adapter;
 pub mod notify_file_watcher;
 pub mod sqlite_state_store;
 
 pub use crossterm_adapter::CrosstermTerminal;
 pub use git2_adapter::Git2Repo;
+pub use git_notes::GitNotes;
 pub use jj_adapter::JjRepo;
 pub use notify_file_watcher::NotifyFileWatcher;
 pub use sqlite_state_store::SqliteStateStore;
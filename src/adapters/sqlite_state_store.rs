@@ -1,15 +1,239 @@
 //! SQLite implementation of the StateStore port.
 
-use crate::domain::{Comment, Reply};
+use crate::domain::{
+    new_uuid, AuthorKind, Comment, CommentEvent, CommentEventKind, CommentStatus, Reply, Review, ReviewVerdict,
+    Severity,
+};
 use crate::ports::{NewComment, NewReply, StateStore, ViewedFile};
-use anyhow::{Context, Result};
-use rusqlite::Connection;
-use std::path::PathBuf;
+use anyhow::{bail, Context, Result};
+use rusqlite::{Connection, OptionalExtension, Row};
+use std::ops::DerefMut;
+use std::panic::{self, AssertUnwindSafe};
+use std::path::{Path, PathBuf};
 use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
 
-pub struct SqliteStateStore {
-    conn: Mutex<Connection>,
+/// SQL expression generating a random version 4 UUID, for backfilling rows.
+macro_rules! uuid_v4_sql {
+    () => {
+        "lower(hex(randomblob(4))) || '-' || lower(hex(randomblob(2))) || '-4' ||
+         substr(lower(hex(randomblob(2))), 2) || '-' ||
+         substr('89ab', abs(random()) % 4 + 1, 1) || substr(lower(hex(randomblob(2))), 2) || '-' ||
+         lower(hex(randomblob(6)))"
+    };
+}
+
+/// SQL expression for the current Unix time in milliseconds, for triggers.
+macro_rules! now_ms_sql {
+    () => {
+        "CAST((julianday('now') - 2440587.5) * 86400000 AS INTEGER)"
+    };
+}
+
+/// SQL condition that a comment row (e.g. `NEW`) is published: not a draft
+/// in a pending review.
+macro_rules! published_sql {
+    ($row:literal) => {
+        concat!(
+            "(", $row, ".review_id IS NULL OR EXISTS (SELECT 1 FROM reviews WHERE id = ", $row,
+            ".review_id AND submitted_at IS NOT NULL))"
+        )
+    };
+}
+
+/// Schema changes applied on top of the base schema, in order.
+/// `PRAGMA user_version` records how many have already run.
+const MIGRATIONS: &[&str] = &[
+    // 1: pending reviews
+    "
+    CREATE TABLE reviews (
+        id INTEGER PRIMARY KEY,
+        repo_path TEXT NOT NULL,
+        branch TEXT NOT NULL,
+        author TEXT NOT NULL,
+        body TEXT NOT NULL DEFAULT '',
+        verdict TEXT,
+        created_at INTEGER NOT NULL,
+        submitted_at INTEGER
+    );
+    CREATE INDEX idx_reviews_repo_branch ON reviews(repo_path, branch);
+    ALTER TABLE comments ADD COLUMN review_id INTEGER REFERENCES reviews(id);
+    ",
+    // 2: file-level and branch-level comments (nullable anchor columns)
+    "
+    CREATE TABLE comments_new (
+        id INTEGER PRIMARY KEY,
+        repo_path TEXT NOT NULL,
+        branch TEXT NOT NULL,
+        file_path TEXT,
+        start_line INTEGER,
+        end_line INTEGER,
+        body TEXT NOT NULL,
+        author TEXT NOT NULL,
+        created_at INTEGER NOT NULL,
+        resolved INTEGER NOT NULL DEFAULT 0,
+        resolved_at INTEGER,
+        review_id INTEGER REFERENCES reviews(id)
+    );
+    INSERT INTO comments_new
+        (id, repo_path, branch, file_path, start_line, end_line, body, author,
+         created_at, resolved, resolved_at, review_id)
+    SELECT id, repo_path, branch, file_path, start_line, end_line, body, author,
+           created_at, resolved, resolved_at, review_id
+    FROM comments;
+    DROP TABLE comments;
+    ALTER TABLE comments_new RENAME TO comments;
+    CREATE INDEX idx_comments_repo_branch ON comments(repo_path, branch);
+    CREATE INDEX idx_comments_file ON comments(repo_path, branch, file_path);
+    ",
+    // 3: severity and labels (labels stored as a JSON array)
+    "
+    ALTER TABLE comments ADD COLUMN severity TEXT;
+    ALTER TABLE comments ADD COLUMN labels TEXT NOT NULL DEFAULT '[]';
+    ",
+    // 4: workflow status and assignee (`resolved` is kept in sync for older readers)
+    "
+    ALTER TABLE comments ADD COLUMN status TEXT NOT NULL DEFAULT 'open';
+    ALTER TABLE comments ADD COLUMN assignee TEXT;
+    UPDATE comments SET status = 'resolved' WHERE resolved = 1;
+    ",
+    // 5: stable UUIDs, update timestamps and tombstones for sharing comments
+    concat!(
+        "
+        ALTER TABLE comments ADD COLUMN uuid TEXT;
+        ALTER TABLE comments ADD COLUMN updated_at INTEGER NOT NULL DEFAULT 0;
+        UPDATE comments SET updated_at = COALESCE(resolved_at, created_at), uuid = ",
+        uuid_v4_sql!(),
+        ";
+        CREATE UNIQUE INDEX idx_comments_uuid ON comments(uuid);
+        ALTER TABLE replies ADD COLUMN uuid TEXT;
+        UPDATE replies SET uuid = ",
+        uuid_v4_sql!(),
+        ";
+        CREATE UNIQUE INDEX idx_replies_uuid ON replies(uuid);
+        CREATE TABLE deleted_comments (
+            uuid TEXT PRIMARY KEY,
+            repo_path TEXT NOT NULL,
+            branch TEXT NOT NULL,
+            deleted_at INTEGER NOT NULL
+        );
+        "
+    ),
+    // 6: stable UUIDs for reviews, so archives can be imported idempotently
+    concat!(
+        "
+        ALTER TABLE reviews ADD COLUMN uuid TEXT;
+        UPDATE reviews SET uuid = ",
+        uuid_v4_sql!(),
+        ";
+        CREATE UNIQUE INDEX idx_reviews_uuid ON reviews(uuid);
+        "
+    ),
+    // 7: text of the anchored lines, to flag comments whose code changed
+    "ALTER TABLE comments ADD COLUMN anchor TEXT;",
+    // 8: change log of published comments, for `panko watch`. Triggers record
+    // every writer, including other processes and imports; drafts are logged
+    // when their review is submitted.
+    concat!(
+        "
+        CREATE TABLE comment_events (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            repo_path TEXT NOT NULL,
+            branch TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            comment_id INTEGER NOT NULL,
+            comment_uuid TEXT NOT NULL,
+            reply_id INTEGER,
+            created_at INTEGER NOT NULL
+        );
+        CREATE INDEX idx_comment_events_repo_branch ON comment_events(repo_path, branch, id);
+
+        CREATE TRIGGER comment_added_event AFTER INSERT ON comments
+        WHEN ", published_sql!("NEW"), "
+        BEGIN
+            INSERT INTO comment_events (repo_path, branch, kind, comment_id, comment_uuid, created_at)
+            VALUES (NEW.repo_path, NEW.branch, 'comment_added', NEW.id, NEW.uuid, ", now_ms_sql!(), ");
+        END;
+
+        CREATE TRIGGER review_submitted_events AFTER UPDATE OF submitted_at ON reviews
+        WHEN OLD.submitted_at IS NULL AND NEW.submitted_at IS NOT NULL
+        BEGIN
+            INSERT INTO comment_events (repo_path, branch, kind, comment_id, comment_uuid, created_at)
+            SELECT repo_path, branch, 'comment_added', id, uuid, ", now_ms_sql!(), "
+            FROM comments WHERE review_id = NEW.id ORDER BY id;
+        END;
+
+        CREATE TRIGGER reply_added_event AFTER INSERT ON replies
+        BEGIN
+            INSERT INTO comment_events (repo_path, branch, kind, comment_id, comment_uuid, reply_id, created_at)
+            SELECT c.repo_path, c.branch, 'reply_added', c.id, c.uuid, NEW.id, ", now_ms_sql!(), "
+            FROM comments c WHERE c.id = NEW.comment_id AND ", published_sql!("c"), ";
+        END;
+
+        CREATE TRIGGER comment_status_event AFTER UPDATE OF status ON comments
+        WHEN (OLD.status IN ('resolved', 'wont-fix')) != (NEW.status IN ('resolved', 'wont-fix'))
+            AND ", published_sql!("NEW"), "
+        BEGIN
+            INSERT INTO comment_events (repo_path, branch, kind, comment_id, comment_uuid, created_at)
+            VALUES (NEW.repo_path, NEW.branch,
+                    CASE WHEN NEW.status IN ('resolved', 'wont-fix') THEN 'resolved' ELSE 'unresolved' END,
+                    NEW.id, NEW.uuid, ", now_ms_sql!(), ");
+        END;
+
+        CREATE TRIGGER comment_deleted_event AFTER DELETE ON comments
+        WHEN ", published_sql!("OLD"), "
+        BEGIN
+            INSERT INTO comment_events (repo_path, branch, kind, comment_id, comment_uuid, created_at)
+            VALUES (OLD.repo_path, OLD.branch, 'deleted', OLD.id, OLD.uuid, ", now_ms_sql!(), ");
+        END;
+        "
+    ),
+    // 9: whether a human, an agent or a tool wrote each message. Earlier
+    // agent messages were written as "Agent".
+    "
+    ALTER TABLE comments ADD COLUMN author_kind TEXT NOT NULL DEFAULT 'human';
+    ALTER TABLE replies ADD COLUMN author_kind TEXT NOT NULL DEFAULT 'human';
+    UPDATE comments SET author_kind = 'agent' WHERE author = 'Agent';
+    UPDATE replies SET author_kind = 'agent' WHERE author = 'Agent';
+    ",
+    // 10: UUIDs and tombstones are unique per repository and branch, so other
+    // checkouts sharing this database can hold copies of the same comments
+    "
+    DROP INDEX idx_comments_uuid;
+    CREATE UNIQUE INDEX idx_comments_uuid ON comments(repo_path, branch, uuid);
+    DROP INDEX idx_replies_uuid;
+    CREATE UNIQUE INDEX idx_replies_uuid ON replies(comment_id, uuid);
+    CREATE TABLE deleted_comments_new (
+        uuid TEXT NOT NULL,
+        repo_path TEXT NOT NULL,
+        branch TEXT NOT NULL,
+        deleted_at INTEGER NOT NULL,
+        PRIMARY KEY (repo_path, branch, uuid)
+    );
+    INSERT INTO deleted_comments_new (uuid, repo_path, branch, deleted_at)
+    SELECT uuid, repo_path, branch, deleted_at FROM deleted_comments;
+    DROP TABLE deleted_comments;
+    ALTER TABLE deleted_comments_new RENAME TO deleted_comments;
+    ",
+    // 11: review UUIDs are unique per repository and branch too, so an archive
+    // can be imported into another branch of the repository it came from
+    "
+    DROP INDEX idx_reviews_uuid;
+    CREATE UNIQUE INDEX idx_reviews_uuid ON reviews(repo_path, branch, uuid);
+    ",
+];
+
+/// Columns selected for a `Comment` (see `comment_from_row`).
+/// Expects `comments` aliased as `c` and `reviews` left-joined as `r`.
+const COMMENT_COLUMNS: &str =
+    "c.id, c.file_path, c.start_line, c.end_line, c.body, c.author, c.created_at,
+     c.status, c.resolved_at, c.review_id, r.id IS NOT NULL AND r.submitted_at IS NULL,
+     c.severity, c.labels, c.assignee, c.uuid, c.updated_at, c.anchor, c.author_kind";
+
+/// `C` holds the connection: the store's own, or one borrowed by an open
+/// transaction (see `StateStore::transaction`).
+pub struct SqliteStateStore<C = Box<Connection>> {
+    conn: Mutex<C>,
 }
 
 impl SqliteStateStore {
@@ -24,9 +248,23 @@ impl SqliteStateStore {
                 .context("Failed to create config directory")?;
         }
 
-        let conn = Connection::open(&db_path)
+        Self::open(&db_path)
+    }
+
+    /// Open (or create) a state store at an explicit path.
+    pub fn open(db_path: &Path) -> Result<Self> {
+        let conn = Connection::open(db_path)
             .context("Failed to open SQLite database")?;
+        Self::from_connection(conn)
+    }
 
+    /// Open a throwaway in-memory store.
+    #[cfg(test)]
+    pub fn open_in_memory() -> Result<Self> {
+        Self::from_connection(Connection::open_in_memory()?)
+    }
+
+    fn from_connection(conn: Connection) -> Result<Self> {
         // Initialize schema
         conn.execute_batch(
             "
@@ -72,18 +310,44 @@ impl SqliteStateStore {
             "
         ).context("Failed to initialize database schema")?;
 
+        Self::migrate(&conn)?;
+
         Ok(Self {
-            conn: Mutex::new(conn),
+            conn: Mutex::new(Box::new(conn)),
         })
     }
 
+    /// Apply any migrations that haven't run yet.
+    fn migrate(conn: &Connection) -> Result<()> {
+        let version: usize = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+        if version >= MIGRATIONS.len() {
+            return Ok(());
+        }
+
+        // Table rebuilds must not cascade deletes into replies. This pragma is a
+        // no-op inside a transaction, so toggle it around the migrations.
+        conn.execute_batch("PRAGMA foreign_keys = OFF;")?;
+        let result = MIGRATIONS.iter().enumerate().skip(version).try_for_each(|(i, sql)| {
+            conn.execute_batch(&format!(
+                "BEGIN; {} PRAGMA user_version = {}; COMMIT;",
+                sql,
+                i + 1
+            ))
+            .with_context(|| format!("Failed to apply database migration {}", i + 1))
+        });
+        conn.execute_batch("PRAGMA foreign_keys = ON;")?;
+        result
+    }
+
     /// Get the database file path.
     fn db_path() -> Result<PathBuf> {
         let config_dir = dirs::config_dir()
             .context("Could not find config directory")?;
         Ok(config_dir.join("panko").join("state.db"))
     }
+}
 
+impl<C> SqliteStateStore<C> {
     /// Get current timestamp in milliseconds.
     fn now_ms() -> i64 {
         SystemTime::now()
@@ -92,10 +356,76 @@ impl SqliteStateStore {
             .unwrap_or(0)
     }
 
+    /// Map a row selected with `COMMENT_COLUMNS` to a comment (without replies).
+    fn comment_from_row(row: &Row) -> rusqlite::Result<Comment> {
+        Ok(Comment {
+            id: row.get(0)?,
+            uuid: row.get(14)?,
+            file_path: row.get(1)?,
+            line_range: match (row.get::<_, Option<i64>>(2)?, row.get::<_, Option<i64>>(3)?) {
+                (Some(start), Some(end)) => Some((start as usize, end as usize)),
+                _ => None,
+            },
+            anchor: row.get(16)?,
+            body: row.get(4)?,
+            author: row.get(5)?,
+            author_kind: AuthorKind::parse(&row.get::<_, String>(17)?).unwrap_or_default(),
+            created_at: row.get(6)?,
+            status: CommentStatus::parse(&row.get::<_, String>(7)?).unwrap_or_default(),
+            resolved_at: row.get(8)?,
+            assignee: row.get(13)?,
+            updated_at: row.get(15)?,
+            review_id: row.get(9)?,
+            draft: row.get(10)?,
+            severity: row
+                .get::<_, Option<String>>(11)?
+                .as_deref()
+                .and_then(Severity::parse),
+            labels: serde_json::from_str(&row.get::<_, String>(12)?).unwrap_or_default(),
+            replies: vec![],
+        })
+    }
+
+    /// Run a comment query and attach replies to each result.
+    fn query_comments(
+        conn: &Connection,
+        sql: &str,
+        params: impl rusqlite::Params,
+    ) -> Result<Vec<Comment>> {
+        let mut stmt = conn.prepare(sql)?;
+        let mut comments: Vec<Comment> = stmt
+            .query_map(params, Self::comment_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        // Drop the statement to release the borrow on conn
+        drop(stmt);
+
+        // Load replies for each comment
+        for comment in &mut comments {
+            comment.replies = Self::load_replies(conn, comment.id)?;
+        }
+
+        Ok(comments)
+    }
+
+    fn review_from_row(row: &Row) -> rusqlite::Result<Review> {
+        let verdict: Option<String> = row.get(4)?;
+        Ok(Review {
+            id: row.get(0)?,
+            uuid: row.get(6)?,
+            author: row.get(1)?,
+            body: row.get(2)?,
+            created_at: row.get(3)?,
+            verdict: verdict.as_deref().and_then(ReviewVerdict::parse),
+            submitted_at: row.get(5)?,
+        })
+    }
+
     /// Internal helper to load replies for a comment.
     fn load_replies(conn: &Connection, comment_id: i64) -> Result<Vec<Reply>> {
         let mut stmt = conn.prepare(
-            "SELECT id, comment_id, body, author, created_at
+            "SELECT id, comment_id, body, author, created_at, uuid, author_kind
              FROM replies
              WHERE comment_id = ?1
              ORDER BY created_at"
@@ -105,9 +435,11 @@ impl SqliteStateStore {
             .query_map((comment_id,), |row| {
                 Ok(Reply {
                     id: row.get(0)?,
+                    uuid: row.get(5)?,
                     comment_id: row.get(1)?,
                     body: row.get(2)?,
                     author: row.get(3)?,
+                    author_kind: AuthorKind::parse(&row.get::<_, String>(6)?).unwrap_or_default(),
                     created_at: row.get(4)?,
                 })
             })?
@@ -118,7 +450,7 @@ impl SqliteStateStore {
     }
 }
 
-impl StateStore for SqliteStateStore {
+impl<C: DerefMut<Target = Connection> + Send> StateStore for SqliteStateStore<C> {
     fn mark_viewed(&self, repo_path: &str, branch: &str, file_path: &str) -> Result<()> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
@@ -138,6 +470,18 @@ impl StateStore for SqliteStateStore {
         Ok(())
     }
 
+    fn import_viewed(&self, repo_path: &str, branch: &str, file: &ViewedFile) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO viewed_files (repo_path, branch, file_path, viewed_at)
+             VALUES (?1, ?2, ?3, ?4)
+             ON CONFLICT (repo_path, branch, file_path)
+             DO UPDATE SET viewed_at = max(viewed_at, excluded.viewed_at)",
+            (repo_path, branch, &file.file_path, file.viewed_at),
+        )?;
+        Ok(())
+    }
+
     fn get_viewed_files(&self, repo_path: &str, branch: &str) -> Result<Vec<ViewedFile>> {
         let conn = self.conn.lock().unwrap();
         let mut stmt = conn.prepare(
@@ -157,22 +501,60 @@ impl StateStore for SqliteStateStore {
         Ok(files)
     }
 
+    fn transaction(&self, f: &mut dyn FnMut(&dyn StateStore) -> Result<bool>) -> Result<()> {
+        // Hold the lock throughout, so other callers sharing this store wait
+        // instead of running inside the transaction. `f` makes its calls
+        // through a store borrowing the locked connection; multi-statement
+        // methods use savepoints, which nest inside this.
+        let mut conn = self.conn.lock().unwrap();
+        conn.execute_batch("BEGIN IMMEDIATE")?;
+        let scoped = SqliteStateStore { conn: Mutex::new(&mut **conn) };
+        let result = panic::catch_unwind(AssertUnwindSafe(|| f(&scoped)));
+
+        let ended = if matches!(result, Ok(Ok(true))) {
+            conn.execute_batch("COMMIT")
+        } else {
+            conn.execute_batch("ROLLBACK")
+        };
+        if ended.is_err() && !conn.is_autocommit() {
+            conn.execute_batch("ROLLBACK")?;
+        }
+        match result {
+            Ok(result) => {
+                result?;
+                Ok(ended?)
+            }
+            Err(panic) => {
+                // Release the lock unpoisoned; the transaction is already rolled back
+                drop(conn);
+                panic::resume_unwind(panic)
+            }
+        }
+    }
+
     // ─── Comment methods ───
 
     fn add_comment(&self, repo_path: &str, branch: &str, comment: NewComment) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
+        let now = Self::now_ms();
         conn.execute(
-            "INSERT INTO comments (repo_path, branch, file_path, start_line, end_line, body, author, created_at, resolved)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 0)",
+            "INSERT INTO comments (repo_path, branch, file_path, start_line, end_line, body, author, created_at, resolved, review_id, severity, labels, uuid, updated_at, anchor, author_kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 0, ?9, ?10, ?11, ?12, ?8, ?13, ?14)",
             (
                 repo_path,
                 branch,
                 &comment.file_path,
-                comment.start_line as i64,
-                comment.end_line as i64,
+                comment.line_range.map(|(start, _)| start as i64),
+                comment.line_range.map(|(_, end)| end as i64),
                 &comment.body,
                 &comment.author,
-                Self::now_ms(),
+                now,
+                comment.review_id,
+                comment.severity.map(Severity::as_str),
+                serde_json::to_string(&comment.labels)?,
+                &comment.uuid,
+                &comment.anchor,
+                comment.author_kind.as_str(),
             ),
         )?;
         Ok(conn.last_insert_rowid())
@@ -180,65 +562,157 @@ impl StateStore for SqliteStateStore {
 
     fn get_comments(&self, repo_path: &str, branch: &str) -> Result<Vec<Comment>> {
         let conn = self.conn.lock().unwrap();
+        Self::query_comments(
+            &conn,
+            &format!(
+                "SELECT {COMMENT_COLUMNS}
+                 FROM comments c LEFT JOIN reviews r ON r.id = c.review_id
+                 WHERE c.repo_path = ?1 AND c.branch = ?2
+                   AND (r.id IS NULL OR r.submitted_at IS NOT NULL)
+                 ORDER BY c.file_path IS NOT NULL, c.file_path, c.start_line, c.created_at"
+            ),
+            (repo_path, branch),
+        )
+    }
 
-        // First, collect comment IDs and data
-        let mut stmt = conn.prepare(
-            "SELECT id, file_path, start_line, end_line, body, author, created_at, resolved, resolved_at
-             FROM comments
-             WHERE repo_path = ?1 AND branch = ?2
-             ORDER BY file_path, start_line"
+    fn set_comment_status(&self, comment_id: i64, status: CommentStatus) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let now = Self::now_ms();
+        let resolved_at = status.is_closed().then_some(now);
+        let updated = conn.execute(
+            "UPDATE comments SET status = ?1, resolved = ?2, resolved_at = ?3, updated_at = ?4
+             WHERE id = ?5",
+            (status.as_str(), status == CommentStatus::Resolved, resolved_at, now, comment_id),
         )?;
+        if updated == 0 {
+            bail!("Comment #{} not found", comment_id);
+        }
+        Ok(())
+    }
 
-        let mut comments: Vec<Comment> = stmt
-            .query_map((repo_path, branch), |row| {
-                Ok(Comment {
-                    id: row.get(0)?,
-                    file_path: row.get(1)?,
-                    start_line: row.get::<_, i64>(2)? as usize,
-                    end_line: row.get::<_, i64>(3)? as usize,
-                    body: row.get(4)?,
-                    author: row.get(5)?,
-                    created_at: row.get(6)?,
-                    resolved: row.get::<_, i64>(7)? != 0,
-                    resolved_at: row.get(8)?,
-                    replies: vec![],
-                })
-            })?
-            .filter_map(|r| r.ok())
-            .collect();
+    fn assign_comment(&self, comment_id: i64, assignee: Option<&str>) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE comments SET assignee = ?1, updated_at = ?2 WHERE id = ?3",
+            (assignee, Self::now_ms(), comment_id),
+        )?;
+        if updated == 0 {
+            bail!("Comment #{} not found", comment_id);
+        }
+        Ok(())
+    }
 
-        // Drop the statement to release the borrow on conn
-        drop(stmt);
+    fn delete_comment(&self, comment_id: i64) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.savepoint()?;
+        // Leave a tombstone so the deletion propagates to shared copies
+        tx.execute(
+            "INSERT OR REPLACE INTO deleted_comments (uuid, repo_path, branch, deleted_at)
+             SELECT uuid, repo_path, branch, ?2 FROM comments WHERE id = ?1",
+            (comment_id, Self::now_ms()),
+        )?;
+        tx.execute("DELETE FROM comments WHERE id = ?1", (comment_id,))?;
+        tx.commit()?;
+        Ok(())
+    }
 
-        // Load replies for each comment
-        for comment in &mut comments {
-            comment.replies = Self::load_replies(&conn, comment.id)?;
+    fn import_comment(&self, repo_path: &str, branch: &str, comment: &Comment) -> Result<i64> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.savepoint()?;
+        tx.execute(
+            "INSERT INTO comments (repo_path, branch, file_path, start_line, end_line, body, author, created_at,
+                                   resolved, resolved_at, status, assignee, severity, labels, uuid, updated_at,
+                                   review_id, anchor, author_kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)",
+            rusqlite::params![
+                repo_path,
+                branch,
+                &comment.file_path,
+                comment.line_range.map(|(start, _)| start as i64),
+                comment.line_range.map(|(_, end)| end as i64),
+                &comment.body,
+                &comment.author,
+                comment.created_at,
+                comment.status == CommentStatus::Resolved,
+                comment.resolved_at,
+                comment.status.as_str(),
+                &comment.assignee,
+                comment.severity.map(Severity::as_str),
+                serde_json::to_string(&comment.labels)?,
+                &comment.uuid,
+                comment.updated_at,
+                comment.review_id,
+                &comment.anchor,
+                comment.author_kind.as_str(),
+            ],
+        )?;
+        let id = tx.last_insert_rowid();
+        for reply in &comment.replies {
+            tx.execute(
+                "INSERT INTO replies (comment_id, body, author, created_at, uuid, author_kind)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                (id, &reply.body, &reply.author, reply.created_at, &reply.uuid, reply.author_kind.as_str()),
+            )?;
         }
-
-        Ok(comments)
+        tx.commit()?;
+        Ok(id)
     }
 
-    fn resolve_comment(&self, comment_id: i64) -> Result<()> {
+    fn update_comment_state(&self, comment_id: i64, comment: &Comment) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "UPDATE comments SET resolved = 1, resolved_at = ?1 WHERE id = ?2",
-            (Self::now_ms(), comment_id),
+        let updated = conn.execute(
+            "UPDATE comments SET status = ?1, resolved = ?2, resolved_at = ?3, assignee = ?4, updated_at = ?5,
+                 start_line = ?6, end_line = ?7, anchor = ?8
+             WHERE id = ?9",
+            (
+                comment.status.as_str(),
+                comment.status == CommentStatus::Resolved,
+                comment.resolved_at,
+                &comment.assignee,
+                comment.updated_at,
+                comment.line_range.map(|(start, _)| start as i64),
+                comment.line_range.map(|(_, end)| end as i64),
+                &comment.anchor,
+                comment_id,
+            ),
         )?;
+        if updated == 0 {
+            bail!("Comment #{} not found", comment_id);
+        }
         Ok(())
     }
 
-    fn unresolve_comment(&self, comment_id: i64) -> Result<()> {
+    fn move_comment(&self, comment_id: i64, line_range: (usize, usize), anchor: Option<&str>) -> Result<()> {
         let conn = self.conn.lock().unwrap();
-        conn.execute(
-            "UPDATE comments SET resolved = 0, resolved_at = NULL WHERE id = ?1",
-            (comment_id,),
+        let updated = conn.execute(
+            "UPDATE comments SET start_line = ?1, end_line = ?2, anchor = ?3, updated_at = ?4 WHERE id = ?5",
+            (line_range.0 as i64, line_range.1 as i64, anchor, Self::now_ms(), comment_id),
         )?;
+        if updated == 0 {
+            bail!("Comment #{} not found", comment_id);
+        }
         Ok(())
     }
 
-    fn delete_comment(&self, comment_id: i64) -> Result<()> {
+    fn get_deleted_comments(&self, repo_path: &str, branch: &str) -> Result<Vec<String>> {
         let conn = self.conn.lock().unwrap();
-        conn.execute("DELETE FROM comments WHERE id = ?1", (comment_id,))?;
+        let mut stmt = conn.prepare(
+            "SELECT uuid FROM deleted_comments WHERE repo_path = ?1 AND branch = ?2 ORDER BY deleted_at",
+        )?;
+        let uuids = stmt
+            .query_map((repo_path, branch), |row| row.get(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(uuids)
+    }
+
+    fn record_deleted_comment(&self, repo_path: &str, branch: &str, uuid: &str) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT OR IGNORE INTO deleted_comments (uuid, repo_path, branch, deleted_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            (uuid, repo_path, branch, Self::now_ms()),
+        )?;
         Ok(())
     }
 
@@ -247,11 +721,481 @@ impl StateStore for SqliteStateStore {
     fn add_reply(&self, reply: NewReply) -> Result<i64> {
         let conn = self.conn.lock().unwrap();
         conn.execute(
-            "INSERT INTO replies (comment_id, body, author, created_at)
-             VALUES (?1, ?2, ?3, ?4)",
-            (reply.comment_id, &reply.body, &reply.author, Self::now_ms()),
+            "INSERT INTO replies (comment_id, body, author, created_at, uuid, author_kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            (reply.comment_id, &reply.body, &reply.author, Self::now_ms(), &reply.uuid, reply.author_kind.as_str()),
         )?;
         Ok(conn.last_insert_rowid())
     }
 
+    fn import_reply(&self, comment_id: i64, reply: &Reply) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO replies (comment_id, body, author, created_at, uuid, author_kind)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            (comment_id, &reply.body, &reply.author, reply.created_at, &reply.uuid, reply.author_kind.as_str()),
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    // ─── Event methods ───
+
+    fn get_comment_events(&self, repo_path: &str, branch: &str, cursor: i64) -> Result<Vec<CommentEvent>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, kind, comment_id, comment_uuid, reply_id, created_at
+             FROM comment_events
+             WHERE repo_path = ?1 AND branch = ?2 AND id > ?3
+             ORDER BY id",
+        )?;
+        let events = stmt
+            .query_map((repo_path, branch, cursor), |row| {
+                let kind: String = row.get(1)?;
+                Ok(CommentEvent {
+                    cursor: row.get(0)?,
+                    kind: CommentEventKind::parse(&kind).unwrap_or(CommentEventKind::CommentAdded),
+                    comment_id: row.get(2)?,
+                    comment_uuid: row.get(3)?,
+                    reply_id: row.get(4)?,
+                    created_at: row.get(5)?,
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(events)
+    }
+
+    fn latest_event_cursor(&self) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        let cursor = conn.query_row("SELECT COALESCE(MAX(id), 0) FROM comment_events", [], |row| row.get(0))?;
+        Ok(cursor)
+    }
+
+    // ─── Review methods ───
+
+    fn start_review(&self, repo_path: &str, branch: &str, author: &str) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO reviews (repo_path, branch, author, created_at, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            (repo_path, branch, author, Self::now_ms(), new_uuid()),
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn get_pending_review(
+        &self,
+        repo_path: &str,
+        branch: &str,
+        author: &str,
+    ) -> Result<Option<Review>> {
+        let conn = self.conn.lock().unwrap();
+        let review = conn
+            .query_row(
+                "SELECT id, author, body, created_at, verdict, submitted_at, uuid
+                 FROM reviews
+                 WHERE repo_path = ?1 AND branch = ?2 AND author = ?3
+                   AND submitted_at IS NULL
+                 ORDER BY created_at DESC
+                 LIMIT 1",
+                (repo_path, branch, author),
+                Self::review_from_row,
+            )
+            .optional()?;
+        Ok(review)
+    }
+
+    fn get_review_comments(&self, review_id: i64) -> Result<Vec<Comment>> {
+        let conn = self.conn.lock().unwrap();
+        Self::query_comments(
+            &conn,
+            &format!(
+                "SELECT {COMMENT_COLUMNS}
+                 FROM comments c LEFT JOIN reviews r ON r.id = c.review_id
+                 WHERE c.review_id = ?1
+                 ORDER BY c.file_path IS NOT NULL, c.file_path, c.start_line, c.created_at"
+            ),
+            (review_id,),
+        )
+    }
+
+    fn submit_review(&self, review_id: i64, body: &str, verdict: ReviewVerdict) -> Result<()> {
+        let conn = self.conn.lock().unwrap();
+        let updated = conn.execute(
+            "UPDATE reviews SET body = ?1, verdict = ?2, submitted_at = ?3
+             WHERE id = ?4 AND submitted_at IS NULL",
+            (body, verdict.as_str(), Self::now_ms(), review_id),
+        )?;
+        if updated == 0 {
+            bail!("No pending review with id {}", review_id);
+        }
+        Ok(())
+    }
+
+    fn discard_review(&self, review_id: i64) -> Result<()> {
+        let mut conn = self.conn.lock().unwrap();
+        let tx = conn.savepoint()?;
+        let pending: bool = tx.query_row(
+            "SELECT EXISTS(SELECT 1 FROM reviews WHERE id = ?1 AND submitted_at IS NULL)",
+            (review_id,),
+            |row| row.get(0),
+        )?;
+        if !pending {
+            bail!("No pending review with id {}", review_id);
+        }
+        // Replies cascade with their comments
+        tx.execute("DELETE FROM comments WHERE review_id = ?1", (review_id,))?;
+        tx.execute("DELETE FROM reviews WHERE id = ?1", (review_id,))?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn import_review(&self, repo_path: &str, branch: &str, review: &Review) -> Result<i64> {
+        let conn = self.conn.lock().unwrap();
+        conn.execute(
+            "INSERT INTO reviews (repo_path, branch, author, body, verdict, created_at, submitted_at, uuid)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            (
+                repo_path,
+                branch,
+                &review.author,
+                &review.body,
+                review.verdict.map(ReviewVerdict::as_str),
+                review.created_at,
+                review.submitted_at,
+                &review.uuid,
+            ),
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    fn get_reviews(&self, repo_path: &str, branch: &str) -> Result<Vec<Review>> {
+        let conn = self.conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT id, author, body, created_at, verdict, submitted_at, uuid
+             FROM reviews
+             WHERE repo_path = ?1 AND branch = ?2 AND submitted_at IS NOT NULL
+             ORDER BY submitted_at",
+        )?;
+        let reviews = stmt
+            .query_map((repo_path, branch), Self::review_from_row)?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(reviews)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CommentScope;
+
+    fn store() -> SqliteStateStore {
+        SqliteStateStore::from_connection(Connection::open_in_memory().unwrap()).unwrap()
+    }
+
+    fn new_comment(line: usize, review_id: Option<i64>) -> NewComment {
+        NewComment {
+            file_path: Some("src/lib.rs".to_string()),
+            line_range: Some((line, line)),
+            anchor: None,
+            body: format!("comment on {}", line),
+            author: "alice".to_string(),
+            author_kind: AuthorKind::Human,
+            severity: None,
+            labels: vec![],
+            review_id,
+            uuid: crate::domain::new_uuid(),
+        }
+    }
+
+    #[test]
+    fn migrations_are_idempotent() {
+        let conn = Connection::open_in_memory().unwrap();
+        let store = SqliteStateStore::from_connection(conn).unwrap();
+        let conn = store.conn.into_inner().unwrap();
+        SqliteStateStore::migrate(&conn).unwrap();
+        let version: usize = conn
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(version, MIGRATIONS.len());
+    }
+
+    #[test]
+    fn drafts_are_hidden_until_review_is_submitted() {
+        let store = store();
+        let review = store.start_review("/repo", "main", "alice").unwrap();
+        store.add_comment("/repo", "main", new_comment(1, None)).unwrap();
+        store.add_comment("/repo", "main", new_comment(2, Some(review))).unwrap();
+
+        let published = store.get_comments("/repo", "main").unwrap();
+        assert_eq!(published.len(), 1);
+        assert!(!published[0].draft);
+
+        let drafts = store.get_review_comments(review).unwrap();
+        assert_eq!(drafts.len(), 1);
+        assert!(drafts[0].draft);
+        assert_eq!(
+            store.get_pending_review("/repo", "main", "alice").unwrap().map(|r| r.id),
+            Some(review)
+        );
+        assert!(store.get_pending_review("/repo", "main", "bob").unwrap().is_none());
+
+        store
+            .submit_review(review, "looks good", ReviewVerdict::Approve)
+            .unwrap();
+        let published = store.get_comments("/repo", "main").unwrap();
+        assert_eq!(published.len(), 2);
+        assert!(published.iter().all(|c| !c.draft));
+        assert!(store.get_pending_review("/repo", "main", "alice").unwrap().is_none());
+
+        let reviews = store.get_reviews("/repo", "main").unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].verdict, Some(ReviewVerdict::Approve));
+        assert_eq!(reviews[0].body, "looks good");
+        assert!(store.submit_review(review, "", ReviewVerdict::Comment).is_err());
+    }
+
+    #[test]
+    fn discarding_a_review_deletes_its_drafts() {
+        let store = store();
+        let review = store.start_review("/repo", "main", "alice").unwrap();
+        store.add_comment("/repo", "main", new_comment(1, Some(review))).unwrap();
+        store.discard_review(review).unwrap();
+
+        assert!(store.get_review_comments(review).unwrap().is_empty());
+        assert!(store.get_reviews("/repo", "main").unwrap().is_empty());
+        assert!(store.get_pending_review("/repo", "main", "alice").unwrap().is_none());
+    }
+
+    #[test]
+    fn comments_without_lines_or_file_round_trip() {
+        let store = store();
+        let mut general = new_comment(0, None);
+        general.file_path = None;
+        general.line_range = None;
+        let mut file_level = new_comment(0, None);
+        file_level.line_range = None;
+        store.add_comment("/repo", "main", new_comment(3, None)).unwrap();
+        store.add_comment("/repo", "main", file_level).unwrap();
+        store.add_comment("/repo", "main", general).unwrap();
+
+        let comments = store.get_comments("/repo", "main").unwrap();
+        let scopes: Vec<_> = comments.iter().map(|c| c.scope()).collect();
+        assert_eq!(
+            scopes,
+            vec![CommentScope::Branch, CommentScope::File, CommentScope::Lines]
+        );
+        assert_eq!(comments[2].line_range, Some((3, 3)));
+    }
+
+    #[test]
+    fn nullable_anchor_migration_keeps_existing_rows() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(
+            "
+            CREATE TABLE comments (
+                id INTEGER PRIMARY KEY,
+                repo_path TEXT NOT NULL,
+                branch TEXT NOT NULL,
+                file_path TEXT NOT NULL,
+                start_line INTEGER NOT NULL,
+                end_line INTEGER NOT NULL,
+                body TEXT NOT NULL,
+                author TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                resolved INTEGER NOT NULL DEFAULT 0,
+                resolved_at INTEGER
+            );
+            CREATE TABLE replies (
+                id INTEGER PRIMARY KEY,
+                comment_id INTEGER NOT NULL,
+                body TEXT NOT NULL,
+                author TEXT NOT NULL,
+                created_at INTEGER NOT NULL,
+                FOREIGN KEY (comment_id) REFERENCES comments(id) ON DELETE CASCADE
+            );
+            INSERT INTO comments VALUES (7, '/repo', 'main', 'a.rs', 2, 4, 'old', 'bob', 1, 0, NULL);
+            INSERT INTO comments VALUES (8, '/repo', 'main', 'b.rs', 1, 1, 'done', 'bob', 1, 1, 5);
+            INSERT INTO replies VALUES (1, 7, 'reply', 'alice', 2);
+            ",
+        )
+        .unwrap();
+
+        let store = SqliteStateStore::from_connection(conn).unwrap();
+        let comments = store.get_comments("/repo", "main").unwrap();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].id, 7);
+        assert_eq!(comments[0].line_range, Some((2, 4)));
+        assert_eq!(comments[0].replies.len(), 1);
+        assert_eq!(comments[0].status, CommentStatus::Open);
+        assert_eq!(comments[1].status, CommentStatus::Resolved);
+        assert_eq!(comments[1].resolved_at, Some(5));
+        assert_eq!(comments[1].updated_at, 5);
+        assert_eq!(comments[0].uuid.len(), 36);
+        assert_ne!(comments[0].uuid, comments[1].uuid);
+        assert_eq!(comments[0].replies[0].uuid.len(), 36);
+    }
+
+    #[test]
+    fn severity_and_labels_round_trip() {
+        let store = store();
+        let mut comment = new_comment(1, None);
+        comment.severity = Some(Severity::Nit);
+        comment.labels = vec!["perf".to_string(), "naming".to_string()];
+        store.add_comment("/repo", "main", comment).unwrap();
+        store.add_comment("/repo", "main", new_comment(2, None)).unwrap();
+
+        let comments = store.get_comments("/repo", "main").unwrap();
+        assert_eq!(comments[0].severity, Some(Severity::Nit));
+        assert_eq!(comments[0].labels, vec!["perf", "naming"]);
+        assert_eq!(comments[1].severity, None);
+        assert!(comments[1].labels.is_empty());
+    }
+
+    #[test]
+    fn status_and_assignee_round_trip() {
+        let store = store();
+        let id = store.add_comment("/repo", "main", new_comment(1, None)).unwrap();
+
+        store.assign_comment(id, Some("agent")).unwrap();
+        store.set_comment_status(id, CommentStatus::InProgress).unwrap();
+        let comment = &store.get_comments("/repo", "main").unwrap()[0];
+        assert_eq!(comment.status, CommentStatus::InProgress);
+        assert_eq!(comment.assignee.as_deref(), Some("agent"));
+        assert_eq!(comment.resolved_at, None);
+
+        store.set_comment_status(id, CommentStatus::WontFix).unwrap();
+        let comment = &store.get_comments("/repo", "main").unwrap()[0];
+        assert_eq!(comment.status, CommentStatus::WontFix);
+        assert!(comment.resolved_at.is_some());
+
+        store.unresolve_comment(id).unwrap();
+        let comment = &store.get_comments("/repo", "main").unwrap()[0];
+        assert_eq!(comment.status, CommentStatus::Open);
+        assert_eq!(comment.resolved_at, None);
+
+        assert!(store.set_comment_status(999, CommentStatus::Resolved).is_err());
+        assert!(store.assign_comment(999, None).is_err());
+    }
+
+    #[test]
+    fn imported_comments_keep_identity_and_deletes_leave_tombstones() {
+        let source = store();
+        let id = source.add_comment("/repo", "main", new_comment(1, None)).unwrap();
+        source
+            .add_reply(NewReply {
+                comment_id: id,
+                body: "ack".to_string(),
+                author: "bob".to_string(),
+                author_kind: AuthorKind::Human,
+                uuid: crate::domain::new_uuid(),
+            })
+            .unwrap();
+        source.set_comment_status(id, CommentStatus::Resolved).unwrap();
+        let original = source.get_comments("/repo", "main").unwrap().remove(0);
+
+        let target = store();
+        let copy_id = target.import_comment("/repo", "main", &original).unwrap();
+        let copy = target.get_comments("/repo", "main").unwrap().remove(0);
+        assert_eq!(copy.uuid, original.uuid);
+        assert_eq!(copy.status, CommentStatus::Resolved);
+        assert_eq!(copy.updated_at, original.updated_at);
+        assert_eq!(copy.replies[0].uuid, original.replies[0].uuid);
+        assert!(target.import_comment("/repo", "main", &original).is_err());
+
+        target.delete_comment(copy_id).unwrap();
+        assert_eq!(target.get_deleted_comments("/repo", "main").unwrap(), vec![original.uuid]);
+        assert!(target.get_deleted_comments("/repo", "other").unwrap().is_empty());
+    }
+
+    #[test]
+    fn changes_to_published_comments_are_logged() {
+        let store = store();
+        let id = store.add_comment("/repo", "main", new_comment(1, None)).unwrap();
+        let reply = store
+            .add_reply(NewReply {
+                comment_id: id,
+                body: "ack".to_string(),
+                author: "bob".to_string(),
+                author_kind: AuthorKind::Human,
+                uuid: crate::domain::new_uuid(),
+            })
+            .unwrap();
+        // Claiming doesn't close the comment, so it isn't logged
+        store.set_comment_status(id, CommentStatus::InProgress).unwrap();
+        store.set_comment_status(id, CommentStatus::WontFix).unwrap();
+        store.unresolve_comment(id).unwrap();
+        store.add_comment("/repo", "other", new_comment(1, None)).unwrap();
+
+        // Drafts appear when their review is submitted
+        let review = store.start_review("/repo", "main", "alice").unwrap();
+        let draft = store.add_comment("/repo", "main", new_comment(2, Some(review))).unwrap();
+        let cursor = store.latest_event_cursor().unwrap();
+        store.submit_review(review, "", ReviewVerdict::Comment).unwrap();
+        store.delete_comment(id).unwrap();
+
+        use CommentEventKind::*;
+        let events = store.get_comment_events("/repo", "main", 0).unwrap();
+        let kinds: Vec<CommentEventKind> = events.iter().map(|e| e.kind).collect();
+        assert_eq!(kinds, vec![CommentAdded, ReplyAdded, Resolved, Unresolved, CommentAdded, Deleted]);
+        assert_eq!(events[1].reply_id, Some(reply));
+        assert_eq!(events[4].comment_id, draft);
+        assert_eq!(events[5].comment_uuid, events[0].comment_uuid);
+        assert!(events[0].created_at > 0);
+
+        let later = store.get_comment_events("/repo", "main", cursor).unwrap();
+        assert_eq!(later, events[4..]);
+        assert_eq!(store.latest_event_cursor().unwrap(), events[5].cursor);
+    }
+
+    #[test]
+    fn failed_transactions_roll_back_only_their_own_writes() {
+        use std::sync::{mpsc, Arc};
+
+        let store = Arc::new(store());
+        let (started, wait) = mpsc::channel();
+        let other = {
+            let store = Arc::clone(&store);
+            std::thread::spawn(move || {
+                wait.recv().unwrap();
+                store.add_comment("/repo", "main", new_comment(2, None)).unwrap();
+            })
+        };
+
+        let result = store.transaction(&mut |tx| {
+            tx.add_comment("/repo", "main", new_comment(1, None))?;
+            // Another caller sharing the store writes while this one is open
+            started.send(()).unwrap();
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            anyhow::bail!("batch failed")
+        });
+        assert!(result.is_err());
+        other.join().unwrap();
+
+        let comments = store.get_comments("/repo", "main").unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].line_range, Some((2, 2)));
+    }
+
+    #[test]
+    fn panicking_transactions_roll_back_and_release_the_store() {
+        let store = store();
+        let panicked = panic::catch_unwind(AssertUnwindSafe(|| {
+            store.transaction(&mut |tx| {
+                tx.add_comment("/repo", "main", new_comment(1, None))?;
+                panic!("operation panicked");
+            })
+        }));
+        assert!(panicked.is_err());
+
+        store.add_comment("/repo", "main", new_comment(2, None)).unwrap();
+        store.transaction(&mut |tx| {
+            tx.add_comment("/repo", "main", new_comment(3, None))?;
+            Ok(true)
+        })
+        .unwrap();
+        let lines: Vec<_> = store.get_comments("/repo", "main").unwrap().iter().map(|c| c.line_range).collect();
+        assert_eq!(lines, vec![Some((2, 2)), Some((3, 3))]);
+    }
 }
@@ -1,16 +1,21 @@
 //! Application state machine.
 
-use crate::domain::{BranchPreview, Comment, Diff, Reply};
+use crate::domain::coverage::{Coverage, DiffCoverage};
+use crate::domain::{
+    new_uuid, parse_comment_prefix, AuthorKind, BranchPreview, Comment, CommentScope, CommentStatus, Diff, Reply, ReviewVerdict,
+    Severity,
+};
 use crate::keymap::{Action, Context, Keymap, build_default_keymap};
 use crate::ports::{
     FileWatcher, GitRepo, KeyCode, KeyModifiers, MouseEvent, NewComment, NewReply, StateStore,
     Terminal, TerminalEvent,
 };
 use crate::search::{self, FuzzySearchState, SearchableEntry};
-use crate::ui::{diff_view, file_tree, layout, theme};
-use anyhow::Result;
+use crate::ui::{comments_panel, diff_view, file_tree, layout, theme};
+use anyhow::{Context as _, Result};
 use ratatui::widgets::ListState;
 use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
@@ -27,6 +32,8 @@ pub enum ViewMode {
     CommentInput,
     /// Fuzzy search through diff content
     FuzzySearch,
+    /// Submit review dialog (summary + verdict)
+    ReviewSubmit,
 }
 
 /// Which pane/input has focus.
@@ -36,6 +43,7 @@ pub enum Focus {
     #[default]
     DiffView,
     FilterInput,
+    CommentsPanel,
 }
 
 /// Source of the diff being displayed.
@@ -50,6 +58,50 @@ pub enum DiffSource {
     All,
 }
 
+/// Which inline comments are shown (cycled by the show/hide comments toggle).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentFilter {
+    #[default]
+    All,
+    /// Hide nits and praise
+    HideNits,
+    /// Only blocking comments
+    BlockingOnly,
+    /// No inline comments
+    Hidden,
+}
+
+impl CommentFilter {
+    pub fn next(self) -> Self {
+        match self {
+            CommentFilter::All => CommentFilter::HideNits,
+            CommentFilter::HideNits => CommentFilter::BlockingOnly,
+            CommentFilter::BlockingOnly => CommentFilter::Hidden,
+            CommentFilter::Hidden => CommentFilter::All,
+        }
+    }
+
+    pub fn matches(self, comment: &Comment) -> bool {
+        match self {
+            CommentFilter::All => true,
+            CommentFilter::HideNits => {
+                !matches!(comment.severity, Some(Severity::Nit | Severity::Praise))
+            }
+            CommentFilter::BlockingOnly => comment.severity == Some(Severity::Blocking),
+            CommentFilter::Hidden => false,
+        }
+    }
+
+    /// Label for the toggle in the diff hints bar.
+    pub fn label(self) -> &'static str {
+        match self {
+            CommentFilter::All | CommentFilter::Hidden => "Comments",
+            CommentFilter::HideNits => "No nits",
+            CommentFilter::BlockingOnly => "Blocking",
+        }
+    }
+}
+
 /// Application state.
 pub struct App {
     pub preview: BranchPreview,
@@ -89,23 +141,50 @@ pub struct App {
     // Set of file paths with uncommitted changes (for orange gutter in All mode)
     pub uncommitted_files: HashSet<String>,
 
+    // Test coverage overlay
+    /// Coverage report and where it was read from (re-read on refresh)
+    coverage: Option<(PathBuf, Coverage)>,
+    /// Coverage of the diff's added lines, when a report is loaded
+    pub diff_coverage: Option<DiffCoverage>,
+
     // ─── Comment/annotation system ───
     /// All comments for the current repo/branch
     pub comments: Vec<Comment>,
-    /// Whether to show comments inline
-    pub show_comments: bool,
+    /// Which comments to show inline
+    pub comment_filter: CommentFilter,
     /// Visual selection anchor (the line where 'V' was pressed, uses cursor position)
     pub visual_anchor: Option<usize>,
     /// Current comment input buffer
     pub comment_input: String,
     /// The file path for the current comment being created
     pub comment_file_path: Option<String>,
+    /// What the comment being created is anchored to
+    pub comment_scope: CommentScope,
+    /// Severity picked for the comment being created (Tab cycles)
+    pub comment_severity: Option<Severity>,
+    /// Whether the branch conversation panel is open
+    pub conversation_open: bool,
+    /// Whether the comments overview panel is shown in the sidebar
+    pub comments_panel_open: bool,
+    /// Filters applied to the comments panel
+    pub comments_panel_filter: comments_panel::PanelFilter,
+    /// Selected row in the comments panel
+    pub comments_panel_selected: usize,
+    pub comments_panel_state: ListState,
     /// Author name for comments (from git config or default)
     pub comment_author: String,
     /// Currently focused comment (when navigating into a comment)
     pub focused_comment: Option<i64>,
     /// Comment ID we're replying to (None = creating new comment)
     pub reply_to_comment_id: Option<i64>,
+    /// Pending review; new comments are drafts until it is submitted
+    pub pending_review: Option<i64>,
+    /// Summary input for the submit review dialog
+    pub review_input: String,
+    /// Verdict selected in the submit review dialog
+    pub review_verdict: ReviewVerdict,
+    /// Error shown in the status bar until the next key press
+    pub status_message: Option<String>,
     /// Last known viewport height (updated during render)
     pub viewport_height: usize,
     /// Keymap for handling key bindings with context-based dispatch
@@ -158,6 +237,16 @@ impl App {
             .collect();
         let diff_lines = diff_view::build_unified_lines(&diff, &collapsed_files);
 
+        // Resume a review left pending by a previous session
+        let comment_author = Self::get_git_author(git);
+        let pending_review = state_store.as_ref().and_then(|store| {
+            store
+                .get_pending_review(&repo_path, &current_branch, &comment_author)
+                .ok()
+                .flatten()
+                .map(|review| review.id)
+        });
+
         Ok(Self {
             preview: BranchPreview {
                 current_branch: current_branch.clone(),
@@ -191,14 +280,27 @@ impl App {
             viewed_timestamps,
             diff_source: DiffSource::Committed,
             uncommitted_files: HashSet::new(),
-            comments: Self::load_comments(&state_store, &repo_path, &current_branch),
-            show_comments: true,
+            coverage: None,
+            diff_coverage: None,
+            comments: Self::load_comments(&state_store, &repo_path, &current_branch, pending_review),
+            comment_filter: CommentFilter::All,
             visual_anchor: None,
             comment_input: String::new(),
             comment_file_path: None,
-            comment_author: Self::get_git_author(git),
+            comment_scope: CommentScope::Lines,
+            comment_severity: None,
+            conversation_open: false,
+            comments_panel_open: false,
+            comments_panel_filter: comments_panel::PanelFilter::default(),
+            comments_panel_selected: 0,
+            comments_panel_state: ListState::default(),
+            comment_author,
             focused_comment: None,
             reply_to_comment_id: None,
+            pending_review,
+            review_input: String::new(),
+            review_verdict: ReviewVerdict::Comment,
+            status_message: None,
             viewport_height: 30, // Default, updated during render
             keymap: build_default_keymap(),
             theme_picker_items: Vec::new(),
@@ -208,16 +310,28 @@ impl App {
         })
     }
 
-    /// Load comments from state store.
+    /// Overlay a coverage report, read from `path`, on the diff's added lines.
+    pub fn with_coverage(mut self, path: PathBuf, coverage: Coverage) -> Self {
+        self.diff_coverage = Some(DiffCoverage::new(&coverage, &self.diff));
+        self.coverage = Some((path, coverage));
+        self
+    }
+
+    /// Load comments from state store, including drafts of our pending review.
     fn load_comments(
         state_store: &Option<Arc<dyn StateStore>>,
         repo_path: &str,
         branch: &str,
+        pending_review: Option<i64>,
     ) -> Vec<Comment> {
-        state_store
-            .as_ref()
-            .and_then(|store| store.get_comments(repo_path, branch).ok())
-            .unwrap_or_default()
+        let Some(store) = state_store else {
+            return Vec::new();
+        };
+        let mut comments = store.get_comments(repo_path, branch).unwrap_or_default();
+        if let Some(review_id) = pending_review {
+            comments.extend(store.get_review_comments(review_id).unwrap_or_default());
+        }
+        comments
     }
 
     /// Get git author name for comments.
@@ -287,10 +401,13 @@ impl App {
 
     /// Refresh comments from the database (for live updates from CLI/agents).
     fn refresh_comments(&mut self) {
-        if let Some(ref store) = self.state_store {
-            if let Ok(comments) = store.get_comments(&self.repo_path, &self.branch) {
-                self.comments = comments;
-            }
+        if self.state_store.is_some() {
+            self.comments = Self::load_comments(
+                &self.state_store,
+                &self.repo_path,
+                &self.branch,
+                self.pending_review,
+            );
         }
     }
 
@@ -338,6 +455,14 @@ impl App {
         let (viewed_files, viewed_timestamps) =
             Self::load_viewed_state(&self.state_store, &self.repo_path, &self.branch, &diff);
 
+        // Pick up a regenerated coverage report, keeping the last one if it can't be read
+        if let Some((path, coverage)) = &mut self.coverage {
+            if let Ok(reloaded) = load_coverage(path, git) {
+                *coverage = reloaded;
+            }
+            self.diff_coverage = Some(DiffCoverage::new(coverage, &diff));
+        }
+
         self.preview.merge_base = merge_base;
         self.preview.commits = commits;
         self.diff = diff;
@@ -412,21 +537,62 @@ impl App {
         let has_pending_changes = self.has_pending_changes;
         let diff_source = self.diff_source;
         let uncommitted_files = &self.uncommitted_files;
+        let coverage = self.diff_coverage.as_ref();
         let stale_viewed = &self.stale_viewed_files;
-        let comments = &self.comments;
-        let show_comments = self.show_comments;
+        let comment_filter = self.comment_filter;
+        let filtered_comments: Vec<Comment>;
+        let comments: &[Comment] = if comment_filter == CommentFilter::All {
+            &self.comments
+        } else {
+            filtered_comments = self
+                .comments
+                .iter()
+                // Drafts and branch-level comments stay visible while being worked on
+                .filter(|c| comment_filter.matches(c) || c.draft || c.file_path.is_none())
+                .cloned()
+                .collect();
+            &filtered_comments
+        };
         let focused_comment = self.focused_comment;
         let focus = self.focus;
 
-        // Build draft comment for inline rendering during comment input mode (new comments only)
-        let draft_comment = if mode == ViewMode::CommentInput && self.reply_to_comment_id.is_none() {
-            visual_selection.map(|(start, end)| {
-                let file_path = self.comment_file_path.clone().unwrap_or_default();
-                (file_path, start, end, self.comment_input.clone())
-            })
+        let panel_items = if self.comments_panel_open {
+            comments_panel::build_items(&self.comments, &self.comments_panel_filter)
         } else {
-            None
+            Vec::new()
+        };
+        let comments_panel = self.comments_panel_open.then(|| comments_panel::PanelView {
+            comments: &self.comments,
+            items: &panel_items,
+            filter: &self.comments_panel_filter,
+            selected: self.comments_panel_selected.min(panel_items.len().saturating_sub(1)),
+            list_state: &mut self.comments_panel_state,
+        });
+
+        // Build draft comment for inline rendering during comment input mode (new comments only)
+        let composing = mode == ViewMode::CommentInput && self.reply_to_comment_id.is_none();
+        let draft_comment = match self.comment_scope {
+            _ if !composing => None,
+            CommentScope::Lines => visual_selection.map(|range| diff_view::DraftComment {
+                file_path: self.comment_file_path.clone().unwrap_or_default(),
+                line_range: Some(range),
+                body: self.comment_input.clone(),
+                severity: self.comment_severity,
+            }),
+            CommentScope::File => self.comment_file_path.clone().map(|file_path| {
+                diff_view::DraftComment {
+                    file_path,
+                    line_range: None,
+                    body: self.comment_input.clone(),
+                    severity: self.comment_severity,
+                }
+            }),
+            // Branch-level drafts are shown in the conversation panel
+            CommentScope::Branch => None,
         };
+        let conversation_draft = (composing && self.comment_scope == CommentScope::Branch)
+            .then(|| (self.comment_input.clone(), self.comment_severity));
+        let conversation_open = self.conversation_open;
 
         // Build reply info for inline rendering during reply input mode
         let reply_to_id = self.reply_to_comment_id;
@@ -435,6 +601,9 @@ impl App {
         // Fuzzy search state for overlay
         let fuzzy_search = &self.fuzzy_search;
 
+        let pending_review_drafts = self.pending_review.map(|_| comments.iter().filter(|c| c.draft).count());
+        let status_message = self.status_message.as_deref();
+
         terminal.draw(|frame| {
             let area = frame.area();
 
@@ -471,12 +640,16 @@ impl App {
                     has_pending_changes,
                     diff_source,
                     uncommitted_files,
+                    coverage,
                     comments,
-                    show_comments,
+                    comment_filter,
                     visual_selection,
                     focused_comment,
                     draft_comment.as_ref(),
                     reply_info,
+                    pending_review_drafts,
+                    comments_panel,
+                    status_message,
                     focus,
                     mode,
                 );
@@ -494,6 +667,25 @@ impl App {
                     self.theme_picker_index,
                 );
             }
+            if conversation_open {
+                layout::render_conversation(
+                    frame,
+                    area,
+                    comments,
+                    focused_comment,
+                    conversation_draft.as_ref().map(|(body, severity)| (body.as_str(), *severity)),
+                    reply_info,
+                );
+            }
+            if mode == ViewMode::ReviewSubmit {
+                layout::render_review_dialog(
+                    frame,
+                    area,
+                    &self.review_input,
+                    self.review_verdict,
+                    pending_review_drafts.unwrap_or(0),
+                );
+            }
             if mode == ViewMode::FuzzySearch {
                 if let Some(state) = fuzzy_search {
                     layout::render_fuzzy_search(frame, area, state, sidebar_collapsed);
@@ -522,7 +714,7 @@ impl App {
         let base_vh = self.viewport_height.max(5);
 
         // Account for comment heights when comments are visible
-        let comment_lines = if self.show_comments {
+        let comment_lines = if self.show_comments() {
             self.estimate_comment_lines_in_range(self.scroll, self.scroll + base_vh)
         } else {
             0
@@ -558,6 +750,12 @@ impl App {
             Focus::FileTree => contexts.push(Context::FileTree),
             Focus::DiffView => contexts.push(Context::DiffView),
             Focus::FilterInput => contexts.push(Context::FilterInput),
+            Focus::CommentsPanel => contexts.push(Context::CommentsPanel),
+        }
+
+        // The conversation panel takes over navigation while it's open
+        if self.conversation_open && self.mode == ViewMode::Normal {
+            contexts.push(Context::Conversation);
         }
 
         // Add mode-based contexts (more specific than focus)
@@ -567,6 +765,7 @@ impl App {
             ViewMode::Visual => contexts.push(Context::Visual),
             ViewMode::CommentInput => contexts.push(Context::CommentInput),
             ViewMode::FuzzySearch => contexts.push(Context::FuzzySearch),
+            ViewMode::ReviewSubmit => contexts.push(Context::ReviewSubmit),
             ViewMode::Normal => {}
         }
 
@@ -579,6 +778,7 @@ impl App {
     }
 
     fn handle_key(&mut self, code: KeyCode, modifiers: KeyModifiers, git: &dyn GitRepo) -> Result<()> {
+        self.status_message = None;
         // Handle character input specially for text input modes
         if let KeyCode::Char(c) = code {
             if !modifiers.ctrl {
@@ -594,6 +794,9 @@ impl App {
                 } else if self.mode == ViewMode::CommentInput {
                     self.comment_input.push(c);
                     return Ok(());
+                } else if self.mode == ViewMode::ReviewSubmit {
+                    self.review_input.push(c);
+                    return Ok(());
                 } else if self.focus == Focus::FilterInput {
                     self.filter.push(c);
                     self.rebuild_flat_items();
@@ -621,10 +824,8 @@ impl App {
         if self.mode == ViewMode::ThemePicker {
             let len = self.theme_picker_items.len();
             match action {
-                Action::MoveDown => {
-                    if self.theme_picker_index + 1 < len {
-                        self.theme_picker_index += 1;
-                    }
+                Action::MoveDown if self.theme_picker_index + 1 < len => {
+                    self.theme_picker_index += 1;
                 }
                 Action::MoveUp => {
                     self.theme_picker_index = self.theme_picker_index.saturating_sub(1);
@@ -632,10 +833,8 @@ impl App {
                 Action::GotoTop => {
                     self.theme_picker_index = 0;
                 }
-                Action::GotoBottom => {
-                    if len > 0 {
-                        self.theme_picker_index = len - 1;
-                    }
+                Action::GotoBottom if len > 0 => {
+                    self.theme_picker_index = len - 1;
                 }
                 Action::ApplyTheme => {
                     self.apply_theme_selection();
@@ -648,6 +847,50 @@ impl App {
             return Ok(());
         }
 
+        if self.mode == ViewMode::ReviewSubmit {
+            match action {
+                Action::SubmitInput => self.submit_review(),
+                Action::CancelInput => self.mode = ViewMode::Normal,
+                Action::InputBackspace => {
+                    self.review_input.pop();
+                }
+                Action::CycleVerdict => self.review_verdict = self.review_verdict.next(),
+                Action::DiscardReview => self.discard_review(),
+                _ => {}
+            }
+            return Ok(());
+        }
+
+        if self.conversation_open && self.mode == ViewMode::Normal {
+            let handled = match action {
+                Action::MoveDown => {
+                    self.move_conversation_focus(true);
+                    true
+                }
+                Action::MoveUp => {
+                    self.move_conversation_focus(false);
+                    true
+                }
+                Action::StartComment => {
+                    self.start_comment(CommentScope::Branch);
+                    true
+                }
+                Action::ToggleConversation => {
+                    self.toggle_conversation();
+                    true
+                }
+                // Thread actions on the focused comment fall through to the usual handling
+                Action::ReplyToComment | Action::ToggleResolved | Action::DeleteComment => {
+                    self.focused_comment.is_none()
+                }
+                Action::Quit | Action::StartReview => false,
+                _ => true,
+            };
+            if handled {
+                return Ok(());
+            }
+        }
+
         if self.mode == ViewMode::FuzzySearch {
             // Search drawer is 10 rows: 1 border + 1 input + results + 1 hints
             // So results area is about 7 rows
@@ -696,6 +939,7 @@ impl App {
                             self.selected_tree_item += 1;
                         }
                     }
+                    Focus::CommentsPanel => self.move_panel_selection(true),
                     Focus::DiffView => self.move_cursor_down(max_line),
                     _ => {}
                 }
@@ -705,6 +949,7 @@ impl App {
                     Focus::FileTree => {
                         self.selected_tree_item = self.selected_tree_item.saturating_sub(1);
                     }
+                    Focus::CommentsPanel => self.move_panel_selection(false),
                     Focus::DiffView => self.move_cursor_up(),
                     _ => {}
                 }
@@ -732,6 +977,7 @@ impl App {
             Action::GotoTop => {
                 match self.focus {
                     Focus::FileTree => self.selected_tree_item = 0,
+                    Focus::CommentsPanel => self.comments_panel_selected = 0,
                     Focus::DiffView => {
                         self.focused_comment = None;
                         self.cursor = 0;
@@ -746,6 +992,9 @@ impl App {
                     Focus::FileTree => {
                         self.selected_tree_item = self.flat_items.len().saturating_sub(1);
                     }
+                    Focus::CommentsPanel => {
+                        self.comments_panel_selected = self.panel_items().len().saturating_sub(1);
+                    }
                     Focus::DiffView => {
                         self.focused_comment = None;
                         self.cursor = max_line;
@@ -780,8 +1029,9 @@ impl App {
             Action::SwitchPane => {
                 self.focus = match self.focus {
                     Focus::FileTree => Focus::DiffView,
+                    Focus::DiffView if self.comments_panel_open => Focus::CommentsPanel,
                     Focus::DiffView => Focus::FileTree,
-                    Focus::FilterInput => Focus::FileTree,
+                    Focus::FilterInput | Focus::CommentsPanel => Focus::FileTree,
                 };
             }
             Action::FocusFileTree => {
@@ -797,6 +1047,11 @@ impl App {
             Action::FocusFilter => {
                 self.focus = Focus::FilterInput;
             }
+            Action::FocusCommentsPanel => {
+                self.sidebar_collapsed = false;
+                self.comments_panel_open = true;
+                self.focus = Focus::CommentsPanel;
+            }
 
             // === View toggles ===
             Action::ToggleSplitView => {
@@ -806,6 +1061,15 @@ impl App {
                 };
                 self.rebuild_diff_lines();
             }
+            Action::ToggleCommentsPanel => {
+                self.comments_panel_open = !self.comments_panel_open;
+                if self.comments_panel_open {
+                    self.sidebar_collapsed = false;
+                    self.focus = Focus::CommentsPanel;
+                } else if self.focus == Focus::CommentsPanel {
+                    self.focus = Focus::DiffView;
+                }
+            }
             Action::ToggleSidebar => {
                 self.sidebar_collapsed = !self.sidebar_collapsed;
                 // Auto-focus diff view when hiding the sidebar
@@ -814,7 +1078,10 @@ impl App {
                 }
             }
             Action::ToggleComments => {
-                self.show_comments = !self.show_comments;
+                self.comment_filter = self.comment_filter.next();
+                if self.focused_comment.is_some() && !self.conversation_open {
+                    self.focused_comment = None;
+                }
             }
             Action::CycleDiffSource => {
                 self.diff_source = match self.diff_source {
@@ -904,8 +1171,18 @@ impl App {
                 self.exit_visual_mode();
             }
             Action::StartComment => {
-                self.mode = ViewMode::CommentInput;
-                self.comment_input.clear();
+                self.start_comment(CommentScope::Lines);
+            }
+            Action::StartFileComment => {
+                if let Some(file) = self.diff_lines.get(self.cursor)
+                    .and_then(|line| self.diff.files.get(line.file_index))
+                {
+                    self.comment_file_path = Some(file.path.clone());
+                    self.start_comment(CommentScope::File);
+                }
+            }
+            Action::ToggleConversation => {
+                self.toggle_conversation();
             }
             Action::ReplyToComment => {
                 if let Some(comment_id) = self.focused_comment {
@@ -913,7 +1190,11 @@ impl App {
                 }
             }
             Action::ToggleResolved => {
-                if let Some(comment_id) = self.focused_comment {
+                if self.focus == Focus::CommentsPanel {
+                    if let Some(comment_id) = self.selected_panel_comment() {
+                        self.toggle_comment_resolved_by_id(comment_id);
+                    }
+                } else if let Some(comment_id) = self.focused_comment {
                     self.toggle_comment_resolved_by_id(comment_id);
                 } else {
                     self.toggle_comment_resolved();
@@ -924,6 +1205,41 @@ impl App {
                     self.delete_comment(comment_id);
                 }
             }
+            Action::NextOpenComment => self.jump_to_open_comment(true),
+            Action::NextUncoveredLine => self.jump_to_uncovered_line(),
+            Action::PrevOpenComment => self.jump_to_open_comment(false),
+
+            // === Comments panel ===
+            Action::JumpToComment => match self.panel_items().get(self.comments_panel_selected) {
+                Some(comments_panel::PanelItem::Comment(id)) => {
+                    let id = *id;
+                    self.jump_to_comment(id);
+                }
+                Some(comments_panel::PanelItem::Group(Some(path))) => {
+                    if let Some(file_idx) = self.diff.files.iter().position(|f| &f.path == path) {
+                        self.jump_to_file(file_idx);
+                    }
+                }
+                _ => {}
+            },
+            Action::CyclePanelStatus => {
+                self.comments_panel_filter.status = self.comments_panel_filter.status.next();
+                self.comments_panel_selected = 0;
+            }
+            Action::CyclePanelAuthor => {
+                self.comments_panel_filter.cycle_author(&self.comments);
+                self.comments_panel_selected = 0;
+            }
+
+            Action::StartReview => {
+                self.start_or_open_review();
+            }
+            Action::CycleVerdict | Action::DiscardReview => {}
+            Action::CycleSeverity => {
+                if self.mode == ViewMode::CommentInput && self.reply_to_comment_id.is_none() {
+                    self.comment_severity = Severity::cycle(self.comment_severity);
+                }
+            }
 
             // === Input handling ===
             Action::SubmitInput => {
@@ -940,6 +1256,8 @@ impl App {
                         self.exit_visual_mode();
                         self.comment_input.clear();
                         self.reply_to_comment_id = None;
+                        self.comment_scope = CommentScope::Lines;
+                        self.comment_severity = None;
                     }
                     _ => {
                         // Filter input submit
@@ -953,6 +1271,8 @@ impl App {
                         self.mode = ViewMode::Normal;
                         self.comment_input.clear();
                         self.reply_to_comment_id = None;
+                        self.comment_scope = CommentScope::Lines;
+                        self.comment_severity = None;
                         self.exit_visual_mode();
                     }
                     _ => {
@@ -1117,13 +1437,17 @@ impl App {
         })
     }
 
-    fn submit_comment(&mut self) {
-        let Some((start_idx, end_idx)) = self.visual_selection() else {
-            return;
-        };
-        let Some(file_path) = self.comment_file_path.clone() else {
-            return;
-        };
+    /// Enter comment input for a new comment anchored to `scope`.
+    fn start_comment(&mut self, scope: CommentScope) {
+        self.comment_scope = scope;
+        self.comment_severity = None;
+        self.mode = ViewMode::CommentInput;
+        self.comment_input.clear();
+    }
+
+    /// Source line range (in the NEW file) of the visual selection.
+    fn selected_source_range(&self) -> Option<(usize, usize)> {
+        let (start_idx, end_idx) = self.visual_selection()?;
 
         // Extract actual source line numbers from the diff lines
         // Look for new_num (right side) as that's what we're commenting on
@@ -1147,12 +1471,46 @@ impl App {
             .map(|n| n as usize)
             .unwrap_or(end_idx);
 
+        Some((start_line, end_line))
+    }
+
+    fn submit_comment(&mut self) {
+        let (file_path, line_range) = match self.comment_scope {
+            CommentScope::Branch => (None, None),
+            CommentScope::File => {
+                let Some(file_path) = self.comment_file_path.clone() else {
+                    return;
+                };
+                (Some(file_path), None)
+            }
+            CommentScope::Lines => {
+                let Some(range) = self.selected_source_range() else {
+                    return;
+                };
+                let Some(file_path) = self.comment_file_path.clone() else {
+                    return;
+                };
+                (Some(file_path), Some(range))
+            }
+        };
+
+        // A Conventional Comments prefix ("nit (perf): ...") sets severity and labels
+        let (prefix_severity, labels, body) = parse_comment_prefix(self.comment_input.trim());
+        let anchor = file_path.as_deref().zip(line_range).and_then(|(path, (start, end))| {
+            let file = self.diff.files.iter().find(|f| f.path == path)?;
+            file.new_lines_text(start, end)
+        });
         let new_comment = NewComment {
             file_path,
-            start_line,
-            end_line,
-            body: self.comment_input.trim().to_string(),
+            line_range,
+            anchor,
+            body: body.to_string(),
             author: self.comment_author.clone(),
+            author_kind: AuthorKind::Human,
+            severity: self.comment_severity.or(prefix_severity),
+            labels,
+            review_id: self.pending_review,
+            uuid: new_uuid(),
         };
 
         // Save to state store
@@ -1166,20 +1524,258 @@ impl App {
 
                 self.comments.push(Comment {
                     id,
+                    uuid: new_comment.uuid,
                     file_path: new_comment.file_path,
-                    start_line: new_comment.start_line,
-                    end_line: new_comment.end_line,
+                    line_range: new_comment.line_range,
+                    anchor: new_comment.anchor,
                     body: new_comment.body,
                     author: new_comment.author,
+                    author_kind: new_comment.author_kind,
                     created_at: now,
-                    resolved: false,
+                    status: CommentStatus::Open,
+                    assignee: None,
                     resolved_at: None,
+                    updated_at: now,
+                    severity: new_comment.severity,
+                    labels: new_comment.labels,
+                    review_id: new_comment.review_id,
+                    draft: new_comment.review_id.is_some(),
                     replies: vec![],
                 });
             }
         }
     }
 
+    // ─── Branch conversation ───
+
+    fn toggle_conversation(&mut self) {
+        self.conversation_open = !self.conversation_open;
+        self.focused_comment = if self.conversation_open {
+            self.conversation_ids().first().copied()
+        } else {
+            None
+        };
+    }
+
+    /// IDs of branch-level comments, in display order.
+    fn conversation_ids(&self) -> Vec<i64> {
+        self.comments
+            .iter()
+            .filter(|c| c.scope() == CommentScope::Branch)
+            .map(|c| c.id)
+            .collect()
+    }
+
+    /// Move focus to the next (or previous) comment in the conversation panel.
+    fn move_conversation_focus(&mut self, forward: bool) {
+        let ids = self.conversation_ids();
+        let current = self.focused_comment.and_then(|id| ids.iter().position(|&i| i == id));
+        let next = match current {
+            Some(i) if forward => (i + 1).min(ids.len().saturating_sub(1)),
+            Some(i) => i.saturating_sub(1),
+            None => 0,
+        };
+        self.focused_comment = ids.get(next).copied();
+    }
+
+    // ─── Comments panel and comment navigation ───
+
+    fn panel_items(&self) -> Vec<comments_panel::PanelItem> {
+        comments_panel::build_items(&self.comments, &self.comments_panel_filter)
+    }
+
+    /// The comment selected in the comments panel, if the selection is on one.
+    fn selected_panel_comment(&self) -> Option<i64> {
+        match self.panel_items().get(self.comments_panel_selected) {
+            Some(comments_panel::PanelItem::Comment(id)) => Some(*id),
+            _ => None,
+        }
+    }
+
+    fn move_panel_selection(&mut self, forward: bool) {
+        let len = self.panel_items().len();
+        self.comments_panel_selected = if forward {
+            (self.comments_panel_selected + 1).min(len.saturating_sub(1))
+        } else {
+            self.comments_panel_selected.saturating_sub(1).min(len.saturating_sub(1))
+        };
+    }
+
+    /// Expand a file if needed and put the cursor on its header.
+    fn jump_to_file(&mut self, file_idx: usize) {
+        if self.collapsed_files.remove(&file_idx) {
+            self.rebuild_diff_lines();
+        }
+        self.focused_comment = None;
+        self.cursor = diff_view::find_file_start(&self.diff_lines, file_idx);
+        self.scroll = self.cursor;
+        self.current_file_index = file_idx;
+        self.sync_tree_selection();
+        self.focus = Focus::DiffView;
+    }
+
+    /// Scroll the diff to a comment and focus it, expanding its file if collapsed.
+    /// Branch-level comments open the conversation panel instead.
+    fn jump_to_comment(&mut self, comment_id: i64) {
+        let Some(comment) = self.comments.iter().find(|c| c.id == comment_id) else {
+            return;
+        };
+        let Some(path) = comment.file_path.clone() else {
+            self.conversation_open = true;
+            self.focused_comment = Some(comment_id);
+            return;
+        };
+        if !self.comment_filter.matches(comment) && !comment.draft {
+            self.comment_filter = CommentFilter::All;
+        }
+        let Some(file_idx) = self.diff.files.iter().position(|f| f.path == path) else {
+            return;
+        };
+
+        self.jump_to_file(file_idx);
+        // The comment renders below its anchor line; if the lines are no longer
+        // in the diff, stay on the file header
+        if let Some(idx) = (0..self.diff_lines.len())
+            .find(|&idx| self.comments_below_line(idx).any(|c| c.id == comment_id))
+        {
+            self.cursor = idx;
+            self.scroll = idx.saturating_sub(self.viewport_height / 3);
+            self.focused_comment = Some(comment_id);
+        }
+        self.sync_from_cursor();
+    }
+
+    /// Open (not resolved or won't fix) file and line comments in diff order:
+    /// (file index, anchor line, comment id). File-level comments anchor at 0.
+    fn open_comment_positions(&self) -> Vec<(usize, usize, i64)> {
+        let mut positions: Vec<_> = self
+            .comments
+            .iter()
+            .filter(|c| !c.status.is_closed())
+            .filter_map(|c| {
+                let path = c.file_path.as_deref()?;
+                let file_idx = self.diff.files.iter().position(|f| f.path == path)?;
+                let anchor = c.line_range.map_or(0, |(_, end)| end);
+                Some((file_idx, anchor, c.id))
+            })
+            .collect();
+        positions.sort_unstable();
+        positions
+    }
+
+    /// Jump to the next (or previous) open comment relative to the cursor.
+    fn jump_to_open_comment(&mut self, forward: bool) {
+        let positions = self.open_comment_positions();
+        let focused = self
+            .focused_comment
+            .and_then(|id| positions.iter().position(|&(_, _, c)| c == id));
+
+        let target = match focused {
+            Some(i) if forward => positions.get(i + 1),
+            Some(i) => i.checked_sub(1).and_then(|i| positions.get(i)),
+            None => {
+                let cursor = self.diff_lines.get(self.cursor).map(|l| {
+                    (l.file_index, l.content.new_line_num().map_or(0, |n| n as usize))
+                });
+                let Some(cursor) = cursor else { return };
+                if forward {
+                    positions.iter().find(|&&(f, line, _)| (f, line) >= cursor)
+                } else {
+                    positions.iter().rev().find(|&&(f, line, _)| (f, line) < cursor)
+                }
+            }
+        };
+        if let Some(&(_, _, id)) = target {
+            self.jump_to_comment(id);
+        }
+    }
+
+    /// Move the cursor to the next added line the coverage report marks as
+    /// uncovered, expanding its file if collapsed.
+    fn jump_to_uncovered_line(&mut self) {
+        let Some(ref coverage) = self.diff_coverage else { return };
+        let cursor = self.diff_lines.get(self.cursor).map(|l| {
+            (l.file_index, l.content.new_line_num().map_or(0, |n| n as usize))
+        });
+        let Some(cursor) = cursor else { return };
+        let Some((file_idx, line)) = coverage.uncovered_lines().into_iter().find(|&pos| pos > cursor) else {
+            return;
+        };
+
+        if self.collapsed_files.remove(&file_idx) {
+            self.rebuild_diff_lines();
+        }
+        if let Some(idx) = self.diff_lines.iter().position(|l| {
+            l.file_index == file_idx && l.content.new_line_num() == Some(line as u32)
+        }) {
+            self.focused_comment = None;
+            self.focus = Focus::DiffView;
+            self.cursor = idx;
+            self.scroll = idx.saturating_sub(self.viewport_height / 3);
+            self.sync_from_cursor();
+        }
+    }
+
+    // ─── Pending reviews ───
+
+    /// Start a pending review, or open the submit dialog if one is already pending.
+    fn start_or_open_review(&mut self) {
+        if self.pending_review.is_some() {
+            self.review_input.clear();
+            self.review_verdict = ReviewVerdict::Comment;
+            self.mode = ViewMode::ReviewSubmit;
+            return;
+        }
+        if let Some(ref store) = self.state_store {
+            match store.start_review(&self.repo_path, &self.branch, &self.comment_author) {
+                Ok(id) => self.pending_review = Some(id),
+                Err(e) => self.status_message = Some(format!("Could not start a review: {}", e)),
+            }
+        }
+    }
+
+    /// Publish the pending review with the dialog's summary and verdict.
+    fn submit_review(&mut self) {
+        let Some(review_id) = self.pending_review else {
+            self.mode = ViewMode::Normal;
+            return;
+        };
+        if let Some(ref store) = self.state_store {
+            // The dialog stays open so the summary isn't lost
+            if let Err(e) = store.submit_review(review_id, self.review_input.trim(), self.review_verdict) {
+                self.status_message = Some(format!("Could not submit the review: {}", e));
+                return;
+            }
+        }
+        for comment in self.comments.iter_mut().filter(|c| c.review_id == Some(review_id)) {
+            comment.draft = false;
+        }
+        self.pending_review = None;
+        self.review_input.clear();
+        self.mode = ViewMode::Normal;
+    }
+
+    /// Throw away the pending review and all of its draft comments.
+    fn discard_review(&mut self) {
+        let Some(review_id) = self.pending_review else {
+            self.mode = ViewMode::Normal;
+            return;
+        };
+        if let Some(ref store) = self.state_store {
+            if let Err(e) = store.discard_review(review_id) {
+                self.status_message = Some(format!("Could not discard the review: {}", e));
+                return;
+            }
+        }
+        self.pending_review = None;
+        self.comments.retain(|c| c.review_id != Some(review_id));
+        if self.focused_comment.is_some_and(|id| !self.comments.iter().any(|c| c.id == id)) {
+            self.focused_comment = None;
+        }
+        self.review_input.clear();
+        self.mode = ViewMode::Normal;
+    }
+
     /// Start replying to a comment.
     fn start_reply(&mut self, comment_id: i64) {
         self.reply_to_comment_id = Some(comment_id);
@@ -1200,10 +1796,13 @@ impl App {
 
         // Save to state store
         if let Some(ref store) = self.state_store {
+            let uuid = new_uuid();
             if let Ok(reply_id) = store.add_reply(NewReply {
                 comment_id,
                 body: body.clone(),
                 author: self.comment_author.clone(),
+                author_kind: AuthorKind::Human,
+                uuid: uuid.clone(),
             }) {
                 // Add to local comments list
                 let now = std::time::SystemTime::now()
@@ -1214,9 +1813,11 @@ impl App {
                 if let Some(comment) = self.comments.iter_mut().find(|c| c.id == comment_id) {
                     comment.replies.push(Reply {
                         id: reply_id,
+                        uuid,
                         comment_id,
                         body,
                         author: self.comment_author.clone(),
+                        author_kind: AuthorKind::Human,
                         created_at: now,
                     });
                 }
@@ -1239,16 +1840,14 @@ impl App {
         let Some(line_num) = source_line else { return };
         let Some(path) = file_path else { return };
 
-        if let Some(comment) = self.comments.iter_mut().find(|c| {
-            c.file_path == path && line_num >= c.start_line && line_num <= c.end_line
-        }) {
-            comment.resolved = !comment.resolved;
+        if let Some(comment) = self.comments.iter_mut().find(|c| c.covers_line(path, line_num)) {
+            comment.status = if comment.status.is_closed() {
+                CommentStatus::Open
+            } else {
+                CommentStatus::Resolved
+            };
             if let Some(ref store) = self.state_store {
-                if comment.resolved {
-                    let _ = store.resolve_comment(comment.id);
-                } else {
-                    let _ = store.unresolve_comment(comment.id);
-                }
+                let _ = store.set_comment_status(comment.id, comment.status);
             }
         }
     }
@@ -1296,7 +1895,7 @@ impl App {
             if self.cursor < max_line {
                 self.cursor += 1;
             }
-        } else if self.show_comments {
+        } else if self.show_comments() {
             // Check if there's a comment ending at current cursor that we should enter
             if let Some(comment) = self.find_comment_at_cursor_end() {
                 self.focused_comment = Some(comment.id);
@@ -1317,7 +1916,7 @@ impl App {
         } else if self.cursor > 0 {
             self.cursor -= 1;
             // Check if we should enter a comment above (at previous line's end)
-            if self.show_comments {
+            if self.show_comments() {
                 if let Some(comment) = self.find_comment_at_cursor_end() {
                     self.focused_comment = Some(comment.id);
                 }
@@ -1326,14 +1925,37 @@ impl App {
         self.sync_from_cursor();
     }
 
-    /// Find a comment that ends at the current cursor position (using source line numbers).
+    /// Whether inline comments are shown at all.
+    pub fn show_comments(&self) -> bool {
+        self.comment_filter != CommentFilter::Hidden
+    }
+
+    /// Find a comment rendered right below the current cursor position.
     fn find_comment_at_cursor_end(&self) -> Option<&Comment> {
-        let diff_line = self.diff_lines.get(self.cursor)?;
-        let source_line = diff_line.content.new_line_num()? as usize;
-        let file_path = self.diff.files.get(diff_line.file_index)?.path.as_str();
+        self.comments_below_line(self.cursor).next()
+    }
+
+    /// Comments rendered below a diff line: whole-file comments under the file
+    /// header, line comments under the last line of their range.
+    fn comments_below_line(&self, idx: usize) -> impl Iterator<Item = &Comment> {
+        let diff_line = self.diff_lines.get(idx);
+        let path = diff_line
+            .and_then(|l| self.diff.files.get(l.file_index))
+            .map(|f| f.path.as_str());
+        let is_header = diff_line
+            .is_some_and(|l| matches!(l.content, diff_view::LineContent::FileHeaderTop { .. }));
+        let source_line = diff_line
+            .and_then(|l| l.content.new_line_num())
+            .map(|n| n as usize);
 
-        self.comments.iter().find(|c| {
-            c.file_path == file_path && c.end_line == source_line
+        let filter = self.comment_filter;
+        self.comments.iter().filter(move |c| {
+            let anchored = match (path, source_line) {
+                (Some(path), _) if is_header => c.is_on_file(path),
+                (Some(path), Some(line)) => c.ends_on_line(path, line),
+                _ => false,
+            };
+            anchored && (filter.matches(c) || c.draft)
         })
     }
 
@@ -1353,13 +1975,13 @@ impl App {
     /// Toggle resolved by comment ID.
     fn toggle_comment_resolved_by_id(&mut self, comment_id: i64) {
         if let Some(comment) = self.comments.iter_mut().find(|c| c.id == comment_id) {
-            comment.resolved = !comment.resolved;
+            comment.status = if comment.status.is_closed() {
+                CommentStatus::Open
+            } else {
+                CommentStatus::Resolved
+            };
             if let Some(ref store) = self.state_store {
-                if comment.resolved {
-                    let _ = store.resolve_comment(comment.id);
-                } else {
-                    let _ = store.unresolve_comment(comment.id);
-                }
+                let _ = store.set_comment_status(comment.id, comment.status);
             }
         }
     }
@@ -1423,7 +2045,7 @@ impl App {
         // When comments are shown, we need to account for comment box heights
         // between scroll and cursor. Comments take up rendered space that reduces
         // how many diff lines actually fit in the viewport.
-        let comment_lines = if self.show_comments {
+        let comment_lines = if self.show_comments() {
             self.estimate_comment_lines_in_range(self.scroll, self.cursor)
         } else {
             0
@@ -1432,7 +2054,7 @@ impl App {
         // If a comment is focused, we need extra space to display the comment box
         // The comment box is rendered after the cursor line, so we need to reserve
         // space for it below the cursor
-        let focused_comment_lines = if self.show_comments {
+        let focused_comment_lines = if self.show_comments() {
             self.focused_comment
                 .and_then(|id| self.comments.iter().find(|c| c.id == id))
                 .map(|c| self.estimate_comment_height(c))
@@ -1481,27 +2103,10 @@ impl App {
         }
 
         let mut total_lines = 0;
-
-        // Iterate through diff lines in range to find comments that end there
+        // Iterate through diff lines in range to find comments rendered below them
         for idx in start_idx..=end_idx {
-            if let Some(diff_line) = self.diff_lines.get(idx) {
-                let source_line = diff_line.content.new_line_num().map(|n| n as usize);
-                let file_path = self.diff.files.get(diff_line.file_index).map(|f| f.path.as_str());
-
-                if let (Some(path), Some(line_num)) = (file_path, source_line) {
-                    for comment in &self.comments {
-                        // Only count comments that END at this line (that's when they're rendered)
-                        if comment.file_path == path && comment.end_line == line_num {
-                            // Estimate comment box height:
-                            // 7 base lines (header, author, empty, body placeholder, empty, hints, border)
-                            // + ~1 line per 40 chars of body text (rough word wrap estimate)
-                            // + 3 lines per reply
-                            let body_lines = (comment.body.len() / 40).max(1);
-                            let reply_lines = comment.replies.len() * 3;
-                            total_lines += 7 + body_lines + reply_lines;
-                        }
-                    }
-                }
+            for comment in self.comments_below_line(idx) {
+                total_lines += self.estimate_comment_height(comment);
             }
         }
 
@@ -1518,6 +2123,13 @@ impl App {
     }
 }
 
+/// Read a coverage report, resolving its paths against the repository root.
+pub fn load_coverage(path: &Path, git: &dyn GitRepo) -> Result<Coverage> {
+    let text = std::fs::read_to_string(path)
+        .with_context(|| format!("Failed to read coverage report {}", path.display()))?;
+    Coverage::parse(&text, &git.workdir()?).with_context(|| format!("Invalid coverage report {}", path.display()))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1658,4 +2270,98 @@ mod tests {
         app.handle_key(KeyCode::Char('s'), KeyModifiers::default(), &git).unwrap();
         assert_eq!(app.diff_view_mode, diff_view::DiffViewMode::Unified);
     }
+
+    #[test]
+    fn test_comment_filter_cycles() {
+        let git = FakeGitRepo::new();
+        let mut app = App::new(&git, None, None, None).unwrap();
+        assert_eq!(app.comment_filter, CommentFilter::All);
+
+        for expected in [CommentFilter::HideNits, CommentFilter::BlockingOnly, CommentFilter::Hidden] {
+            app.handle_key(KeyCode::Char('C'), KeyModifiers::default(), &git).unwrap();
+            assert_eq!(app.comment_filter, expected);
+        }
+        assert!(!app.show_comments());
+
+        app.handle_key(KeyCode::Char('C'), KeyModifiers::default(), &git).unwrap();
+        assert_eq!(app.comment_filter, CommentFilter::All);
+    }
+
+    #[test]
+    fn test_next_open_comment_expands_collapsed_file() {
+        let git = FakeGitRepo::new();
+        let mut app = App::new(&git, None, None, None).unwrap();
+        app.comments = vec![Comment { body: "log with tracing".to_string(), ..Comment::test(Some("src/main.rs"), Some(2)) }];
+        app.collapsed_files.insert(0);
+        app.rebuild_diff_lines();
+
+        app.handle_key(KeyCode::Char(']'), KeyModifiers::default(), &git).unwrap();
+        assert!(app.collapsed_files.is_empty());
+        assert_eq!(app.focused_comment, Some(1));
+        assert_eq!(app.diff_lines[app.cursor].content.new_line_num(), Some(2));
+    }
+
+    #[test]
+    fn test_next_uncovered_line_jumps_to_added_line() {
+        let git = FakeGitRepo::new();
+        let report = "SF:/fake/repo/src/main.rs\nDA:1,1\nDA:2,0\nend_of_record\n";
+        let coverage = Coverage::parse(report, Path::new("/fake/repo")).unwrap();
+        let mut app = App::new(&git, None, None, None)
+            .unwrap()
+            .with_coverage(PathBuf::from("lcov.info"), coverage);
+        assert_eq!(app.diff_coverage.as_ref().and_then(|c| c.file(0)).map(|c| c.percent()), Some(0));
+        app.collapsed_files.insert(0);
+        app.rebuild_diff_lines();
+
+        app.handle_key(KeyCode::Char('U'), KeyModifiers::default(), &git).unwrap();
+        assert!(app.collapsed_files.is_empty());
+        assert_eq!(app.diff_lines[app.cursor].content.new_line_num(), Some(2));
+
+        // Nothing uncovered past the cursor
+        let cursor = app.cursor;
+        app.handle_key(KeyCode::Char('U'), KeyModifiers::default(), &git).unwrap();
+        assert_eq!(app.cursor, cursor);
+    }
+
+    #[test]
+    fn test_review_submit_and_discard() {
+        use crate::adapters::SqliteStateStore;
+        let git = FakeGitRepo::new();
+        let store: Arc<dyn StateStore> = Arc::new(SqliteStateStore::open_in_memory().unwrap());
+        let mut app = App::new(&git, None, Some(store.clone()), None).unwrap();
+        let none = KeyModifiers::default();
+
+        // First S starts a review, the second opens the submit dialog
+        app.handle_key(KeyCode::Char('S'), none, &git).unwrap();
+        assert!(app.pending_review.is_some());
+        app.handle_key(KeyCode::Char('S'), none, &git).unwrap();
+        assert_eq!(app.mode, ViewMode::ReviewSubmit);
+        for c in "lgtm".chars() {
+            app.handle_key(KeyCode::Char(c), none, &git).unwrap();
+        }
+        app.handle_key(KeyCode::Enter, none, &git).unwrap();
+        assert_eq!(app.mode, ViewMode::Normal);
+        assert_eq!(app.pending_review, None);
+        let reviews = store.get_reviews("/fake/repo", "feature").unwrap();
+        assert_eq!(reviews.len(), 1);
+        assert_eq!(reviews[0].body, "lgtm");
+        assert!(!reviews[0].is_pending());
+
+        app.handle_key(KeyCode::Char('S'), none, &git).unwrap();
+        app.handle_key(KeyCode::Char('S'), none, &git).unwrap();
+        app.handle_key(KeyCode::Char('d'), KeyModifiers { ctrl: true, ..none }, &git).unwrap();
+        assert_eq!(app.mode, ViewMode::Normal);
+        assert_eq!(app.pending_review, None);
+        assert_eq!(store.get_reviews("/fake/repo", "feature").unwrap().len(), 1);
+
+        // A failed submit keeps the dialog open and says why
+        app.handle_key(KeyCode::Char('S'), none, &git).unwrap();
+        store.discard_review(app.pending_review.unwrap()).unwrap();
+        app.handle_key(KeyCode::Char('S'), none, &git).unwrap();
+        app.handle_key(KeyCode::Enter, none, &git).unwrap();
+        assert_eq!(app.mode, ViewMode::ReviewSubmit);
+        assert!(app.status_message.as_deref().unwrap().starts_with("Could not submit the review: "));
+        app.handle_key(KeyCode::Esc, none, &git).unwrap();
+        assert_eq!(app.status_message, None);
+    }
 }
@@ -0,0 +1,370 @@
+//! Review archives: a lossless JSON export of one branch's review state.
+//!
+//! An archive holds published comments with their replies and workflow state,
+//! deleted-comment tombstones, submitted reviews and viewed files. Everything
+//! is keyed by UUID (or file path), so importing the same archive twice
+//! changes nothing. Pending reviews and their drafts are not included.
+
+use crate::domain::snapshot::{BranchSnapshot, CommentDoc};
+use crate::domain::{Comment, Review, ReviewVerdict};
+use crate::ports::{StateStore, ViewedFile};
+use crate::sync::{self, MergeStats};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Current archive format version.
+pub const ARCHIVE_VERSION: u32 = 1;
+
+/// Marker identifying panko archives.
+const ARCHIVE_FORMAT: &str = "panko-archive";
+
+/// One branch's review state.
+///
+/// In a parsed archive, review IDs are positions within the archive (1-based)
+/// and comments' `review_id` refer to them; they are remapped on import.
+#[derive(Debug, Clone, Default)]
+pub struct ReviewArchive {
+    pub repo_path: String,
+    pub branch: String,
+    /// Unix timestamp in milliseconds
+    pub exported_at: i64,
+    pub comments: Vec<Comment>,
+    /// UUIDs of deleted comments
+    pub deleted: Vec<String>,
+    /// Submitted reviews, oldest first
+    pub reviews: Vec<Review>,
+    pub viewed_files: Vec<ViewedFile>,
+}
+
+/// What an import changed locally.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportStats {
+    pub comments: MergeStats,
+    pub reviews: usize,
+    pub viewed_files: usize,
+}
+
+impl ImportStats {
+    pub fn summary(&self) -> String {
+        format!(
+            "{}; {} new reviews; {} viewed files",
+            self.comments.summary(),
+            self.reviews,
+            self.viewed_files
+        )
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct ArchiveDoc {
+    format: String,
+    version: u32,
+    repo_path: String,
+    branch: String,
+    exported_at: i64,
+    comments: Vec<CommentDoc>,
+    #[serde(default)]
+    deleted: Vec<String>,
+    #[serde(default)]
+    reviews: Vec<ReviewDoc>,
+    #[serde(default)]
+    viewed_files: Vec<ViewedFileDoc>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReviewDoc {
+    uuid: String,
+    author: String,
+    body: String,
+    verdict: Option<String>,
+    created_at: i64,
+    submitted_at: Option<i64>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ViewedFileDoc {
+    file_path: String,
+    viewed_at: i64,
+}
+
+impl ReviewArchive {
+    /// Serialize to pretty-printed JSON.
+    pub fn to_json(&self) -> Result<String> {
+        let review_uuids: HashMap<i64, &str> =
+            self.reviews.iter().map(|r| (r.id, r.uuid.as_str())).collect();
+        let doc = ArchiveDoc {
+            format: ARCHIVE_FORMAT.to_string(),
+            version: ARCHIVE_VERSION,
+            repo_path: self.repo_path.clone(),
+            branch: self.branch.clone(),
+            exported_at: self.exported_at,
+            comments: self
+                .comments
+                .iter()
+                .filter(|c| !c.draft)
+                .map(|c| {
+                    let review = c.review_id.and_then(|id| review_uuids.get(&id)).map(|u| u.to_string());
+                    CommentDoc::from_comment(c, review)
+                })
+                .collect(),
+            deleted: self.deleted.clone(),
+            reviews: self
+                .reviews
+                .iter()
+                .map(|r| ReviewDoc {
+                    uuid: r.uuid.clone(),
+                    author: r.author.clone(),
+                    body: r.body.clone(),
+                    verdict: r.verdict.map(|v| v.as_str().to_string()),
+                    created_at: r.created_at,
+                    submitted_at: r.submitted_at,
+                })
+                .collect(),
+            viewed_files: self
+                .viewed_files
+                .iter()
+                .map(|f| ViewedFileDoc {
+                    file_path: f.file_path.clone(),
+                    viewed_at: f.viewed_at,
+                })
+                .collect(),
+        };
+        Ok(serde_json::to_string_pretty(&doc)?)
+    }
+
+    /// Parse an archive written by `to_json`.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let doc: ArchiveDoc = serde_json::from_str(json).context("Invalid panko archive")?;
+        if doc.format != ARCHIVE_FORMAT {
+            bail!("Not a panko archive (format '{}')", doc.format);
+        }
+        if doc.version > ARCHIVE_VERSION {
+            bail!(
+                "Archive version {} is newer than this panko supports ({}); please upgrade",
+                doc.version,
+                ARCHIVE_VERSION
+            );
+        }
+
+        let reviews: Vec<Review> = doc
+            .reviews
+            .into_iter()
+            .enumerate()
+            .map(|(i, r)| Review {
+                id: i as i64 + 1,
+                uuid: r.uuid,
+                author: r.author,
+                body: r.body,
+                verdict: r.verdict.as_deref().and_then(ReviewVerdict::parse),
+                created_at: r.created_at,
+                submitted_at: r.submitted_at,
+            })
+            .collect();
+        let comments = doc
+            .comments
+            .into_iter()
+            .map(|doc| {
+                let review_id = doc
+                    .review
+                    .as_ref()
+                    .and_then(|uuid| reviews.iter().find(|r| &r.uuid == uuid))
+                    .map(|r| r.id);
+                Comment {
+                    review_id,
+                    ..doc.into_comment()
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            repo_path: doc.repo_path,
+            branch: doc.branch,
+            exported_at: doc.exported_at,
+            comments,
+            deleted: doc.deleted,
+            reviews,
+            viewed_files: doc
+                .viewed_files
+                .into_iter()
+                .map(|f| ViewedFile {
+                    file_path: f.file_path,
+                    viewed_at: f.viewed_at,
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Collect a branch's review state from the store.
+pub fn export(store: &dyn StateStore, repo_path: &str, branch: &str) -> Result<ReviewArchive> {
+    let snapshot = sync::snapshot(store, repo_path, branch)?;
+    let mut viewed_files = store.get_viewed_files(repo_path, branch)?;
+    viewed_files.sort_by(|a, b| a.file_path.cmp(&b.file_path));
+
+    Ok(ReviewArchive {
+        repo_path: repo_path.to_string(),
+        branch: branch.to_string(),
+        exported_at: chrono::Utc::now().timestamp_millis(),
+        comments: snapshot.comments,
+        deleted: snapshot.deleted,
+        reviews: store.get_reviews(repo_path, branch)?,
+        viewed_files,
+    })
+}
+
+/// Import an archive into a branch, merging with what is already there.
+pub fn import(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    archive: &ReviewArchive,
+) -> Result<ImportStats> {
+    let mut stats = ImportStats::default();
+
+    // Reviews first, so comments can point at their local IDs
+    let existing: HashMap<String, i64> = store
+        .get_reviews(repo_path, branch)?
+        .into_iter()
+        .map(|r| (r.uuid, r.id))
+        .collect();
+    let mut review_ids = HashMap::new();
+    for review in &archive.reviews {
+        let local_id = match existing.get(&review.uuid) {
+            Some(&id) => id,
+            None => {
+                stats.reviews += 1;
+                store.import_review(repo_path, branch, review)?
+            }
+        };
+        review_ids.insert(review.id, local_id);
+    }
+
+    let snapshot = BranchSnapshot {
+        comments: archive
+            .comments
+            .iter()
+            .map(|c| Comment {
+                review_id: c.review_id.and_then(|id| review_ids.get(&id).copied()),
+                ..c.clone()
+            })
+            .collect(),
+        deleted: archive.deleted.clone(),
+    };
+    stats.comments = sync::merge(store, repo_path, branch, &snapshot)?;
+
+    let viewed: HashMap<String, i64> = store
+        .get_viewed_files(repo_path, branch)?
+        .into_iter()
+        .map(|f| (f.file_path, f.viewed_at))
+        .collect();
+    for file in &archive.viewed_files {
+        if viewed.get(&file.file_path).is_none_or(|&at| at < file.viewed_at) {
+            store.import_viewed(repo_path, branch, file)?;
+            stats.viewed_files += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SqliteStateStore;
+    use crate::domain::{new_uuid, AuthorKind, CommentStatus};
+    use crate::ports::{NewComment, NewReply};
+
+    /// A branch with a reviewed comment, a reply, a resolved comment and a viewed file.
+    fn populated_store() -> SqliteStateStore {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let review = store.start_review("/repo", "feat", "alice").unwrap();
+        for (line, review_id) in [(1, Some(review)), (2, None)] {
+            store
+                .add_comment(
+                    "/repo",
+                    "feat",
+                    NewComment {
+                        file_path: Some("src/lib.rs".to_string()),
+                        line_range: Some((line, line)),
+                        anchor: None,
+                        body: format!("comment {}", line),
+                        author: "alice".to_string(),
+                        author_kind: AuthorKind::Human,
+                        severity: None,
+                        labels: vec!["api".to_string()],
+                        review_id,
+                        uuid: new_uuid(),
+                    },
+                )
+                .unwrap();
+        }
+        store.submit_review(review, "see inline", ReviewVerdict::RequestChanges).unwrap();
+
+        let comments = store.get_comments("/repo", "feat").unwrap();
+        store
+            .add_reply(NewReply {
+                comment_id: comments[0].id,
+                body: "fixed".to_string(),
+                author: "bob".to_string(),
+                author_kind: AuthorKind::Human,
+                uuid: new_uuid(),
+            })
+            .unwrap();
+        store.set_comment_status(comments[1].id, CommentStatus::Resolved).unwrap();
+        store.mark_viewed("/repo", "feat", "src/lib.rs").unwrap();
+        store
+    }
+
+    #[test]
+    fn archive_round_trips_through_json() {
+        let archive = export(&populated_store(), "/repo", "feat").unwrap();
+        let parsed = ReviewArchive::from_json(&archive.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.to_json().unwrap(), archive.to_json().unwrap());
+        assert_eq!(parsed.reviews[0].id, 1);
+        assert_eq!(parsed.comments[0].review_id, Some(1));
+        assert!(ReviewArchive::from_json(r#"{"format": "other", "version": 1}"#).is_err());
+    }
+
+    #[test]
+    fn import_is_lossless_and_idempotent() {
+        let source = populated_store();
+        let json = export(&source, "/repo", "feat").unwrap().to_json().unwrap();
+        let archive = ReviewArchive::from_json(&json).unwrap();
+
+        let target = SqliteStateStore::open_in_memory().unwrap();
+        let stats = import(&target, "/elsewhere", "feat", &archive).unwrap();
+        assert_eq!((stats.comments.added, stats.reviews, stats.viewed_files), (2, 1, 1));
+
+        let imported = export(&target, "/elsewhere", "feat").unwrap();
+        let original = export(&source, "/repo", "feat").unwrap();
+        assert_eq!(imported.comments.len(), 2);
+        for (a, b) in imported.comments.iter().zip(&original.comments) {
+            assert_eq!(a.uuid, b.uuid);
+            assert_eq!((a.status, a.resolved_at, a.updated_at), (b.status, b.resolved_at, b.updated_at));
+            assert_eq!(a.replies.len(), b.replies.len());
+        }
+        assert_eq!(imported.reviews[0].uuid, original.reviews[0].uuid);
+        assert_eq!(imported.reviews[0].verdict, Some(ReviewVerdict::RequestChanges));
+        assert_eq!(imported.comments[0].review_id, Some(imported.reviews[0].id));
+        assert_eq!(imported.viewed_files[0].viewed_at, original.viewed_files[0].viewed_at);
+
+        assert_eq!(import(&target, "/elsewhere", "feat", &archive).unwrap(), ImportStats::default());
+    }
+
+    #[test]
+    fn import_into_another_branch_of_the_same_repository() {
+        let store = populated_store();
+        let archive = export(&store, "/repo", "feat").unwrap();
+
+        let stats = import(&store, "/repo", "fixture", &archive).unwrap();
+        assert_eq!((stats.comments.added, stats.reviews), (2, 1));
+        assert_eq!(import(&store, "/repo", "fixture", &archive).unwrap(), ImportStats::default());
+
+        let copy = export(&store, "/repo", "fixture").unwrap();
+        assert_eq!(copy.comments[0].replies.len(), 1);
+        assert_eq!(copy.comments[0].review_id, Some(copy.reviews[0].id));
+        assert_ne!(copy.reviews[0].id, archive.reviews[0].id);
+        assert_eq!(export(&store, "/repo", "feat").unwrap().comments.len(), 2);
+    }
+}
@@ -0,0 +1,398 @@
+//! Batch operations: many comment changes from one JSON document, applied in a
+//! single state store transaction.
+//!
+//! A batch is a JSON array of operations tagged by `op`:
+//!
+//! ```json
+//! [
+//!   {"op": "comment", "file": "src/db.rs", "start": 10, "end": 12, "message": "..."},
+//!   {"op": "reply", "id": 3, "message": "fixed"},
+//!   {"op": "resolve", "id": 3}
+//! ]
+//! ```
+//!
+//! Each operation gets a result (the new or affected ID, or an error). A
+//! failed operation changes nothing; in atomic mode it also rolls back the
+//! whole batch and the operations after it are skipped. Operations on
+//! existing comments are checked against the actor's agent policy.
+
+use crate::domain::policy::{Actor, CommentAction};
+use crate::domain::{self, AuthorKind, CommentStatus, Severity};
+use crate::output::{self, ErrorCode};
+use crate::ports::{NewComment, NewReply, StateStore};
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// One operation. IDs refer to comments on the current branch.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Operation {
+    /// A line comment with `file` and `start`, file-level with only `file`,
+    /// branch-level with neither
+    Comment {
+        file: Option<String>,
+        start: Option<usize>,
+        /// Defaults to `start`
+        end: Option<usize>,
+        message: String,
+        severity: Option<String>,
+        #[serde(default)]
+        labels: Vec<String>,
+        author: Option<String>,
+    },
+    Reply {
+        id: i64,
+        message: String,
+        author: Option<String>,
+    },
+    Resolve {
+        id: i64,
+    },
+    Unresolve {
+        id: i64,
+    },
+    Delete {
+        id: i64,
+    },
+}
+
+impl Operation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Operation::Comment { .. } => "comment",
+            Operation::Reply { .. } => "reply",
+            Operation::Resolve { .. } => "resolve",
+            Operation::Unresolve { .. } => "unresolve",
+            Operation::Delete { .. } => "delete",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OpStatus {
+    Applied,
+    Failed,
+    /// Not attempted after an earlier failure in an atomic batch
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct OpError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+/// Result of one operation, in input order.
+#[derive(Debug, Clone, Serialize)]
+pub struct OpResult {
+    pub index: usize,
+    /// None when the entry is not a valid operation
+    pub op: Option<&'static str>,
+    pub status: OpStatus,
+    /// New comment or reply ID, or the comment acted on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<OpError>,
+}
+
+/// `panko batch` output.
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchOutcome {
+    /// False when an atomic batch was rolled back
+    pub committed: bool,
+    pub results: Vec<OpResult>,
+}
+
+impl BatchOutcome {
+    pub fn failed(&self) -> usize {
+        self.results.iter().filter(|r| r.status == OpStatus::Failed).count()
+    }
+}
+
+/// Parse a batch. Entries that are not valid operations are kept as errors,
+/// so the rest of the batch can still be applied.
+pub fn parse(json: &str) -> Result<Vec<Result<Operation, String>>> {
+    let entries: Vec<serde_json::Value> = serde_json::from_str(json)
+        .map_err(|e| output::usage(format!("Expected a JSON array of operations: {}", e)))?;
+    Ok(entries
+        .into_iter()
+        .map(|entry| serde_json::from_value(entry).map_err(|e| e.to_string()))
+        .collect())
+}
+
+/// Apply a batch to a repo/branch in one transaction. The actor's name is used
+/// for operations that don't name their own. With `atomic`, the first failure
+/// rolls everything back; otherwise the operations that succeeded are kept.
+pub fn apply(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    actor: &Actor,
+    operations: &[Result<Operation, String>],
+    atomic: bool,
+) -> Result<BatchOutcome> {
+    let mut results = Vec::new();
+    let mut committed = false;
+    store.transaction(&mut |tx| {
+        // Looked up inside the transaction so policy checks see the same
+        // comments the operations change
+        let mut known = comment_authors(tx, repo_path, branch)?;
+        results.clear();
+        let mut failed = false;
+        for (index, operation) in operations.iter().enumerate() {
+            let op = operation.as_ref().ok().map(Operation::name);
+            if failed && atomic {
+                results.push(OpResult { index, op, status: OpStatus::Skipped, id: None, error: None });
+                continue;
+            }
+
+            let outcome = match operation {
+                Ok(operation) => apply_one(tx, repo_path, branch, actor, &mut known, operation),
+                Err(message) => Err(output::usage(message.clone())),
+            };
+            results.push(match outcome {
+                Ok(id) => OpResult { index, op, status: OpStatus::Applied, id: Some(id), error: None },
+                Err(err) => {
+                    failed = true;
+                    OpResult {
+                        index,
+                        op,
+                        status: OpStatus::Failed,
+                        id: None,
+                        error: Some(OpError { code: output::error_code(&err), message: format!("{:#}", err) }),
+                    }
+                }
+            });
+        }
+        committed = !(failed && atomic);
+        Ok(committed)
+    })?;
+
+    Ok(BatchOutcome { committed, results })
+}
+
+/// Apply one operation on its own, returning the same ID a batch would report.
+pub fn apply_single(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    actor: &Actor,
+    operation: &Operation,
+) -> Result<i64> {
+    let mut known = comment_authors(store, repo_path, branch)?;
+    apply_one(store, repo_path, branch, actor, &mut known, operation)
+}
+
+/// Check that `start..=end` are lines of `file` and return their text to
+/// anchor a comment to. Files that can't be read, e.g. deleted ones, only get
+/// the range itself checked.
+pub fn line_anchor(repo_path: &str, file: &str, (start, end): (usize, usize)) -> Result<Option<String>> {
+    if start == 0 || end < start {
+        return Err(output::usage(format!("Invalid line range {}-{}", start, end)));
+    }
+    let Ok(source) = std::fs::read_to_string(Path::new(repo_path).join(file)) else {
+        return Ok(None);
+    };
+    match domain::context::anchor_text(&source, start, end) {
+        Some(anchor) => Ok(Some(anchor)),
+        None => Err(output::usage(format!(
+            "Line range {}-{} is past the end of {} ({} lines)",
+            start,
+            end,
+            file,
+            source.lines().count()
+        ))),
+    }
+}
+
+/// Kinds of author of a branch's comments, by ID.
+fn comment_authors(store: &dyn StateStore, repo_path: &str, branch: &str) -> Result<HashMap<i64, AuthorKind>> {
+    Ok(store.get_comments(repo_path, branch)?.iter().map(|c| (c.id, c.author_kind)).collect())
+}
+
+/// Apply one operation, returning the ID to report. `known` tracks the
+/// branch's comments as the batch adds and deletes them.
+fn apply_one(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    actor: &Actor,
+    known: &mut HashMap<i64, AuthorKind>,
+    operation: &Operation,
+) -> Result<i64> {
+    let check = |id: i64, action: CommentAction| -> Result<i64> {
+        let author = known
+            .get(&id)
+            .ok_or_else(|| output::not_found(format!("Comment #{} not found", id)))?;
+        actor.check(action, *author).map_err(output::forbidden)?;
+        Ok(id)
+    };
+
+    match operation {
+        Operation::Comment { file, start, end, message, severity, labels, author } => {
+            let line_range = match (start, end) {
+                (Some(start), end) => Some((*start, end.unwrap_or(*start))),
+                (None, Some(_)) => return Err(output::usage("`end` needs a `start` line")),
+                (None, None) => None,
+            };
+            let anchor = match (file, line_range) {
+                (Some(file), Some(range)) => line_anchor(repo_path, file, range)?,
+                (None, Some(_)) => return Err(output::usage("A line comment needs a `file`")),
+                _ => None,
+            };
+            let severity = match severity {
+                Some(s) => Some(
+                    Severity::parse(s).ok_or_else(|| output::usage(format!("Unknown severity '{}'", s)))?,
+                ),
+                None => None,
+            };
+
+            let id = store.add_comment(repo_path, branch, NewComment {
+                file_path: file.clone(),
+                line_range,
+                anchor,
+                body: message.clone(),
+                author: author.clone().unwrap_or_else(|| actor.name.clone()),
+                author_kind: actor.kind,
+                severity,
+                labels: labels.clone(),
+                review_id: None,
+                uuid: domain::new_uuid(),
+            })?;
+            known.insert(id, actor.kind);
+            Ok(id)
+        }
+        Operation::Reply { id, message, author } => {
+            check(*id, CommentAction::Reply)?;
+            store.add_reply(NewReply {
+                comment_id: *id,
+                body: message.clone(),
+                author: author.clone().unwrap_or_else(|| actor.name.clone()),
+                author_kind: actor.kind,
+                uuid: domain::new_uuid(),
+            })
+        }
+        Operation::Resolve { id } => {
+            check(*id, CommentAction::SetStatus(CommentStatus::Resolved))?;
+            store.resolve_comment(*id)?;
+            Ok(*id)
+        }
+        Operation::Unresolve { id } => {
+            check(*id, CommentAction::SetStatus(CommentStatus::Open))?;
+            store.unresolve_comment(*id)?;
+            Ok(*id)
+        }
+        Operation::Delete { id } => {
+            check(*id, CommentAction::Delete)?;
+            store.delete_comment(*id)?;
+            known.remove(id);
+            Ok(*id)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SqliteStateStore;
+    use crate::domain::policy::Policy;
+
+    fn alice() -> Actor {
+        Actor { name: "alice".to_string(), kind: AuthorKind::Human, declared: true, policy: Policy::Guarded }
+    }
+
+    const BATCH: &str = r#"[
+        {"op": "comment", "file": "src/db.rs", "start": 3, "message": "use a pool", "severity": "nit"},
+        {"op": "comment", "message": "split this PR"},
+        {"op": "resolve", "id": 1},
+        {"op": "reply", "id": 99, "message": "?"},
+        {"op": "frobnicate"}
+    ]"#;
+
+    #[test]
+    fn applies_operations_and_reports_each_result() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let operations = parse(BATCH).unwrap();
+        let outcome = apply(&store, "/repo", "main", &alice(), &operations, false).unwrap();
+
+        assert!(outcome.committed);
+        let statuses: Vec<OpStatus> = outcome.results.iter().map(|r| r.status).collect();
+        use OpStatus::*;
+        assert_eq!(statuses, vec![Applied, Applied, Applied, Failed, Failed]);
+        assert_eq!(outcome.results[3].error.as_ref().unwrap().code, ErrorCode::NotFound);
+        assert_eq!(outcome.results[4].op, None);
+        assert_eq!(outcome.failed(), 2);
+
+        let comments = store.get_comments("/repo", "main").unwrap();
+        assert_eq!(comments.len(), 2);
+        let line_comment = comments.iter().find(|c| c.id == 1).unwrap();
+        assert_eq!(line_comment.line_range, Some((3, 3)));
+        assert_eq!(line_comment.severity, Some(Severity::Nit));
+        assert_eq!(line_comment.status, CommentStatus::Resolved);
+        assert_eq!(line_comment.author, "alice");
+    }
+
+    #[test]
+    fn atomic_batches_roll_back_on_failure() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let operations = parse(BATCH).unwrap();
+        let outcome = apply(&store, "/repo", "main", &alice(), &operations, true).unwrap();
+
+        assert!(!outcome.committed);
+        assert_eq!(outcome.results[4].status, OpStatus::Skipped);
+        assert!(store.get_comments("/repo", "main").unwrap().is_empty());
+
+        // The store is usable afterwards
+        let outcome = apply(&store, "/repo", "main", &alice(), &operations[..3], true).unwrap();
+        assert!(outcome.committed);
+        assert_eq!(store.get_comments("/repo", "main").unwrap().len(), 2);
+    }
+
+    #[test]
+    fn guarded_agents_cannot_close_human_comments() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        apply(&store, "/repo", "main", &alice(), &parse(BATCH).unwrap()[..2], false).unwrap();
+        let agent = Actor { kind: AuthorKind::Agent, ..alice() };
+        let operations = parse(
+            r#"[
+                {"op": "reply", "id": 2, "message": "done"},
+                {"op": "resolve", "id": 2},
+                {"op": "delete", "id": 2},
+                {"op": "comment", "message": "follow-up"},
+                {"op": "resolve", "id": 3}
+            ]"#,
+        )
+        .unwrap();
+        let outcome = apply(&store, "/repo", "main", &agent, &operations, false).unwrap();
+
+        let codes: Vec<_> = outcome.results.iter().map(|r| r.error.as_ref().map(|e| e.code)).collect();
+        assert_eq!(codes, vec![None, Some(ErrorCode::Forbidden), Some(ErrorCode::Forbidden), None, None]);
+        let comments = store.get_comments("/repo", "main").unwrap();
+        let human = comments.iter().find(|c| c.id == 2).unwrap();
+        assert_eq!(human.status, CommentStatus::Open);
+        assert_eq!(human.replies[0].author_kind, AuthorKind::Agent);
+        let own = comments.iter().find(|c| c.id == 3).unwrap();
+        assert_eq!((own.author_kind, own.status), (AuthorKind::Agent, CommentStatus::Resolved));
+    }
+
+    #[test]
+    fn line_comments_must_be_inside_the_file() {
+        let dir = std::env::temp_dir().join(format!("panko-batch-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join("lib.rs"), "fn a() {}\nfn b() {}\n").unwrap();
+        let repo_path = dir.to_str().unwrap();
+
+        assert_eq!(line_anchor(repo_path, "lib.rs", (2, 2)).unwrap().as_deref(), Some("fn b() {}"));
+        for range in [(2, 3), (2, 1), (0, 1)] {
+            let err = line_anchor(repo_path, "lib.rs", range).unwrap_err();
+            assert_eq!(output::error_code(&err), ErrorCode::Usage);
+        }
+        // Deleted files have no lines to check against
+        assert_eq!(line_anchor(repo_path, "gone.rs", (7, 9)).unwrap(), None);
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
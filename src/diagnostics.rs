@@ -0,0 +1,582 @@
+//! Linter and compiler diagnostics as review comments (`panko import-diagnostics`).
+//!
+//! Reads SARIF 2.1 (eslint, semgrep, clippy-sarif, ...) or cargo/rustc
+//! `--message-format=json` output. Only diagnostics on lines the branch
+//! changed are kept; each becomes a comment authored by its tool, with the
+//! rule as a label. Importing again is idempotent: known diagnostics (same
+//! tool, file, rule and message) keep their comment and follow their lines if
+//! edits moved them, resolved ones that come back are reopened, and open ones
+//! the tool no longer reports are resolved.
+//!
+//! A clean `cargo clippy` run looks like any other build, so its output is
+//! read as `Format::Clippy` for clippy to count as run.
+
+use crate::domain::{AuthorKind, Comment, CommentStatus, Diff};
+use crate::output;
+use crate::ports::StateStore;
+use crate::reviewer::{self, Finding};
+use anyhow::Result;
+use serde::Deserialize;
+use std::collections::{BTreeMap, HashSet};
+
+/// One finding from a tool.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub tool: String,
+    /// Path relative to the repository root
+    pub file: String,
+    pub start_line: usize,
+    pub end_line: usize,
+    /// The tool's level (error, warning, note, ...)
+    pub level: String,
+    pub message: String,
+    pub rule: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Sarif,
+    /// JSON lines from `cargo --message-format=json` or `rustc --error-format=json`
+    Cargo,
+    /// `cargo clippy --message-format=json`: clippy ran even if it found nothing
+    Clippy,
+}
+
+/// Parsed tool output.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Report {
+    /// Tools that ran, including ones that found nothing
+    pub tools: Vec<String>,
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl Report {
+    /// Attribute every diagnostic to `tool`, e.g. `clippy` for the rustc
+    /// warnings `cargo clippy` repeats.
+    pub fn with_tool(mut self, tool: &str) -> Self {
+        self.tools = vec![tool.to_string()];
+        for d in &mut self.diagnostics {
+            d.tool = tool.to_string();
+        }
+        self
+    }
+
+    /// Keep the diagnostics on lines the diff added or changed.
+    pub fn on_changed_lines(mut self, diff: &Diff) -> Self {
+        self.diagnostics.retain(|d| {
+            diff.files
+                .iter()
+                .any(|f| f.path == d.file && f.changes_lines(d.start_line, d.end_line))
+        });
+        self
+    }
+}
+
+/// What an import did for one tool.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolImport {
+    pub added: usize,
+    /// Still reported, on other lines
+    pub moved: usize,
+    pub unchanged: usize,
+    pub reopened: usize,
+    pub resolved: usize,
+}
+
+// ─── SARIF 2.1 (only the parts we read) ───
+
+#[derive(Deserialize)]
+struct SarifLog {
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Deserialize)]
+struct SarifRun {
+    tool: SarifTool,
+    #[serde(default)]
+    results: Vec<SarifResult>,
+}
+
+#[derive(Deserialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Deserialize)]
+struct SarifDriver {
+    name: String,
+    #[serde(default)]
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: String,
+    default_configuration: Option<SarifConfiguration>,
+}
+
+#[derive(Deserialize)]
+struct SarifConfiguration {
+    level: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: Option<String>,
+    level: Option<String>,
+    message: SarifMessage,
+    #[serde(default)]
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Deserialize)]
+struct SarifMessage {
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: Option<SarifPhysicalLocation>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: Option<SarifRegion>,
+}
+
+#[derive(Deserialize)]
+struct SarifArtifactLocation {
+    uri: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: Option<usize>,
+    end_line: Option<usize>,
+}
+
+// ─── rustc JSON diagnostics ───
+
+/// A cargo message; only `compiler-message` ones carry diagnostics.
+#[derive(Deserialize)]
+struct CargoMessage {
+    reason: String,
+    message: Option<RustcDiagnostic>,
+}
+
+#[derive(Deserialize)]
+struct RustcDiagnostic {
+    message: String,
+    code: Option<RustcCode>,
+    level: String,
+    spans: Vec<RustcSpan>,
+}
+
+#[derive(Deserialize)]
+struct RustcCode {
+    code: String,
+}
+
+#[derive(Deserialize)]
+struct RustcSpan {
+    file_name: String,
+    line_start: usize,
+    line_end: usize,
+    is_primary: bool,
+}
+
+/// Parse diagnostics, detecting the format when `format` is None. Paths
+/// are made relative to `repo_path`.
+pub fn parse(input: &str, format: Option<Format>, repo_path: &str) -> Result<Report> {
+    let format = format.unwrap_or_else(|| {
+        let sarif = serde_json::from_str::<serde_json::Value>(input)
+            .is_ok_and(|value| value.get("runs").is_some());
+        if sarif {
+            Format::Sarif
+        } else {
+            Format::Cargo
+        }
+    });
+    match format {
+        Format::Sarif => parse_sarif(input, repo_path),
+        Format::Cargo => parse_rustc(input, repo_path, false),
+        Format::Clippy => parse_rustc(input, repo_path, true),
+    }
+}
+
+fn parse_sarif(input: &str, repo_path: &str) -> Result<Report> {
+    let log: SarifLog =
+        serde_json::from_str(input).map_err(|e| output::usage(format!("Invalid SARIF: {}", e)))?;
+    let mut report = Report::default();
+    for run in log.runs {
+        let driver = run.tool.driver;
+        if !report.tools.contains(&driver.name) {
+            report.tools.push(driver.name.clone());
+        }
+        for result in run.results {
+            let rule_level = result.rule_id.as_ref().and_then(|id| {
+                driver
+                    .rules
+                    .iter()
+                    .find(|rule| &rule.id == id)
+                    .and_then(|rule| rule.default_configuration.as_ref()?.level.clone())
+            });
+            let Some(location) = result.locations.iter().find_map(|l| l.physical_location.as_ref()) else {
+                continue;
+            };
+            let Some(start_line) = location.region.as_ref().and_then(|r| r.start_line) else {
+                continue;
+            };
+            let end_line = location.region.as_ref().and_then(|r| r.end_line).unwrap_or(start_line);
+            report.diagnostics.push(Diagnostic {
+                tool: driver.name.clone(),
+                file: relative_path(&location.artifact_location.uri, repo_path),
+                start_line,
+                end_line: end_line.max(start_line),
+                // SARIF's default level
+                level: result.level.clone().or(rule_level).unwrap_or_else(|| "warning".to_string()),
+                message: result.message.text.clone().unwrap_or_default(),
+                rule: result.rule_id.clone(),
+            });
+        }
+    }
+    Ok(report)
+}
+
+fn parse_rustc(input: &str, repo_path: &str, clippy: bool) -> Result<Report> {
+    // Otherwise clippy only counts as run when it reported something
+    let tools = if clippy { vec!["rustc", "clippy"] } else { vec!["rustc"] };
+    let mut report = Report { tools: tools.into_iter().map(String::from).collect(), diagnostics: Vec::new() };
+    for (i, line) in input.lines().enumerate() {
+        let line = line.trim();
+        // cargo interleaves plain text when stderr is redirected too
+        if !line.starts_with('{') {
+            continue;
+        }
+        let invalid = |e: serde_json::Error| output::usage(format!("Invalid diagnostic on line {}: {}", i + 1, e));
+        let value: serde_json::Value = serde_json::from_str(line).map_err(invalid)?;
+        let diagnostic: RustcDiagnostic = if value.get("reason").is_some() {
+            match serde_json::from_value::<CargoMessage>(value).map_err(invalid)? {
+                CargoMessage { reason, message: Some(message) } if reason == "compiler-message" => message,
+                _ => continue,
+            }
+        } else {
+            serde_json::from_value(value).map_err(invalid)?
+        };
+
+        let Some(span) = diagnostic.spans.iter().find(|s| s.is_primary) else {
+            continue;
+        };
+        let rule = diagnostic.code.map(|c| c.code);
+        let tool = match &rule {
+            Some(code) if code.starts_with("clippy::") => "clippy",
+            _ => "rustc",
+        };
+        if !report.tools.iter().any(|t| t == tool) {
+            report.tools.push(tool.to_string());
+        }
+        report.diagnostics.push(Diagnostic {
+            tool: tool.to_string(),
+            file: relative_path(&span.file_name, repo_path),
+            start_line: span.line_start,
+            end_line: span.line_end.max(span.line_start),
+            level: diagnostic.level,
+            message: diagnostic.message,
+            rule,
+        });
+    }
+    Ok(report)
+}
+
+/// A tool's path for a file, relative to the repository root.
+fn relative_path(uri: &str, repo_path: &str) -> String {
+    let path = uri.strip_prefix("file://").unwrap_or(uri);
+    let path = path
+        .strip_prefix(repo_path)
+        .and_then(|rest| rest.strip_prefix('/'))
+        .unwrap_or(path);
+    path.trim_start_matches("./").to_string()
+}
+
+/// Sync the comments of each tool in the report with its diagnostics, in
+/// one transaction. Comments from tools that didn't run are left alone.
+pub fn import(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    report: &Report,
+) -> Result<BTreeMap<String, ToolImport>> {
+    let mut by_tool: BTreeMap<String, Vec<Finding>> =
+        report.tools.iter().map(|tool| (tool.clone(), Vec::new())).collect();
+    for d in &report.diagnostics {
+        by_tool.entry(d.tool.clone()).or_default().push(Finding {
+            file: Some(d.file.clone()),
+            line: Some(d.start_line),
+            end_line: Some(d.end_line),
+            body: d.message.clone(),
+            severity: reviewer::severity_from_level(&d.level).map(|s| s.as_str().to_string()),
+            labels: d.rule.iter().cloned().collect(),
+        });
+    }
+    let existing = store.get_comments(repo_path, branch)?;
+
+    let mut imported = BTreeMap::new();
+    store.transaction(&mut |tx| {
+        imported.clear();
+        for (tool, findings) in &by_tool {
+            let mut counts = ToolImport::default();
+            let mut seen = HashSet::new();
+            let mut matched = HashSet::new();
+            // A person who shares the tool's name keeps their comments
+            let previous: Vec<_> =
+                existing.iter().filter(|c| &c.author == tool && c.author_kind == AuthorKind::Tool).collect();
+
+            for finding in findings {
+                let comment = reviewer::new_comment(repo_path, tool, AuthorKind::Tool, finding)?;
+                let key = (&comment.file_path, comment.labels.as_slice(), comment.body.as_str());
+                if !seen.insert((key.0.clone(), key.1.to_vec(), key.2.to_string(), comment.line_range)) {
+                    continue;
+                }
+                // The same finding's comment, preferring the one nearest its lines
+                let found = previous
+                    .iter()
+                    .filter(|c| !matched.contains(&c.id) && identity(c) == key)
+                    .min_by_key(|c| line_distance(c.line_range, comment.line_range));
+                let Some(c) = found else {
+                    tx.add_comment(repo_path, branch, comment)?;
+                    counts.added += 1;
+                    continue;
+                };
+                matched.insert(c.id);
+
+                let moved = c.line_range != comment.line_range;
+                if let (true, Some(lines)) = (moved, comment.line_range) {
+                    tx.move_comment(c.id, lines, comment.anchor.as_deref())?;
+                }
+                // Resolved by an earlier import or by hand; won't-fix stays declined
+                if c.status == CommentStatus::Resolved {
+                    tx.unresolve_comment(c.id)?;
+                    counts.reopened += 1;
+                } else if moved {
+                    counts.moved += 1;
+                } else {
+                    counts.unchanged += 1;
+                }
+            }
+
+            for c in previous.iter().filter(|c| !c.status.is_closed() && !matched.contains(&c.id)) {
+                tx.resolve_comment(c.id)?;
+                counts.resolved += 1;
+            }
+            imported.insert(tool.clone(), counts);
+        }
+        Ok(true)
+    })?;
+    Ok(imported)
+}
+
+/// What identifies a tool's finding across runs: file, rule and message.
+fn identity(comment: &Comment) -> (&Option<String>, &[String], &str) {
+    (&comment.file_path, &comment.labels, &comment.body)
+}
+
+fn line_distance(a: Option<(usize, usize)>, b: Option<(usize, usize)>) -> usize {
+    match (a, b) {
+        (Some((a, _)), Some((b, _))) => a.abs_diff(b),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SqliteStateStore;
+    use crate::domain::{new_uuid, DiffLine, DiffStats, FileDiff, Hunk, Severity};
+    use crate::ports::{NewComment, NewReply};
+
+    const SARIF: &str = r#"{
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {"driver": {"name": "semgrep", "rules": [
+                {"id": "sql-injection", "defaultConfiguration": {"level": "error"}}
+            ]}},
+            "results": [
+                {"ruleId": "sql-injection", "message": {"text": "query built from input"},
+                 "locations": [{"physicalLocation": {"artifactLocation": {"uri": "file:///repo/src/db.rs"},
+                                                     "region": {"startLine": 2}}}]},
+                {"ruleId": "todo", "level": "note", "message": {"text": "leftover TODO"},
+                 "locations": [{"physicalLocation": {"artifactLocation": {"uri": "src/db.rs"},
+                                                     "region": {"startLine": 1}}}]}
+            ]
+        }]
+    }"#;
+
+    const CARGO: &str = r#"   Compiling panko v0.1.0
+{"reason":"compiler-artifact","target":{"name":"panko"}}
+{"reason":"compiler-message","message":{"message":"unused variable: `x`","code":{"code":"unused_variables"},"level":"warning","spans":[{"file_name":"src/db.rs","line_start":3,"line_end":3,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"message":"this `if` can be collapsed","code":{"code":"clippy::collapsible_if"},"level":"warning","spans":[{"file_name":"src/db.rs","line_start":2,"line_end":3,"is_primary":true}]}}
+{"reason":"compiler-message","message":{"message":"aborting due to 1 previous error","code":null,"level":"error","spans":[]}}
+"#;
+
+    /// src/db.rs with line 1 unchanged and lines 2-3 added.
+    fn diff() -> Diff {
+        Diff {
+            files: vec![FileDiff {
+                path: "src/db.rs".to_string(),
+                old_path: None,
+                hunks: vec![Hunk {
+                    old_start: 1,
+                    old_lines: 1,
+                    new_start: 1,
+                    new_lines: 3,
+                    lines: vec![
+                        DiffLine::Context("fn query() {".to_string()),
+                        DiffLine::Addition("    let sql = format!(\"{}\", input);".to_string()),
+                        DiffLine::Addition("    let x = 1;".to_string()),
+                    ],
+                }],
+                stats: DiffStats::new(2, 0),
+                is_binary: false,
+            }],
+        }
+    }
+
+    #[test]
+    fn parses_sarif_and_cargo_output() {
+        let sarif = parse(SARIF, None, "/repo").unwrap();
+        assert_eq!(sarif.tools, vec!["semgrep"]);
+        let sarif = sarif.diagnostics;
+        assert_eq!(sarif.len(), 2);
+        assert_eq!(sarif[0].file, "src/db.rs");
+        assert_eq!(sarif[0].level, "error");
+        assert_eq!((sarif[0].start_line, sarif[0].end_line), (2, 2));
+        assert_eq!(sarif[1].level, "note");
+
+        let cargo = parse(CARGO, None, "/repo").unwrap();
+        assert_eq!(cargo.tools, vec!["rustc", "clippy"]);
+        let tools: Vec<&str> = cargo.diagnostics.iter().map(|d| d.tool.as_str()).collect();
+        assert_eq!(tools, vec!["rustc", "clippy"]);
+        assert_eq!(cargo.diagnostics[1].rule.as_deref(), Some("clippy::collapsible_if"));
+        assert_eq!(cargo.clone().with_tool("clippy").tools, vec!["clippy"]);
+
+        // Only the diagnostic on an unchanged line is dropped
+        let sarif = Report { tools: vec![], diagnostics: sarif };
+        assert_eq!(sarif.on_changed_lines(&diff()).diagnostics.len(), 1);
+        assert_eq!(cargo.on_changed_lines(&diff()).diagnostics.len(), 2);
+    }
+
+    #[test]
+    fn reimports_update_comments_in_place() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let cargo = parse(CARGO, Some(Format::Cargo), "/repo").unwrap().on_changed_lines(&diff());
+        let first = import(&store, "/repo", "main", &cargo).unwrap();
+        assert_eq!(first["clippy"], ToolImport { added: 1, ..Default::default() });
+        let only = |tool: &str, diagnostics: &[Diagnostic]| Report {
+            tools: vec![tool.to_string()],
+            diagnostics: diagnostics.to_vec(),
+        };
+        let cargo = cargo.diagnostics;
+
+        let comments = store.get_comments("/repo", "main").unwrap();
+        let clippy = comments.iter().find(|c| c.author == "clippy").unwrap();
+        assert_eq!(clippy.severity, Some(Severity::Suggestion));
+        assert_eq!(clippy.labels, vec!["clippy::collapsible_if"]);
+        assert_eq!(clippy.line_range, Some((2, 3)));
+
+        // clippy's warning is still there; rustc's was fixed and another appeared
+        let second = import(&store, "/repo", "main", &only("clippy", &cargo[1..])).unwrap();
+        assert_eq!(second["clippy"], ToolImport { unchanged: 1, ..Default::default() });
+        assert!(!second.contains_key("rustc"));
+        let rustc = [Diagnostic { message: "something else".to_string(), ..cargo[0].clone() }];
+        let third = import(&store, "/repo", "main", &only("rustc", &rustc)).unwrap();
+        assert_eq!(third["rustc"], ToolImport { added: 1, resolved: 1, ..Default::default() });
+
+        // The original warning coming back reopens its comment
+        let fourth = import(&store, "/repo", "main", &only("rustc", &cargo[..1])).unwrap();
+        assert_eq!(fourth["rustc"], ToolImport { reopened: 1, resolved: 1, ..Default::default() });
+        assert_eq!(store.get_comments("/repo", "main").unwrap().len(), 3);
+
+        // A clean run resolves everything the tool left
+        let clean = import(&store, "/repo", "main", &only("clippy", &[])).unwrap();
+        assert_eq!(clean["clippy"], ToolImport { resolved: 1, ..Default::default() });
+    }
+
+    #[test]
+    fn clean_clippy_runs_resolve_earlier_findings() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let cargo = parse(CARGO, Some(Format::Clippy), "/repo").unwrap().on_changed_lines(&diff());
+        import(&store, "/repo", "main", &cargo).unwrap();
+        // Someone whose git user is "clippy"
+        let person = store
+            .add_comment(
+                "/repo",
+                "main",
+                NewComment {
+                    file_path: Some("src/db.rs".to_string()),
+                    line_range: None,
+                    anchor: None,
+                    body: "split this module".to_string(),
+                    author: "clippy".to_string(),
+                    author_kind: AuthorKind::Human,
+                    severity: None,
+                    labels: vec![],
+                    review_id: None,
+                    uuid: new_uuid(),
+                },
+            )
+            .unwrap();
+
+        // Nothing but build noise, as when clippy finds nothing
+        let clean = parse("   Checking panko v0.1.0\n    Finished dev\n", Some(Format::Clippy), "/repo").unwrap();
+        assert_eq!(clean.tools, vec!["rustc", "clippy"]);
+        let imported = import(&store, "/repo", "main", &clean).unwrap();
+        assert_eq!(imported["clippy"], ToolImport { resolved: 1, ..Default::default() });
+        assert_eq!(imported["rustc"], ToolImport { resolved: 1, ..Default::default() });
+        let comments = store.get_comments("/repo", "main").unwrap();
+        let still_open: Vec<i64> = comments.iter().filter(|c| !c.status.is_closed()).map(|c| c.id).collect();
+        assert_eq!(still_open, vec![person]);
+
+        // Plain cargo output doesn't say whether clippy ran
+        assert_eq!(parse("", Some(Format::Cargo), "/repo").unwrap().tools, vec!["rustc"]);
+    }
+
+    #[test]
+    fn findings_follow_their_lines_when_code_above_them_changes() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let clippy = parse(CARGO, Some(Format::Cargo), "/repo").unwrap().diagnostics[1].clone();
+        let report = |diagnostics: Vec<Diagnostic>| Report { tools: vec!["clippy".to_string()], diagnostics };
+        import(&store, "/repo", "main", &report(vec![clippy.clone()])).unwrap();
+        let id = store.get_comments("/repo", "main").unwrap()[0].id;
+        store
+            .add_reply(NewReply {
+                comment_id: id,
+                body: "on it".to_string(),
+                author: "alice".to_string(),
+                author_kind: AuthorKind::Human,
+                uuid: new_uuid(),
+            })
+            .unwrap();
+        store.set_comment_status(id, CommentStatus::InProgress).unwrap();
+
+        // Two lines were added above the finding
+        let shifted = Diagnostic { start_line: 4, end_line: 5, ..clippy.clone() };
+        let moved = import(&store, "/repo", "main", &report(vec![shifted])).unwrap();
+        assert_eq!(moved["clippy"], ToolImport { moved: 1, ..Default::default() });
+        let comments = store.get_comments("/repo", "main").unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!((comments[0].id, comments[0].line_range), (id, Some((4, 5))));
+        assert_eq!(comments[0].status, CommentStatus::InProgress);
+        assert_eq!(comments[0].replies.len(), 1);
+
+        // The same message on another line is a second finding
+        let both = vec![Diagnostic { start_line: 4, end_line: 5, ..clippy.clone() }, clippy];
+        let second = import(&store, "/repo", "main", &report(both)).unwrap();
+        assert_eq!(second["clippy"], ToolImport { added: 1, unchanged: 1, ..Default::default() });
+    }
+}
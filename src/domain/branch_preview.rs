@@ -4,6 +4,7 @@
 #![allow(dead_code)]
 
 use super::types::{BranchPreview, Commit, Diff, DiffStats};
+use serde::Serialize;
 
 /// Filter commits by search term (case-insensitive).
 pub fn filter_commits<'a>(commits: &'a [Commit], search: &str) -> Vec<&'a Commit> {
@@ -32,7 +33,7 @@ pub fn compute_summary(preview: &BranchPreview, diff: &Diff) -> BranchSummary {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct BranchSummary {
     pub commit_count: usize,
     pub file_count: usize,
@@ -0,0 +1,112 @@
+//! Code context: the source lines a comment is anchored to, as they read now.
+
+use super::types::Comment;
+use serde::Serialize;
+
+/// Current source around a line comment.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CodeContext {
+    pub lines: Vec<ContextLine>,
+    /// Whether the anchored lines differ from when the comment was written
+    /// (None when the comment predates anchor tracking)
+    pub changed: Option<bool>,
+}
+
+/// One source line, numbered from 1.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContextLine {
+    pub line: usize,
+    /// Inside the comment's line range
+    pub anchored: bool,
+    pub text: String,
+}
+
+/// Text of lines `start..=end` (1-based) of `source`, if it has them.
+pub fn anchor_text(source: &str, start: usize, end: usize) -> Option<String> {
+    let lines: Vec<&str> = source.lines().collect();
+    (start >= 1 && start <= end && end <= lines.len()).then(|| lines[start - 1..end].join("\n"))
+}
+
+/// Lines `start..=end` of `source` with `radius` extra lines on each side,
+/// clipped to the file.
+pub fn lines_around(source: &str, start: usize, end: usize, radius: usize) -> Vec<ContextLine> {
+    let lines: Vec<&str> = source.lines().collect();
+    let first_line = start.saturating_sub(radius).max(1);
+    let last_line = (end + radius).min(lines.len());
+    lines
+        .get(first_line - 1..last_line)
+        .unwrap_or_default()
+        .iter()
+        .zip(first_line..)
+        .map(|(text, line)| ContextLine {
+            line,
+            anchored: (start..=end).contains(&line),
+            text: text.to_string(),
+        })
+        .collect()
+}
+
+/// Context for a line comment from the file's current `source`, with `radius`
+/// extra lines on each side. None for file- and branch-level comments.
+pub fn code_context(comment: &Comment, source: &str, radius: usize) -> Option<CodeContext> {
+    let (start, end) = comment.line_range?;
+    let current = anchor_text(source, start, end);
+    Some(CodeContext {
+        lines: lines_around(source, start, end, radius),
+        changed: comment
+            .anchor
+            .as_ref()
+            .map(|anchor| current.as_deref() != Some(anchor.as_str())),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(line_range: Option<(usize, usize)>, anchor: Option<&str>) -> Comment {
+        Comment { line_range, anchor: anchor.map(String::from), ..Comment::test(Some("src/lib.rs"), None) }
+    }
+
+    #[test]
+    fn context_surrounds_anchor_and_flags_changes() {
+        let source = "one\ntwo\nthree\nfour\nfive\n";
+
+        let context = code_context(&comment(Some((2, 3)), Some("two\nthree")), source, 1).unwrap();
+        let text: Vec<&str> = context.lines.iter().map(|l| l.text.as_str()).collect();
+        assert_eq!(text, vec!["one", "two", "three", "four"]);
+        assert_eq!(context.lines[0].line, 1);
+        assert_eq!(context.changed, Some(false));
+        let anchored: Vec<usize> = context.lines.iter().filter(|l| l.anchored).map(|l| l.line).collect();
+        assert_eq!(anchored, vec![2, 3]);
+
+        let edited = code_context(&comment(Some((2, 3)), Some("two\n3")), source, 0).unwrap();
+        assert_eq!(edited.changed, Some(true));
+        // Lines past the end of a shrunken file
+        let gone = code_context(&comment(Some((9, 9)), Some("nine")), source, 2).unwrap();
+        assert!(gone.lines.is_empty());
+        assert_eq!(gone.changed, Some(true));
+
+        assert_eq!(code_context(&comment(Some((1, 1)), None), source, 0).unwrap().changed, None);
+        assert!(code_context(&comment(None, None), source, 3).is_none());
+    }
+
+    #[test]
+    fn lines_are_clipped_to_the_file() {
+        let source = "one\r\ntwo\r\nthree";
+        assert_eq!(anchor_text(source, 1, 2).as_deref(), Some("one\ntwo"));
+        assert_eq!(anchor_text(source, 3, 3).as_deref(), Some("three"));
+        for (start, end) in [(0, 1), (3, 2), (3, 4)] {
+            assert_eq!(anchor_text(source, start, end), None, "{}-{}", start, end);
+        }
+
+        let numbers = |lines: Vec<ContextLine>| lines.iter().map(|l| l.line).collect::<Vec<_>>();
+        assert_eq!(numbers(lines_around(source, 1, 1, 5)), vec![1, 2, 3]);
+        assert_eq!(numbers(lines_around(source, 3, 3, 1)), vec![2, 3]);
+        // A range running past the end keeps the lines that are left
+        let partial = lines_around(source, 2, 6, 0);
+        assert_eq!(numbers(partial.clone()), vec![2, 3]);
+        assert!(partial.iter().all(|l| l.anchored));
+        assert!(lines_around("", 1, 1, 2).is_empty());
+    }
+}
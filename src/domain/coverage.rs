@@ -0,0 +1,296 @@
+//! Test coverage of the lines a branch adds, from an lcov or Cobertura report.
+//!
+//! Report paths are made relative to the repository root so they line up with
+//! diff paths: lcov tools usually write absolute paths, and Cobertura names
+//! files relative to its `<source>` directory.
+
+use super::types::Diff;
+use anyhow::{bail, Result};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Hit counts per line (1-based) per file (repository-relative).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Coverage {
+    files: HashMap<String, HashMap<usize, u64>>,
+}
+
+/// What the report says about one line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineCoverage {
+    Covered,
+    Uncovered,
+    /// The report has no data for the line (e.g. blank lines, comments, or
+    /// files the tests never loaded)
+    NotInstrumented,
+}
+
+/// How many of a file's added lines are covered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileCoverage {
+    pub covered: usize,
+    /// Added lines the report has data for
+    pub instrumented: usize,
+}
+
+impl FileCoverage {
+    /// Covered share of instrumented lines, rounded down so that 100 means all.
+    pub fn percent(&self) -> usize {
+        if self.instrumented == 0 {
+            return 100;
+        }
+        self.covered * 100 / self.instrumented
+    }
+}
+
+impl Coverage {
+    /// Parse an lcov tracefile or a Cobertura XML report, telling them apart
+    /// by content. `root` is the repository root.
+    pub fn parse(text: &str, root: &Path) -> Result<Self> {
+        let coverage = if text.trim_start().starts_with('<') {
+            Self::parse_cobertura(text, root)
+        } else {
+            Self::parse_lcov(text, root)
+        };
+        if coverage.files.is_empty() {
+            bail!("No line coverage found (expected an lcov tracefile or a Cobertura XML report)");
+        }
+        Ok(coverage)
+    }
+
+    fn parse_lcov(text: &str, root: &Path) -> Self {
+        let mut coverage = Self::default();
+        let mut file: Option<String> = None;
+        for line in text.lines().map(str::trim) {
+            if let Some(path) = line.strip_prefix("SF:") {
+                file = Some(relative_path(Path::new(path), root));
+            } else if line == "end_of_record" {
+                file = None;
+            } else if let (Some(path), Some(data)) = (&file, line.strip_prefix("DA:")) {
+                // DA:<line>,<hits>[,<checksum>]
+                let mut fields = data.split(',');
+                let number = fields.next().and_then(|n| n.trim().parse().ok());
+                let hits = fields.next().and_then(|n| n.trim().parse().ok());
+                if let (Some(number), Some(hits)) = (number, hits) {
+                    coverage.add(path, number, hits);
+                }
+            }
+        }
+        coverage
+    }
+
+    fn parse_cobertura(text: &str, root: &Path) -> Self {
+        let mut coverage = Self::default();
+        let mut sources = Vec::new();
+        let mut file: Option<String> = None;
+        let mut rest = text;
+        while let Some(start) = rest.find('<') {
+            let Some(end) = rest[start..].find('>') else { break };
+            let tag = &rest[start + 1..start + end];
+            rest = &rest[start + end + 1..];
+
+            match tag.split_whitespace().next().unwrap_or_default() {
+                "source" => {
+                    let text = rest[..rest.find('<').unwrap_or(rest.len())].trim();
+                    if !text.is_empty() {
+                        sources.push(unescape_xml(text));
+                    }
+                }
+                "class" => {
+                    file = attribute(tag, "filename").map(|name| {
+                        let name = unescape_xml(name);
+                        let path = match sources.first() {
+                            Some(source) => Path::new(source).join(name),
+                            None => name.into(),
+                        };
+                        relative_path(&path, root)
+                    });
+                }
+                "/class" => file = None,
+                "line" => {
+                    let number = attribute(tag, "number").and_then(|n| n.parse().ok());
+                    let hits = attribute(tag, "hits").and_then(|n| n.parse().ok());
+                    if let (Some(path), Some(number), Some(hits)) = (&file, number, hits) {
+                        coverage.add(path, number, hits);
+                    }
+                }
+                _ => {}
+            }
+        }
+        coverage
+    }
+
+    /// Record hits for a line. Lines listed more than once (e.g. a Cobertura
+    /// class and its methods) keep the highest count.
+    fn add(&mut self, path: &str, line: usize, hits: u64) {
+        let count = self.files.entry(path.to_string()).or_default().entry(line).or_insert(0);
+        *count = (*count).max(hits);
+    }
+
+    /// Coverage of line `line` of `path`.
+    pub fn line(&self, path: &str, line: usize) -> LineCoverage {
+        match self.files.get(path).and_then(|lines| lines.get(&line)) {
+            Some(0) => LineCoverage::Uncovered,
+            Some(_) => LineCoverage::Covered,
+            None => LineCoverage::NotInstrumented,
+        }
+    }
+}
+
+/// Coverage of a diff's added lines, indexed like `Diff::files`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffCoverage {
+    files: Vec<HashMap<usize, LineCoverage>>,
+}
+
+impl DiffCoverage {
+    pub fn new(coverage: &Coverage, diff: &Diff) -> Self {
+        let files = diff
+            .files
+            .iter()
+            .map(|file| {
+                file.added_lines()
+                    .into_iter()
+                    .map(|line| (line, coverage.line(&file.path, line)))
+                    .collect()
+            })
+            .collect();
+        Self { files }
+    }
+
+    /// Coverage of new-side line `line` of a file, if the diff added it.
+    pub fn line(&self, file_index: usize, line: usize) -> Option<LineCoverage> {
+        self.files.get(file_index)?.get(&line).copied()
+    }
+
+    /// Summary of a file's added lines, if the report has data for any.
+    pub fn file(&self, file_index: usize) -> Option<FileCoverage> {
+        let lines = self.files.get(file_index)?;
+        let count = |kind: LineCoverage| lines.values().filter(|&&c| c == kind).count();
+        let covered = count(LineCoverage::Covered);
+        let instrumented = covered + count(LineCoverage::Uncovered);
+        (instrumented > 0).then_some(FileCoverage { covered, instrumented })
+    }
+
+    /// Uncovered added lines in diff order: (file index, line).
+    pub fn uncovered_lines(&self) -> Vec<(usize, usize)> {
+        let mut lines: Vec<_> = self
+            .files
+            .iter()
+            .enumerate()
+            .flat_map(|(file_index, lines)| {
+                lines
+                    .iter()
+                    .filter(|(_, &c)| c == LineCoverage::Uncovered)
+                    .map(move |(&line, _)| (file_index, line))
+            })
+            .collect();
+        lines.sort_unstable();
+        lines
+    }
+}
+
+/// `path` relative to `root` with `/` separators, or as written if it is
+/// outside the repository.
+fn relative_path(path: &Path, root: &Path) -> String {
+    let path = path.strip_prefix(root).unwrap_or(path);
+    let path = path.strip_prefix(".").unwrap_or(path);
+    path.to_string_lossy().replace('\\', "/")
+}
+
+/// Value of attribute `name` in the inside of an XML tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let mut rest = tag;
+    loop {
+        let at = rest.find(name)?;
+        let before = rest[..at].chars().next_back();
+        let after = rest[at + name.len()..].trim_start();
+        rest = &rest[at + name.len()..];
+        if before.is_some_and(char::is_whitespace) {
+            if let Some(value) = after.strip_prefix('=') {
+                let value = value.trim_start();
+                let quote = value.chars().next().filter(|&q| q == '"' || q == '\'')?;
+                let value = &value[1..];
+                return value.find(quote).map(|end| &value[..end]);
+            }
+        }
+    }
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DiffLine, DiffStats, FileDiff, Hunk};
+
+    const LCOV: &str = "TN:\nSF:/repo/src/lib.rs\nDA:1,4\nDA:2,0\nDA:3,1,abc\nend_of_record\n";
+
+    const COBERTURA: &str = r#"<?xml version="1.0" ?>
+<coverage line-rate="0.5">
+  <sources><source>/repo/src</source></sources>
+  <packages><package name="app"><classes>
+    <class name="lib" filename="lib.rs" line-rate="0.5">
+      <methods><method name="f"><lines><line number="1" hits="4"/></lines></method></methods>
+      <lines>
+        <line number="1" hits="4"/>
+        <line number="2" hits="0" branch="false"/>
+        <line number="3" hits="1"/>
+      </lines>
+    </class>
+  </classes></package></packages>
+</coverage>"#;
+
+    #[test]
+    fn reads_lcov_and_cobertura_alike() {
+        let root = Path::new("/repo");
+        let lcov = Coverage::parse(LCOV, root).unwrap();
+        assert_eq!(lcov, Coverage::parse(COBERTURA, root).unwrap());
+        assert_eq!(lcov.line("src/lib.rs", 1), LineCoverage::Covered);
+        assert_eq!(lcov.line("src/lib.rs", 2), LineCoverage::Uncovered);
+        assert_eq!(lcov.line("src/lib.rs", 4), LineCoverage::NotInstrumented);
+        assert_eq!(lcov.line("src/main.rs", 1), LineCoverage::NotInstrumented);
+        assert!(Coverage::parse("not a report", root).is_err());
+    }
+
+    #[test]
+    fn summarizes_added_lines() {
+        let coverage = Coverage::parse(LCOV, Path::new("/repo")).unwrap();
+        let diff = Diff {
+            files: vec![FileDiff {
+                path: "src/lib.rs".to_string(),
+                old_path: None,
+                hunks: vec![Hunk {
+                    old_start: 1,
+                    old_lines: 2,
+                    new_start: 1,
+                    new_lines: 4,
+                    lines: vec![
+                        DiffLine::Context("a".to_string()),
+                        DiffLine::Addition("b".to_string()),
+                        DiffLine::Addition("c".to_string()),
+                        DiffLine::Addition("d".to_string()),
+                        DiffLine::Deletion("e".to_string()),
+                    ],
+                }],
+                stats: DiffStats { additions: 3, deletions: 1 },
+                is_binary: false,
+            }],
+        };
+
+        let diff_coverage = DiffCoverage::new(&coverage, &diff);
+        // Context lines are not part of the overlay
+        assert_eq!(diff_coverage.line(0, 1), None);
+        assert_eq!(diff_coverage.line(0, 2), Some(LineCoverage::Uncovered));
+        assert_eq!(diff_coverage.line(0, 4), Some(LineCoverage::NotInstrumented));
+        assert_eq!(diff_coverage.file(0), Some(FileCoverage { covered: 1, instrumented: 2 }));
+        assert_eq!(diff_coverage.file(0).unwrap().percent(), 50);
+        assert_eq!(diff_coverage.uncovered_lines(), vec![(0, 2)]);
+    }
+}
@@ -1,4 +1,11 @@
 pub mod branch_preview;
+pub mod context;
+pub mod coverage;
+pub mod policy;
+pub mod progress;
+pub mod query;
+pub mod review;
+pub mod snapshot;
 pub mod types;
 
 pub use types::*;
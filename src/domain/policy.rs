@@ -0,0 +1,158 @@
+//! Agent policy: what coding agents and tools may do to comments people wrote.
+//!
+//! The policy only restricts non-human actors acting on human comments; people
+//! can do anything, and agents keep full control of agent and tool comments.
+//! Callers that don't say what they are count as agents for restricted
+//! actions, since nothing stops an agent from running the CLI without saying
+//! so; the CLI takes someone at a terminal to be a person.
+//! No I/O - the CLI and MCP server look up the comment and enforce the result.
+
+use super::types::{AuthorKind, CommentStatus};
+
+/// How much agents may change people's comments, set with
+/// `git config panko.agentPolicy`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Policy {
+    /// No restrictions
+    Open,
+    /// Reply, claim, and mark in progress or addressed; closing and deleting
+    /// is left to people
+    #[default]
+    Guarded,
+    /// Reply only
+    ReplyOnly,
+}
+
+/// Something an actor does to an existing comment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentAction {
+    Reply,
+    /// Assign to oneself
+    Claim,
+    SetStatus(CommentStatus),
+    Delete,
+}
+
+impl Policy {
+    pub const ALL: [Policy; 3] = [Policy::Open, Policy::Guarded, Policy::ReplyOnly];
+
+    /// Stable identifier used in git config.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Policy::Open => "open",
+            Policy::Guarded => "guarded",
+            Policy::ReplyOnly => "reply-only",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    /// Whether `actor` may do `action` to a comment written by `author`.
+    /// The error says why not.
+    pub fn check(self, actor: AuthorKind, action: CommentAction, author: AuthorKind) -> Result<(), String> {
+        if actor.is_human() || !author.is_human() {
+            return Ok(());
+        }
+        let allowed = match self {
+            Policy::Open => true,
+            Policy::Guarded => matches!(
+                action,
+                CommentAction::Reply
+                    | CommentAction::Claim
+                    | CommentAction::SetStatus(CommentStatus::InProgress | CommentStatus::Addressed)
+            ),
+            Policy::ReplyOnly => action == CommentAction::Reply,
+        };
+        if allowed {
+            return Ok(());
+        }
+        let what = match action {
+            CommentAction::Reply => "reply to".to_string(),
+            CommentAction::Claim => "claim".to_string(),
+            CommentAction::SetStatus(status) => format!("mark as {}", status.as_str()),
+            CommentAction::Delete => "delete".to_string(),
+        };
+        let hint = match self {
+            Policy::Guarded => "; mark it addressed and leave closing it to a person",
+            _ => "",
+        };
+        Err(format!(
+            "The {} agent policy does not let {}s {} a person's comment{}",
+            self.as_str(),
+            actor.as_str(),
+            what,
+            hint
+        ))
+    }
+}
+
+/// Who is acting, and under which policy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Actor {
+    pub name: String,
+    /// What the actor writes as
+    pub kind: AuthorKind,
+    /// Whether the caller said what it is (see the module docs)
+    pub declared: bool,
+    pub policy: Policy,
+}
+
+impl Actor {
+    /// See `Policy::check`.
+    pub fn check(&self, action: CommentAction, author: AuthorKind) -> Result<(), String> {
+        if self.declared {
+            return self.policy.check(self.kind, action, author);
+        }
+        self.policy.check(AuthorKind::Agent, action, author).map_err(|message| {
+            format!("{} (set PANKO_AUTHOR_KIND=human if a person is running panko)", message)
+        })
+    }
+
+    /// Whether the policy lets this actor do anything, so callers can skip
+    /// looking up who wrote a comment.
+    pub fn unrestricted(&self) -> bool {
+        self.policy == Policy::Open || (self.declared && self.kind.is_human())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn guards_human_comments_from_agents() {
+        use CommentAction::*;
+        let (human, agent, tool) = (AuthorKind::Human, AuthorKind::Agent, AuthorKind::Tool);
+        let resolve = SetStatus(CommentStatus::Resolved);
+        let addressed = SetStatus(CommentStatus::Addressed);
+
+        for action in [Reply, Claim, addressed, SetStatus(CommentStatus::InProgress)] {
+            assert!(Policy::Guarded.check(agent, action, human).is_ok());
+        }
+        for action in [resolve, SetStatus(CommentStatus::WontFix), SetStatus(CommentStatus::Open), Delete] {
+            assert!(Policy::Guarded.check(agent, action, human).is_err());
+            assert!(Policy::Guarded.check(tool, action, human).is_err());
+        }
+        // Agents and tools' own comments, and people, are never restricted
+        assert!(Policy::ReplyOnly.check(agent, Delete, agent).is_ok());
+        assert!(Policy::ReplyOnly.check(agent, resolve, tool).is_ok());
+        assert!(Policy::ReplyOnly.check(human, Delete, human).is_ok());
+        assert!(Policy::ReplyOnly.check(agent, addressed, human).is_err());
+        assert!(Policy::Open.check(agent, Delete, human).is_ok());
+
+        assert_eq!(Policy::parse("Reply-Only"), Some(Policy::ReplyOnly));
+        assert_eq!(Policy::parse("strict"), None);
+
+        // Callers that don't say what they are write as people but act as agents
+        let unknown = Actor { name: "bob".to_string(), kind: human, declared: false, policy: Policy::Guarded };
+        assert!(unknown.check(Reply, human).is_ok());
+        assert!(unknown.check(resolve, agent).is_ok());
+        assert!(unknown.check(resolve, human).unwrap_err().contains("PANKO_AUTHOR_KIND=human"));
+        assert!(!unknown.unrestricted());
+        assert!(Actor { declared: true, ..unknown.clone() }.check(Delete, human).is_ok());
+        assert!(Actor { policy: Policy::Open, ..unknown }.unrestricted());
+    }
+}
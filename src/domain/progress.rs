@@ -0,0 +1,236 @@
+//! Review progress: viewed files and comment counts for a branch, as reported
+//! by `panko status` and gated on by `panko check`.
+//! No I/O - all functions are data in, data out.
+
+use super::types::{Comment, Diff, Severity};
+use serde::Serialize;
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ReviewProgress {
+    /// Changed files marked as viewed
+    pub viewed_files: usize,
+    /// Changed files not yet viewed, in diff order
+    pub unviewed_files: Vec<String>,
+    pub comments: CommentCounts,
+    /// Files with unresolved threads, by path
+    pub unresolved_files: Vec<FileThreads>,
+}
+
+/// Open (open, in progress, addressed) and resolved (resolved, won't fix) comments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommentCounts {
+    pub open: usize,
+    pub resolved: usize,
+    /// One entry per severity, blocking first, then comments without one
+    pub by_severity: Vec<SeverityCounts>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SeverityCounts {
+    pub severity: Option<Severity>,
+    pub open: usize,
+    pub resolved: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FileThreads {
+    pub path: String,
+    pub unresolved: usize,
+}
+
+impl CommentCounts {
+    /// Open comments with a given severity (None = no severity).
+    pub fn open_with(&self, severity: Option<Severity>) -> usize {
+        self.by_severity
+            .iter()
+            .find(|c| c.severity == severity)
+            .map_or(0, |c| c.open)
+    }
+}
+
+/// Progress of a review: `viewed` holds the paths marked viewed on the branch,
+/// and `comments` its published comments.
+pub fn review_progress(diff: &Diff, viewed: &[&str], comments: &[Comment]) -> ReviewProgress {
+    let unviewed_files: Vec<String> = diff
+        .files
+        .iter()
+        .filter(|f| !viewed.contains(&f.path.as_str()))
+        .map(|f| f.path.clone())
+        .collect();
+
+    let severities = Severity::ALL.into_iter().map(Some).chain([None]);
+    let by_severity = severities
+        .map(|severity| {
+            let with: Vec<&Comment> = comments.iter().filter(|c| c.severity == severity).collect();
+            let open = with.iter().filter(|c| !c.status.is_closed()).count();
+            SeverityCounts {
+                severity,
+                open,
+                resolved: with.len() - open,
+            }
+        })
+        .collect();
+    let open = comments.iter().filter(|c| !c.status.is_closed()).count();
+
+    let mut unresolved: BTreeMap<&str, usize> = BTreeMap::new();
+    for comment in comments.iter().filter(|c| !c.status.is_closed()) {
+        if let Some(path) = &comment.file_path {
+            *unresolved.entry(path).or_default() += 1;
+        }
+    }
+
+    ReviewProgress {
+        viewed_files: diff.files.len() - unviewed_files.len(),
+        unviewed_files,
+        comments: CommentCounts {
+            open,
+            resolved: comments.len() - open,
+            by_severity,
+        },
+        unresolved_files: unresolved
+            .into_iter()
+            .map(|(path, unresolved)| FileThreads {
+                path: path.to_string(),
+                unresolved,
+            })
+            .collect(),
+    }
+}
+
+/// What `panko check` requires of a review.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CheckRules {
+    /// Only unresolved blocking comments fail the check
+    pub blocking_only: bool,
+    /// Every changed file must be viewed
+    pub require_viewed: bool,
+}
+
+/// A reason the review is not finished.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "reason", rename_all = "snake_case")]
+pub enum CheckFailure {
+    UnresolvedComments { count: usize, blocking_only: bool },
+    UnviewedFiles { files: Vec<String> },
+}
+
+impl CheckFailure {
+    pub fn message(&self) -> String {
+        match self {
+            CheckFailure::UnresolvedComments { count, blocking_only } => format!(
+                "{} unresolved {}comment{}",
+                count,
+                if *blocking_only { "blocking " } else { "" },
+                if *count == 1 { "" } else { "s" }
+            ),
+            CheckFailure::UnviewedFiles { files } => format!(
+                "{} unviewed file{}",
+                files.len(),
+                if files.len() == 1 { "" } else { "s" }
+            ),
+        }
+    }
+}
+
+/// Everything keeping the review from passing; empty when it passes.
+pub fn check(progress: &ReviewProgress, rules: CheckRules) -> Vec<CheckFailure> {
+    let mut failures = Vec::new();
+    let count = if rules.blocking_only {
+        progress.comments.open_with(Some(Severity::Blocking))
+    } else {
+        progress.comments.open
+    };
+    if count > 0 {
+        failures.push(CheckFailure::UnresolvedComments {
+            count,
+            blocking_only: rules.blocking_only,
+        });
+    }
+    if rules.require_viewed && !progress.unviewed_files.is_empty() {
+        failures.push(CheckFailure::UnviewedFiles {
+            files: progress.unviewed_files.clone(),
+        });
+    }
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{CommentStatus, DiffStats, FileDiff};
+
+    fn file(path: &str) -> FileDiff {
+        FileDiff {
+            path: path.to_string(),
+            old_path: None,
+            hunks: vec![],
+            stats: DiffStats::new(1, 0),
+            is_binary: false,
+        }
+    }
+
+    fn comment(path: Option<&str>, severity: Option<Severity>, status: CommentStatus) -> Comment {
+        Comment { id: 0, status, severity, ..Comment::test(path, None) }
+    }
+
+    #[test]
+    fn counts_viewed_files_and_unresolved_threads() {
+        let diff = Diff {
+            files: vec![file("src/b.rs"), file("src/a.rs"), file("README.md")],
+        };
+        let comments = vec![
+            comment(Some("src/b.rs"), Some(Severity::Blocking), CommentStatus::Open),
+            comment(Some("src/b.rs"), None, CommentStatus::InProgress),
+            comment(Some("src/a.rs"), Some(Severity::Nit), CommentStatus::WontFix),
+            comment(None, Some(Severity::Blocking), CommentStatus::Resolved),
+        ];
+
+        // A viewed file that is no longer in the diff doesn't count
+        let progress = review_progress(&diff, &["src/a.rs", "gone.rs"], &comments);
+        assert_eq!(progress.viewed_files, 1);
+        assert_eq!(progress.unviewed_files, vec!["src/b.rs", "README.md"]);
+        assert_eq!((progress.comments.open, progress.comments.resolved), (2, 2));
+        assert_eq!(progress.comments.open_with(Some(Severity::Blocking)), 1);
+        assert_eq!(progress.comments.open_with(None), 1);
+        assert_eq!(progress.comments.by_severity.len(), Severity::ALL.len() + 1);
+        assert_eq!(
+            progress.unresolved_files,
+            vec![FileThreads { path: "src/b.rs".to_string(), unresolved: 2 }]
+        );
+
+        assert_eq!(
+            check(&progress, CheckRules::default()),
+            vec![CheckFailure::UnresolvedComments { count: 2, blocking_only: false }]
+        );
+        let strict = CheckRules { blocking_only: true, require_viewed: true };
+        assert_eq!(check(&progress, strict).len(), 2);
+        assert_eq!(check(&progress, strict)[1].message(), "2 unviewed files");
+        let done = review_progress(&diff, &["src/a.rs", "src/b.rs", "README.md"], &[]);
+        assert!(check(&done, strict).is_empty());
+    }
+
+    #[test]
+    fn addressed_comments_still_fail_the_check_until_closed() {
+        let diff = Diff { files: vec![file("src/a.rs")] };
+        let comments = vec![
+            comment(Some("src/a.rs"), Some(Severity::Suggestion), CommentStatus::Addressed),
+            comment(Some("src/a.rs"), Some(Severity::Blocking), CommentStatus::WontFix),
+        ];
+        let progress = review_progress(&diff, &["src/a.rs"], &comments);
+
+        let failures = check(&progress, CheckRules::default());
+        assert_eq!(failures, vec![CheckFailure::UnresolvedComments { count: 1, blocking_only: false }]);
+        assert_eq!(failures[0].message(), "1 unresolved comment");
+        assert_eq!(
+            serde_json::to_value(&failures[0]).unwrap(),
+            serde_json::json!({"reason": "unresolved_comments", "count": 1, "blocking_only": false})
+        );
+        // Declining a blocking comment settles it; a suggestion doesn't block
+        let blocking = CheckRules { blocking_only: true, require_viewed: true };
+        assert!(check(&progress, blocking).is_empty());
+
+        let unviewed = review_progress(&diff, &[], &[]);
+        assert_eq!(check(&unviewed, blocking)[0].message(), "1 unviewed file");
+    }
+}
@@ -0,0 +1,224 @@
+//! Comment queries: the filters and orderings behind `panko comments`.
+
+use super::types::{Comment, CommentStatus, Severity};
+use chrono::{DateTime, NaiveDate};
+
+/// Which comments to list. Empty fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct CommentQuery {
+    /// None = any status
+    pub statuses: Option<Vec<CommentStatus>>,
+    pub severity: Option<Severity>,
+    /// All of these labels must be present
+    pub labels: Vec<String>,
+    /// Glob over the file path (see `glob_match`)
+    pub file: Option<String>,
+    /// Any of these authors
+    pub authors: Vec<String>,
+    /// Created at or after (Unix ms)
+    pub since: Option<i64>,
+    /// Created at or before (Unix ms)
+    pub until: Option<i64>,
+    pub has_replies: bool,
+    /// Only threads whose last message is not by this person
+    pub unreplied_by: Option<String>,
+    /// Any of these IDs
+    pub ids: Vec<i64>,
+}
+
+impl CommentQuery {
+    pub fn matches(&self, comment: &Comment) -> bool {
+        let last_author = comment.replies.last().map_or(&comment.author, |r| &r.author);
+        self.statuses.as_ref().is_none_or(|s| s.contains(&comment.status))
+            && (self.severity.is_none() || comment.severity == self.severity)
+            && self.labels.iter().all(|l| comment.labels.contains(l))
+            && self.file.as_deref().is_none_or(|pattern| {
+                comment.file_path.as_deref().is_some_and(|path| glob_match(pattern, path))
+            })
+            && (self.authors.is_empty() || self.authors.contains(&comment.author))
+            && self.since.is_none_or(|t| comment.created_at >= t)
+            && self.until.is_none_or(|t| comment.created_at <= t)
+            && (!self.has_replies || !comment.replies.is_empty())
+            && self.unreplied_by.as_ref().is_none_or(|me| last_author != me)
+            && (self.ids.is_empty() || self.ids.contains(&comment.id))
+    }
+}
+
+/// Orderings for comment lists; all ascending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentSort {
+    /// Branch comments first, then by path and line (the store's order)
+    File,
+    Created,
+    /// Last workflow change (status, assignee)
+    Updated,
+    /// Blocking first; comments without a severity last
+    Severity,
+    /// Workflow order: open, in progress, won't fix, resolved
+    Status,
+}
+
+impl CommentSort {
+    pub const ALL: [CommentSort; 5] = [
+        CommentSort::File,
+        CommentSort::Created,
+        CommentSort::Updated,
+        CommentSort::Severity,
+        CommentSort::Status,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommentSort::File => "file",
+            CommentSort::Created => "created",
+            CommentSort::Updated => "updated",
+            CommentSort::Severity => "severity",
+            CommentSort::Status => "status",
+        }
+    }
+
+    /// Stable sort, so ties keep the store's order.
+    pub fn sort(self, comments: &mut [&Comment]) {
+        match self {
+            CommentSort::File => comments.sort_by(|a, b| {
+                (a.file_path.is_some(), &a.file_path, a.line_range, a.created_at)
+                    .cmp(&(b.file_path.is_some(), &b.file_path, b.line_range, b.created_at))
+            }),
+            CommentSort::Created => comments.sort_by_key(|c| c.created_at),
+            CommentSort::Updated => comments.sort_by_key(|c| c.updated_at),
+            CommentSort::Severity => comments.sort_by_key(|c| (c.severity.is_none(), c.severity)),
+            CommentSort::Status => comments
+                .sort_by_key(|c| CommentStatus::ALL.iter().position(|s| *s == c.status)),
+        }
+    }
+}
+
+/// Match a path against a glob: `*` and `?` stay within one path segment,
+/// `**` spans directories. Patterns without a `/` match the file name in any
+/// directory, so `*.rs` finds Rust files everywhere.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    fn matches(p: &[char], s: &[char]) -> bool {
+        match p.first() {
+            None => s.is_empty(),
+            Some('*') if p.get(1) == Some(&'*') => {
+                let rest = &p[2..];
+                // "**/" also matches no directories at all
+                (rest.first() == Some(&'/') && matches(&rest[1..], s))
+                    || (0..=s.len()).any(|i| matches(rest, &s[i..]))
+            }
+            Some('*') => (0..=s.len())
+                .take_while(|&i| i == 0 || s[i - 1] != '/')
+                .any(|i| matches(&p[1..], &s[i..])),
+            Some('?') => s.first().is_some_and(|&c| c != '/') && matches(&p[1..], &s[1..]),
+            Some(c) => s.first() == Some(c) && matches(&p[1..], &s[1..]),
+        }
+    }
+
+    let target = if pattern.contains('/') {
+        path
+    } else {
+        path.rsplit('/').next().unwrap_or(path)
+    };
+    let p: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = target.chars().collect();
+    matches(&p, &s)
+}
+
+/// Parse a point in time as Unix ms: RFC 3339, a `YYYY-MM-DD` date (UTC
+/// midnight), or an age before `now_ms` such as `30m`, `12h`, `3d` or `2w`.
+pub fn parse_time(s: &str, now_ms: i64) -> Option<i64> {
+    let s = s.trim();
+    if let Some((at, unit)) = s.char_indices().last() {
+        let amount = &s[..at];
+        if !amount.is_empty() && amount.bytes().all(|b| b.is_ascii_digit()) {
+            let minutes = match unit {
+                'm' => 1,
+                'h' => 60,
+                'd' => 60 * 24,
+                'w' => 60 * 24 * 7,
+                _ => return None,
+            };
+            return Some(now_ms - amount.parse::<i64>().ok()? * minutes * 60_000);
+        }
+    }
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Some(date.and_hms_opt(0, 0, 0)?.and_utc().timestamp_millis());
+    }
+    DateTime::parse_from_rfc3339(s).ok().map(|t| t.timestamp_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AuthorKind, Reply};
+
+    fn comment(id: i64, path: Option<&str>, author: &str, created_at: i64) -> Comment {
+        Comment {
+            id,
+            uuid: id.to_string(),
+            author: author.to_string(),
+            created_at,
+            updated_at: created_at,
+            ..Comment::test(path, path.map(|_| 1))
+        }
+    }
+
+    fn reply(author: &str) -> Reply {
+        Reply {
+            id: 0,
+            uuid: String::new(),
+            comment_id: 0,
+            body: String::new(),
+            author: author.to_string(),
+            author_kind: AuthorKind::Human,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn globs_follow_path_segments() {
+        assert!(glob_match("src/**/*.rs", "src/ui/diff_view.rs"));
+        assert!(glob_match("src/**/*.rs", "src/main.rs"));
+        assert!(!glob_match("src/*.rs", "src/ui/diff_view.rs"));
+        assert!(glob_match("*.rs", "src/ui/diff_view.rs"));
+        assert!(glob_match("src/?ain.rs", "src/main.rs"));
+        assert!(!glob_match("docs/**", "src/main.rs"));
+    }
+
+    #[test]
+    fn parses_times() {
+        let now = 1_000_000_000_000;
+        assert_eq!(parse_time("2h", now), Some(now - 2 * 3_600_000));
+        assert_eq!(parse_time("1970-01-02", now), Some(86_400_000));
+        assert_eq!(parse_time("1970-01-01T00:00:01Z", now), Some(1000));
+        assert_eq!(parse_time("3y", now), None);
+        assert_eq!(parse_time("yesterday", now), None);
+    }
+
+    #[test]
+    fn filters_combine() {
+        let mut answered = comment(2, Some("src/db.rs"), "alice", 200);
+        answered.replies.push(reply("bob"));
+        let mut followed_up = comment(3, Some("README.md"), "bob", 300);
+        followed_up.replies.push(reply("alice"));
+        let comments = [comment(1, None, "alice", 100), answered, followed_up];
+
+        let ids = |query: CommentQuery| -> Vec<i64> {
+            comments.iter().filter(|c| query.matches(c)).map(|c| c.id).collect()
+        };
+        assert_eq!(ids(CommentQuery::default()), vec![1, 2, 3]);
+        assert_eq!(ids(CommentQuery { file: Some("*.rs".into()), ..Default::default() }), vec![2]);
+        assert_eq!(ids(CommentQuery { authors: vec!["bob".into()], ..Default::default() }), vec![3]);
+        assert_eq!(ids(CommentQuery { since: Some(150), until: Some(250), ..Default::default() }), vec![2]);
+        assert_eq!(ids(CommentQuery { has_replies: true, ..Default::default() }), vec![2, 3]);
+        // Waiting on bob: threads where someone else spoke last
+        assert_eq!(ids(CommentQuery { unreplied_by: Some("bob".into()), ..Default::default() }), vec![1, 3]);
+        assert_eq!(ids(CommentQuery { ids: vec![3, 1], ..Default::default() }), vec![1, 3]);
+
+        let mut sorted: Vec<&Comment> = comments.iter().rev().collect();
+        CommentSort::File.sort(&mut sorted);
+        assert_eq!(sorted.iter().map(|c| c.id).collect::<Vec<_>>(), vec![1, 3, 2]);
+        CommentSort::Created.sort(&mut sorted);
+        assert_eq!(sorted.iter().map(|c| c.id).collect::<Vec<_>>(), vec![1, 2, 3]);
+    }
+}
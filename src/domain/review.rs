@@ -0,0 +1,133 @@
+//! Review aggregation: derive a branch's overall review state from submitted reviews.
+
+use super::types::{Review, ReviewVerdict};
+use serde::Serialize;
+
+/// Overall review state of a branch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewStatus {
+    /// No reviews submitted yet
+    None,
+    /// Reviews submitted, but nobody approved or requested changes
+    Commented,
+    /// At least one approval and no outstanding change requests
+    Approved,
+    /// Some reviewer's latest decision is "request changes"
+    ChangesRequested,
+}
+
+impl ReviewStatus {
+    /// Stable identifier used in JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReviewStatus::None => "none",
+            ReviewStatus::Commented => "commented",
+            ReviewStatus::Approved => "approved",
+            ReviewStatus::ChangesRequested => "changes_requested",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReviewStatus::None => "No reviews",
+            ReviewStatus::Commented => "Commented",
+            ReviewStatus::Approved => "Approved",
+            ReviewStatus::ChangesRequested => "Changes requested",
+        }
+    }
+}
+
+impl Serialize for ReviewStatus {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+/// Each reviewer's deciding review, in first-review order.
+///
+/// Like GitHub, a comment-only review doesn't override an earlier approval
+/// or change request from the same reviewer.
+pub fn latest_by_author(reviews: &[Review]) -> Vec<&Review> {
+    let mut latest: Vec<&Review> = Vec::new();
+    for review in reviews.iter().filter(|r| !r.is_pending()) {
+        match latest.iter_mut().find(|r| r.author == review.author) {
+            Some(slot) => {
+                let decides = review.verdict != Some(ReviewVerdict::Comment)
+                    || slot.verdict == Some(ReviewVerdict::Comment);
+                if decides {
+                    *slot = review;
+                }
+            }
+            None => latest.push(review),
+        }
+    }
+    latest
+}
+
+/// Overall status: any outstanding change request wins, then approvals.
+pub fn review_status(reviews: &[Review]) -> ReviewStatus {
+    let latest = latest_by_author(reviews);
+    let has = |verdict| latest.iter().any(|r| r.verdict == Some(verdict));
+
+    if latest.is_empty() {
+        ReviewStatus::None
+    } else if has(ReviewVerdict::RequestChanges) {
+        ReviewStatus::ChangesRequested
+    } else if has(ReviewVerdict::Approve) {
+        ReviewStatus::Approved
+    } else {
+        ReviewStatus::Commented
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn review(id: i64, author: &str, verdict: ReviewVerdict) -> Review {
+        Review {
+            id,
+            uuid: id.to_string(),
+            author: author.to_string(),
+            body: String::new(),
+            verdict: Some(verdict),
+            created_at: id,
+            submitted_at: Some(id),
+        }
+    }
+
+    #[test]
+    fn empty_reviews_have_no_status() {
+        assert_eq!(review_status(&[]), ReviewStatus::None);
+    }
+
+    #[test]
+    fn change_request_wins_over_approval() {
+        let reviews = [
+            review(1, "alice", ReviewVerdict::Approve),
+            review(2, "bob", ReviewVerdict::RequestChanges),
+        ];
+        assert_eq!(review_status(&reviews), ReviewStatus::ChangesRequested);
+    }
+
+    #[test]
+    fn later_approval_clears_own_change_request() {
+        let reviews = [
+            review(1, "bob", ReviewVerdict::RequestChanges),
+            review(2, "bob", ReviewVerdict::Approve),
+        ];
+        assert_eq!(review_status(&reviews), ReviewStatus::Approved);
+    }
+
+    #[test]
+    fn comment_review_keeps_earlier_decision() {
+        let reviews = [
+            review(1, "bob", ReviewVerdict::RequestChanges),
+            review(2, "bob", ReviewVerdict::Comment),
+            review(3, "alice", ReviewVerdict::Comment),
+        ];
+        let latest = latest_by_author(&reviews);
+        assert_eq!(latest.iter().map(|r| r.id).collect::<Vec<_>>(), vec![1, 3]);
+        assert_eq!(review_status(&reviews), ReviewStatus::ChangesRequested);
+    }
+}
@@ -0,0 +1,223 @@
+//! Branch snapshot: the shareable form of a branch's published comments.
+//!
+//! Snapshots are what gets stored in git notes. Every comment and reply carries
+//! its UUID so copies from different machines can be merged, and deleted
+//! comments travel along as tombstones.
+
+use super::types::{AuthorKind, Comment, CommentStatus, Reply, Severity};
+use anyhow::{bail, Context, Result};
+use serde::{Deserialize, Serialize};
+
+/// Current snapshot format version.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// A branch's published comments plus the UUIDs of deleted ones.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BranchSnapshot {
+    pub comments: Vec<Comment>,
+    /// UUIDs of comments deleted on this branch
+    pub deleted: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct SnapshotDoc {
+    version: u32,
+    comments: Vec<CommentDoc>,
+    #[serde(default)]
+    deleted: Vec<String>,
+}
+
+/// Serialized form of a published comment, shared with review archives.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct CommentDoc {
+    uuid: String,
+    file_path: Option<String>,
+    line_range: Option<(usize, usize)>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    anchor: Option<String>,
+    body: String,
+    author: String,
+    /// Omitted for humans, so older readers see unchanged snapshots
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author_kind: Option<String>,
+    created_at: i64,
+    updated_at: i64,
+    status: String,
+    resolved_at: Option<i64>,
+    assignee: Option<String>,
+    severity: Option<String>,
+    #[serde(default)]
+    labels: Vec<String>,
+    #[serde(default)]
+    replies: Vec<ReplyDoc>,
+    /// UUID of the review the comment was published in (archives only)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub(crate) review: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ReplyDoc {
+    uuid: String,
+    body: String,
+    author: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    author_kind: Option<String>,
+    created_at: i64,
+}
+
+impl CommentDoc {
+    pub(crate) fn from_comment(c: &Comment, review: Option<String>) -> Self {
+        Self {
+            uuid: c.uuid.clone(),
+            file_path: c.file_path.clone(),
+            line_range: c.line_range,
+            anchor: c.anchor.clone(),
+            body: c.body.clone(),
+            author: c.author.clone(),
+            author_kind: kind_field(c.author_kind),
+            created_at: c.created_at,
+            updated_at: c.updated_at,
+            status: c.status.as_str().to_string(),
+            resolved_at: c.resolved_at,
+            assignee: c.assignee.clone(),
+            severity: c.severity.map(|s| s.as_str().to_string()),
+            labels: c.labels.clone(),
+            replies: c
+                .replies
+                .iter()
+                .map(|r| ReplyDoc {
+                    uuid: r.uuid.clone(),
+                    body: r.body.clone(),
+                    author: r.author.clone(),
+                    author_kind: kind_field(r.author_kind),
+                    created_at: r.created_at,
+                })
+                .collect(),
+            review,
+        }
+    }
+
+    /// Convert to a comment with local ID 0 and no review.
+    pub(crate) fn into_comment(self) -> Comment {
+        Comment {
+            id: 0,
+            uuid: self.uuid,
+            file_path: self.file_path,
+            line_range: self.line_range,
+            anchor: self.anchor,
+            body: self.body,
+            author: self.author,
+            author_kind: parse_kind(self.author_kind.as_deref()),
+            created_at: self.created_at,
+            status: CommentStatus::parse(&self.status).unwrap_or_default(),
+            resolved_at: self.resolved_at,
+            assignee: self.assignee,
+            updated_at: self.updated_at,
+            severity: self.severity.as_deref().and_then(Severity::parse),
+            labels: self.labels,
+            review_id: None,
+            draft: false,
+            replies: self
+                .replies
+                .into_iter()
+                .map(|r| Reply {
+                    id: 0,
+                    uuid: r.uuid,
+                    comment_id: 0,
+                    body: r.body,
+                    author: r.author,
+                    author_kind: parse_kind(r.author_kind.as_deref()),
+                    created_at: r.created_at,
+                })
+                .collect(),
+        }
+    }
+}
+
+fn kind_field(kind: AuthorKind) -> Option<String> {
+    (!kind.is_human()).then(|| kind.as_str().to_string())
+}
+
+fn parse_kind(kind: Option<&str>) -> AuthorKind {
+    kind.and_then(AuthorKind::parse).unwrap_or_default()
+}
+
+impl BranchSnapshot {
+    /// Serialize to pretty-printed JSON. Local IDs and drafts are not included.
+    pub fn to_json(&self) -> Result<String> {
+        let doc = SnapshotDoc {
+            version: SNAPSHOT_VERSION,
+            comments: self
+                .comments
+                .iter()
+                .filter(|c| !c.draft)
+                .map(|c| CommentDoc::from_comment(c, None))
+                .collect(),
+            deleted: self.deleted.clone(),
+        };
+        Ok(serde_json::to_string_pretty(&doc)?)
+    }
+
+    /// Parse a snapshot. Comments get local ID 0 until they are stored.
+    pub fn from_json(json: &str) -> Result<Self> {
+        let doc: SnapshotDoc = serde_json::from_str(json).context("Invalid panko snapshot")?;
+        if doc.version > SNAPSHOT_VERSION {
+            bail!(
+                "Snapshot version {} is newer than this panko supports ({}); please upgrade",
+                doc.version,
+                SNAPSHOT_VERSION
+            );
+        }
+
+        Ok(Self {
+            comments: doc.comments.into_iter().map(CommentDoc::into_comment).collect(),
+            deleted: doc.deleted,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_json() {
+        let snapshot = BranchSnapshot {
+            comments: vec![Comment {
+                id: 0,
+                uuid: "c1".to_string(),
+                line_range: Some((3, 5)),
+                anchor: Some("fn old_name() {\n}\n".to_string()),
+                body: "rename this".to_string(),
+                created_at: 10,
+                status: CommentStatus::WontFix,
+                resolved_at: Some(20),
+                assignee: Some("bob".to_string()),
+                updated_at: 20,
+                severity: Some(Severity::Nit),
+                labels: vec!["naming".to_string()],
+                replies: vec![Reply {
+                    id: 0,
+                    uuid: "r1".to_string(),
+                    comment_id: 0,
+                    body: "no".to_string(),
+                    author: "bob".to_string(),
+                    author_kind: AuthorKind::Agent,
+                    created_at: 15,
+                }],
+                ..Comment::test(Some("src/lib.rs"), None)
+            }],
+            deleted: vec!["gone".to_string()],
+        };
+
+        let parsed = BranchSnapshot::from_json(&snapshot.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, snapshot);
+    }
+
+    #[test]
+    fn rejects_newer_versions() {
+        let json = r#"{"version": 99, "comments": []}"#;
+        assert!(BranchSnapshot::from_json(json).is_err());
+        assert!(BranchSnapshot::from_json("not json").is_err());
+    }
+}
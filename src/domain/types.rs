@@ -1,10 +1,24 @@
 //! Pure data types for the branch preview domain.
-//! No I/O, no dependencies on external crates beyond std.
+//! No I/O; serde derives are the only dependency beyond std.
 
 #![allow(dead_code)]
 
+use serde::Serialize;
 use std::fmt;
 
+/// Serialize enums as their stable `as_str` identifiers.
+macro_rules! serialize_as_str {
+    ($($ty:ty),*) => {$(
+        impl Serialize for $ty {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+    )*};
+}
+
+serialize_as_str!(CommentScope, CommentStatus, Severity, ReviewVerdict, CommentEventKind, AuthorKind);
+
 /// A git commit with metadata.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Commit {
@@ -47,7 +61,8 @@ impl Commit {
 }
 
 /// A single line in a diff hunk.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "kind", content = "content", rename_all = "lowercase")]
 pub enum DiffLine {
     Context(String),
     Addition(String),
@@ -71,7 +86,7 @@ impl DiffLine {
 }
 
 /// A hunk in a diff file.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Hunk {
     pub old_start: u32,
     pub old_lines: u32,
@@ -90,7 +105,7 @@ impl Hunk {
 }
 
 /// Stats for a file diff.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
 pub struct DiffStats {
     pub additions: usize,
     pub deletions: usize,
@@ -112,7 +127,7 @@ impl fmt::Display for DiffStats {
 }
 
 /// Diff for a single file.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct FileDiff {
     pub path: String,
     pub old_path: Option<String>, // For renames
@@ -125,10 +140,63 @@ impl FileDiff {
     pub fn display_path(&self) -> &str {
         &self.path
     }
+
+    /// Text of new-side lines `start..=end`, if the hunks cover all of them.
+    pub fn new_lines_text(&self, start: usize, end: usize) -> Option<String> {
+        let mut lines = Vec::new();
+        for hunk in &self.hunks {
+            let mut new_num = hunk.new_start as usize;
+            for line in &hunk.lines {
+                if matches!(line, DiffLine::Deletion(_)) {
+                    continue;
+                }
+                if (start..=end).contains(&new_num) {
+                    lines.push(line.content());
+                }
+                new_num += 1;
+            }
+        }
+        (end >= start && lines.len() == end + 1 - start).then(|| lines.join("\n"))
+    }
+
+    /// New-side numbers of the lines the diff adds, in order.
+    pub fn added_lines(&self) -> Vec<usize> {
+        let mut added = Vec::new();
+        for hunk in &self.hunks {
+            let mut new_num = hunk.new_start as usize;
+            for line in &hunk.lines {
+                match line {
+                    DiffLine::Deletion(_) => continue,
+                    DiffLine::Addition(_) => added.push(new_num),
+                    DiffLine::Context(_) => {}
+                }
+                new_num += 1;
+            }
+        }
+        added
+    }
+
+    /// Whether any of new-side lines `start..=end` was added or changed.
+    pub fn changes_lines(&self, start: usize, end: usize) -> bool {
+        self.hunks.iter().any(|hunk| {
+            let mut new_num = hunk.new_start as usize;
+            hunk.lines.iter().any(|line| match line {
+                DiffLine::Deletion(_) => false,
+                DiffLine::Context(_) => {
+                    new_num += 1;
+                    false
+                }
+                DiffLine::Addition(_) => {
+                    new_num += 1;
+                    (start..=end).contains(&(new_num - 1))
+                }
+            })
+        })
+    }
 }
 
 /// A complete diff (multiple files).
-#[derive(Debug, Clone, PartialEq, Eq, Default)]
+#[derive(Debug, Clone, PartialEq, Eq, Default, Serialize)]
 pub struct Diff {
     pub files: Vec<FileDiff>,
 }
@@ -162,12 +230,15 @@ impl BranchPreview {
 }
 
 /// A reply to a comment.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub struct Reply {
     pub id: i64,
+    /// Stable identifier shared across machines (see `new_uuid`)
+    pub uuid: String,
     pub comment_id: i64,
     pub body: String,
     pub author: String,
+    pub author_kind: AuthorKind,
     pub created_at: i64, // Unix timestamp in milliseconds
 }
 
@@ -177,20 +248,231 @@ impl Reply {
     }
 }
 
-/// A review comment/annotation on a range of lines.
+/// Who wrote a comment or reply: a person, a coding agent, or an automated
+/// tool (linters, external reviewers). Agent policies guard people's comments.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum AuthorKind {
+    #[default]
+    Human,
+    Agent,
+    Tool,
+}
+
+impl AuthorKind {
+    pub const ALL: [AuthorKind; 3] = [AuthorKind::Human, AuthorKind::Agent, AuthorKind::Tool];
+
+    /// Stable identifier used in storage, the CLI, and JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuthorKind::Human => "human",
+            AuthorKind::Agent => "agent",
+            AuthorKind::Tool => "tool",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    pub fn is_human(self) -> bool {
+        self == AuthorKind::Human
+    }
+}
+
+/// What a comment is anchored to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentScope {
+    /// The branch as a whole (conversation thread)
+    Branch,
+    /// A whole file
+    File,
+    /// A range of lines in a file
+    Lines,
+}
+
+impl CommentScope {
+    /// Stable identifier used in JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommentScope::Branch => "branch",
+            CommentScope::File => "file",
+            CommentScope::Lines => "lines",
+        }
+    }
+}
+
+/// Where a comment is in its workflow.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentStatus {
+    #[default]
+    Open,
+    /// Someone (usually an agent) has claimed the comment
+    InProgress,
+    /// The change was made; waiting for the reviewer to check and resolve
+    Addressed,
+    /// Declined; the change won't be made
+    WontFix,
+    Resolved,
+}
+
+impl CommentStatus {
+    pub const ALL: [CommentStatus; 5] = [
+        CommentStatus::Open,
+        CommentStatus::InProgress,
+        CommentStatus::Addressed,
+        CommentStatus::WontFix,
+        CommentStatus::Resolved,
+    ];
+
+    /// Stable identifier used in storage, the CLI, and JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommentStatus::Open => "open",
+            CommentStatus::InProgress => "in-progress",
+            CommentStatus::Addressed => "addressed",
+            CommentStatus::WontFix => "wont-fix",
+            CommentStatus::Resolved => "resolved",
+        }
+    }
+
+    /// Parse a status, accepting `_` in place of `-` (e.g. "wont_fix").
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase().replace('_', "-");
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CommentStatus::Open => "Open",
+            CommentStatus::InProgress => "In progress",
+            CommentStatus::Addressed => "Addressed",
+            CommentStatus::WontFix => "Won't fix",
+            CommentStatus::Resolved => "Resolved",
+        }
+    }
+
+    pub fn icon(self) -> &'static str {
+        match self {
+            CommentStatus::Open => "○",
+            CommentStatus::InProgress => "◐",
+            CommentStatus::Addressed => "◉",
+            CommentStatus::WontFix => "⊘",
+            CommentStatus::Resolved => "✓",
+        }
+    }
+
+    /// Whether the comment needs no further work (resolved or won't fix).
+    pub fn is_closed(self) -> bool {
+        matches!(self, CommentStatus::Resolved | CommentStatus::WontFix)
+    }
+}
+
+/// How much a comment matters to the reviewer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    Blocking,
+    Suggestion,
+    Nit,
+    Question,
+    Praise,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 5] = [
+        Severity::Blocking,
+        Severity::Suggestion,
+        Severity::Nit,
+        Severity::Question,
+        Severity::Praise,
+    ];
+
+    /// Stable identifier used in storage, the CLI, and JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Blocking => "blocking",
+            Severity::Suggestion => "suggestion",
+            Severity::Nit => "nit",
+            Severity::Question => "question",
+            Severity::Praise => "praise",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        let s = s.trim().to_ascii_lowercase();
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    /// Cycle through severities, ending at "none" (for the comment input).
+    pub fn cycle(current: Option<Self>) -> Option<Self> {
+        match current {
+            None => Some(Severity::Blocking),
+            Some(Severity::Blocking) => Some(Severity::Suggestion),
+            Some(Severity::Suggestion) => Some(Severity::Nit),
+            Some(Severity::Nit) => Some(Severity::Question),
+            Some(Severity::Question) => Some(Severity::Praise),
+            Some(Severity::Praise) => None,
+        }
+    }
+}
+
+/// Split a leading Conventional Comments prefix off a comment body:
+/// `"nit (perf, style): rename this"` yields severity, labels, and `"rename this"`.
+/// Bodies without a recognized prefix are returned unchanged.
+pub fn parse_comment_prefix(body: &str) -> (Option<Severity>, Vec<String>, &str) {
+    let trimmed = body.trim_start();
+    let Some((head, rest)) = trimmed.split_once(':') else {
+        return (None, Vec::new(), body);
+    };
+    let (name, decorations) = match head.split_once('(') {
+        Some((name, decos)) => match decos.trim_end().strip_suffix(')') {
+            Some(decos) => (name, decos),
+            None => return (None, Vec::new(), body),
+        },
+        None => (head, ""),
+    };
+    let Some(severity) = Severity::parse(name) else {
+        return (None, Vec::new(), body);
+    };
+    let labels = decorations
+        .split(',')
+        .map(str::trim)
+        .filter(|l| !l.is_empty())
+        .map(String::from)
+        .collect();
+    (Some(severity), labels, rest.trim_start())
+}
+
+/// A review comment/annotation on a range of lines, a whole file, or the branch.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Comment {
     pub id: i64,
-    pub file_path: String,
-    /// Starting line in the diff (0-indexed into diff view lines)
-    pub start_line: usize,
-    /// Ending line in the diff (inclusive)
-    pub end_line: usize,
+    /// Stable identifier shared across machines (see `new_uuid`)
+    pub uuid: String,
+    /// File the comment is on (None = branch-level comment)
+    pub file_path: Option<String>,
+    /// Start and end line (inclusive) in the NEW file (None = whole file or branch)
+    pub line_range: Option<(usize, usize)>,
+    /// Text of the anchored lines when the comment was written (None if unknown)
+    pub anchor: Option<String>,
     pub body: String,
     pub author: String,
+    pub author_kind: AuthorKind,
     pub created_at: i64, // Unix timestamp in milliseconds
-    pub resolved: bool,
+    pub status: CommentStatus,
+    /// When the comment was closed (resolved or won't fix)
     pub resolved_at: Option<i64>,
+    /// Who is working on the comment
+    pub assignee: Option<String>,
+    /// Last change to status or assignee (Unix ms), for merging synced copies
+    pub updated_at: i64,
+    pub severity: Option<Severity>,
+    /// Free-form labels, e.g. "perf" or "security"
+    pub labels: Vec<String>,
+    /// Review this comment was made in (None = standalone comment)
+    pub review_id: Option<i64>,
+    /// Whether this comment belongs to a review that hasn't been submitted yet
+    pub draft: bool,
     /// Replies to this comment
     pub replies: Vec<Reply>,
 }
@@ -200,15 +482,262 @@ impl Comment {
         relative_time_from_millis(self.created_at)
     }
 
+    pub fn scope(&self) -> CommentScope {
+        match (&self.file_path, self.line_range) {
+            (None, _) => CommentScope::Branch,
+            (Some(_), None) => CommentScope::File,
+            (Some(_), Some(_)) => CommentScope::Lines,
+        }
+    }
+
+    /// Whether this is a whole-file comment on `path`.
+    pub fn is_on_file(&self, path: &str) -> bool {
+        self.line_range.is_none() && self.file_path.as_deref() == Some(path)
+    }
+
+    /// Whether this comment's line range in `path` includes `line`.
+    pub fn covers_line(&self, path: &str, line: usize) -> bool {
+        self.file_path.as_deref() == Some(path)
+            && self.line_range.is_some_and(|(start, end)| line >= start && line <= end)
+    }
+
+    /// Whether this comment's line range in `path` ends at `line`.
+    pub fn ends_on_line(&self, path: &str, line: usize) -> bool {
+        self.file_path.as_deref() == Some(path)
+            && self.line_range.is_some_and(|(_, end)| end == line)
+    }
+
     pub fn line_range_display(&self) -> String {
-        if self.start_line == self.end_line {
-            format!("L{}", self.start_line + 1)
-        } else {
-            format!("L{}-L{}", self.start_line + 1, self.end_line + 1)
+        match self.line_range {
+            Some((start, end)) if start == end => format!("L{}", start + 1),
+            Some((start, end)) => format!("L{}-L{}", start + 1, end + 1),
+            None => String::new(),
+        }
+    }
+
+    /// Where the comment is anchored, e.g. "src/main.rs L5-L7".
+    pub fn location_display(&self) -> String {
+        match (&self.file_path, self.line_range) {
+            (None, _) => "(branch)".to_string(),
+            (Some(path), None) => format!("{} (file)", path),
+            (Some(path), Some(_)) => format!("{} {}", path, self.line_range_display()),
+        }
+    }
+}
+
+#[cfg(test)]
+impl Comment {
+    /// An open comment by alice on `line` of `file` (a file comment without a
+    /// line, a branch comment without a file). Tests override what they check.
+    pub fn test(file: Option<&str>, line: Option<usize>) -> Self {
+        Comment {
+            id: 1,
+            uuid: String::new(),
+            file_path: file.map(String::from),
+            line_range: line.map(|l| (l, l)),
+            anchor: None,
+            body: String::new(),
+            author: "alice".to_string(),
+            author_kind: AuthorKind::Human,
+            created_at: 0,
+            status: CommentStatus::Open,
+            resolved_at: None,
+            assignee: None,
+            updated_at: 0,
+            severity: None,
+            labels: vec![],
+            review_id: None,
+            draft: false,
+            replies: vec![],
         }
     }
 }
 
+/// CLI JSON form (see `src/output/schema.json`): the line range is split into
+/// `start_line`/`end_line`, and `scope` and `resolved` are derived for convenience.
+/// Drafts are never printed, so `draft` is left out.
+impl Serialize for Comment {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+
+        let mut s = serializer.serialize_struct("Comment", 20)?;
+        s.serialize_field("id", &self.id)?;
+        s.serialize_field("uuid", &self.uuid)?;
+        s.serialize_field("scope", &self.scope())?;
+        s.serialize_field("file_path", &self.file_path)?;
+        s.serialize_field("start_line", &self.line_range.map(|(start, _)| start))?;
+        s.serialize_field("end_line", &self.line_range.map(|(_, end)| end))?;
+        s.serialize_field("anchor", &self.anchor)?;
+        s.serialize_field("severity", &self.severity)?;
+        s.serialize_field("labels", &self.labels)?;
+        s.serialize_field("body", &self.body)?;
+        s.serialize_field("author", &self.author)?;
+        s.serialize_field("author_kind", &self.author_kind)?;
+        s.serialize_field("created_at", &self.created_at)?;
+        s.serialize_field("updated_at", &self.updated_at)?;
+        s.serialize_field("status", &self.status)?;
+        s.serialize_field("resolved", &(self.status == CommentStatus::Resolved))?;
+        s.serialize_field("resolved_at", &self.resolved_at)?;
+        s.serialize_field("assignee", &self.assignee)?;
+        s.serialize_field("review_id", &self.review_id)?;
+        s.serialize_field("replies", &self.replies)?;
+        s.end()
+    }
+}
+
+/// Verdict attached to a submitted review.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReviewVerdict {
+    Comment,
+    Approve,
+    RequestChanges,
+}
+
+impl ReviewVerdict {
+    pub const ALL: [ReviewVerdict; 3] = [
+        ReviewVerdict::Comment,
+        ReviewVerdict::Approve,
+        ReviewVerdict::RequestChanges,
+    ];
+
+    /// Stable identifier used in storage and JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReviewVerdict::Comment => "comment",
+            ReviewVerdict::Approve => "approve",
+            ReviewVerdict::RequestChanges => "request_changes",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ReviewVerdict::Comment => "Comment",
+            ReviewVerdict::Approve => "Approve",
+            ReviewVerdict::RequestChanges => "Request changes",
+        }
+    }
+
+    /// Next verdict in the cycle (for the submit dialog).
+    pub fn next(self) -> Self {
+        match self {
+            ReviewVerdict::Comment => ReviewVerdict::Approve,
+            ReviewVerdict::Approve => ReviewVerdict::RequestChanges,
+            ReviewVerdict::RequestChanges => ReviewVerdict::Comment,
+        }
+    }
+}
+
+/// A GitHub-style review: draft comments published together with a verdict.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Review {
+    pub id: i64,
+    /// Stable identifier shared across machines (see `new_uuid`)
+    pub uuid: String,
+    pub author: String,
+    /// Summary body entered when submitting
+    pub body: String,
+    /// None while the review is still pending
+    pub verdict: Option<ReviewVerdict>,
+    pub created_at: i64,         // Unix timestamp in milliseconds
+    pub submitted_at: Option<i64>, // None while pending
+}
+
+impl Review {
+    pub fn is_pending(&self) -> bool {
+        self.submitted_at.is_none()
+    }
+
+    pub fn relative_time(&self) -> String {
+        relative_time_from_millis(self.submitted_at.unwrap_or(self.created_at))
+    }
+}
+
+/// Kind of change recorded in the comment event log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommentEventKind {
+    /// Published directly, with its review, or pulled from a teammate
+    CommentAdded,
+    ReplyAdded,
+    /// Closed (resolved or won't fix)
+    Resolved,
+    /// Reopened from a closed status
+    Unresolved,
+    Deleted,
+}
+
+impl CommentEventKind {
+    pub const ALL: [CommentEventKind; 5] = [
+        CommentEventKind::CommentAdded,
+        CommentEventKind::ReplyAdded,
+        CommentEventKind::Resolved,
+        CommentEventKind::Unresolved,
+        CommentEventKind::Deleted,
+    ];
+
+    /// Stable identifier used in storage and JSON output.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommentEventKind::CommentAdded => "comment_added",
+            CommentEventKind::ReplyAdded => "reply_added",
+            CommentEventKind::Resolved => "resolved",
+            CommentEventKind::Unresolved => "unresolved",
+            CommentEventKind::Deleted => "deleted",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|v| v.as_str() == s)
+    }
+}
+
+/// A change to a branch's published comments.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct CommentEvent {
+    /// Position in the log; events after it are read with `--since <cursor>`
+    pub cursor: i64,
+    #[serde(rename = "event")]
+    pub kind: CommentEventKind,
+    pub comment_id: i64,
+    pub comment_uuid: String,
+    /// The new reply, for `ReplyAdded`
+    pub reply_id: Option<i64>,
+    pub created_at: i64, // Unix timestamp in milliseconds
+}
+
+/// Generate a random (version 4) UUID for comments and replies.
+///
+/// Randomness comes from std's per-process hash keys mixed with the clock and
+/// a counter, which is plenty to keep identifiers unique across machines.
+pub fn new_uuid() -> String {
+    use std::collections::hash_map::RandomState;
+    use std::hash::{BuildHasher, Hasher};
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let seed = (
+        chrono::Utc::now().timestamp_nanos_opt().unwrap_or_default(),
+        COUNTER.fetch_add(1, Ordering::Relaxed),
+    );
+    let mut halves = [0u64; 2];
+    for (i, half) in halves.iter_mut().enumerate() {
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_i64(seed.0);
+        hasher.write_u64(seed.1);
+        hasher.write_usize(i);
+        *half = hasher.finish();
+    }
+
+    let bits = ((halves[0] as u128) << 64) | halves[1] as u128;
+    // Set the version (4) and variant (10xx) bits
+    let bits = (bits & !(0xf << 76) | (0x4 << 76)) & !(0x3 << 62) | (0x2 << 62);
+    let hex = format!("{:032x}", bits);
+    format!("{}-{}-{}-{}-{}", &hex[0..8], &hex[8..12], &hex[12..16], &hex[16..20], &hex[20..32])
+}
+
 /// Helper to format relative time from millisecond timestamp.
 fn relative_time_from_millis(ts: i64) -> String {
     let now = chrono::Utc::now().timestamp_millis();
@@ -227,3 +756,50 @@ fn relative_time_from_millis(ts: i64) -> String {
         format!("{}d ago", days)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_conventional_comment_prefix() {
+        assert_eq!(
+            parse_comment_prefix("nit (perf, style): rename this"),
+            (
+                Some(Severity::Nit),
+                vec!["perf".to_string(), "style".to_string()],
+                "rename this"
+            )
+        );
+        assert_eq!(
+            parse_comment_prefix("Blocking: leaks the handle"),
+            (Some(Severity::Blocking), vec![], "leaks the handle")
+        );
+    }
+
+    #[test]
+    fn leaves_other_bodies_alone() {
+        for body in ["note: not a severity", "why? see: docs", "no prefix", "nit (oops: x"] {
+            assert_eq!(parse_comment_prefix(body), (None, vec![], body));
+        }
+    }
+
+    #[test]
+    fn generates_distinct_v4_uuids() {
+        let a = new_uuid();
+        let b = new_uuid();
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 36);
+        assert_eq!(&a[14..15], "4");
+        assert!(matches!(&a[19..20], "8" | "9" | "a" | "b"));
+    }
+
+    #[test]
+    fn parses_comment_status() {
+        assert_eq!(CommentStatus::parse("wont-fix"), Some(CommentStatus::WontFix));
+        assert_eq!(CommentStatus::parse("In_Progress"), Some(CommentStatus::InProgress));
+        assert_eq!(CommentStatus::parse("done"), None);
+        assert!(CommentStatus::WontFix.is_closed());
+        assert!(!CommentStatus::InProgress.is_closed());
+    }
+}
@@ -0,0 +1,82 @@
+//! Checkstyle XML export, for CI warning dashboards.
+//!
+//! Comments are grouped by file; line comments report their first line.
+//! Checkstyle has no place for branch-level comments, so they are left out.
+
+use super::{escape_xml, thread_text};
+use crate::domain::{Comment, Severity};
+use std::fmt::Write;
+
+/// Render file and line comments as a checkstyle report.
+pub fn render(comments: &[Comment]) -> String {
+    let mut files: Vec<&str> = Vec::new();
+    for path in comments.iter().filter_map(|c| c.file_path.as_deref()) {
+        if !files.contains(&path) {
+            files.push(path);
+        }
+    }
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"8.0\">\n");
+    for path in files {
+        let _ = writeln!(xml, "  <file name=\"{}\">", escape_xml(path));
+        for comment in comments.iter().filter(|c| c.file_path.as_deref() == Some(path)) {
+            let line = comment
+                .line_range
+                .map(|(start, _)| format!(" line=\"{}\"", start))
+                .unwrap_or_default();
+            let _ = writeln!(
+                xml,
+                "    <error{} severity=\"{}\" message=\"{}\" source=\"panko.{}\"/>",
+                line,
+                severity(comment.severity),
+                escape_xml(&thread_text(comment)),
+                comment.severity.map_or("comment", Severity::as_str),
+            );
+        }
+        xml.push_str("  </file>\n");
+    }
+    xml.push_str("</checkstyle>\n");
+    xml
+}
+
+fn severity(severity: Option<Severity>) -> &'static str {
+    match severity {
+        Some(Severity::Blocking) => "error",
+        Some(Severity::Suggestion) | None => "warning",
+        Some(Severity::Nit) | Some(Severity::Question) | Some(Severity::Praise) => "info",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(file: Option<&str>, line: Option<usize>, body: &str) -> Comment {
+        Comment {
+            line_range: line.map(|l| (l, l + 1)),
+            body: body.to_string(),
+            ..Comment::test(file, None)
+        }
+    }
+
+    #[test]
+    fn groups_comments_by_file_and_escapes() {
+        let mut blocking = comment(Some("src/db.rs"), Some(3), "check \"a < b\"\nfirst");
+        blocking.severity = Some(Severity::Blocking);
+        let comments = vec![
+            blocking,
+            comment(Some("src/lib.rs"), None, "split this file"),
+            comment(Some("src/db.rs"), Some(9), "nit"),
+            comment(None, None, "ship it"),
+        ];
+
+        let xml = render(&comments);
+        assert_eq!(xml.matches("<file ").count(), 2);
+        assert!(xml.contains(
+            "<error line=\"3\" severity=\"error\" message=\"check &quot;a &lt; b&quot;&#10;first\" source=\"panko.blocking\"/>"
+        ));
+        assert!(xml.contains("<error severity=\"warning\" message=\"split this file\" source=\"panko.comment\"/>"));
+        assert!(xml.find("line=\"9\"").unwrap() < xml.find("src/lib.rs").unwrap());
+        assert!(!xml.contains("ship it"));
+    }
+}
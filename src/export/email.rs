@@ -0,0 +1,260 @@
+//! Mailing-list style review export: plain-text replies in the kernel/git
+//! style, written as an mbox.
+//!
+//! Each reviewer gets one message replying to the patch series. The branch
+//! diff is quoted with `> ` prefixes, trimmed to the hunks that carry that
+//! reviewer's comments, and every thread is written inline beneath the line it
+//! ends on. Comments are anchored to the branch diff rather than to single
+//! commits, so the reply covers the whole series.
+
+use crate::domain::{BranchPreview, Comment, Diff, DiffLine, FileDiff, Hunk};
+use chrono::{DateTime, Utc};
+use std::fmt::Write;
+
+/// Render an mbox with one reply per comment author. Returns the mbox and the
+/// number of messages in it.
+pub fn render_mbox(
+    preview: &BranchPreview,
+    diff: &Diff,
+    comments: &[Comment],
+    date: DateTime<Utc>,
+) -> (String, usize) {
+    let mut authors: Vec<&str> = Vec::new();
+    for comment in comments {
+        if !authors.contains(&comment.author.as_str()) {
+            authors.push(&comment.author);
+        }
+    }
+
+    let mut mbox = String::new();
+    for author in &authors {
+        let own: Vec<&Comment> = comments.iter().filter(|c| &c.author == author).collect();
+        mbox.push_str(&render_message(preview, diff, author, &own, date));
+    }
+    (mbox, authors.len())
+}
+
+fn render_message(
+    preview: &BranchPreview,
+    diff: &Diff,
+    author: &str,
+    comments: &[&Comment],
+    date: DateTime<Utc>,
+) -> String {
+    let subject = match preview.commits.as_slice() {
+        [only] => format!("Re: [PATCH] {}", only.summary()),
+        commits => format!("Re: [PATCH 0/{}] {}", commits.len(), preview.current_branch),
+    };
+
+    let mut body = String::new();
+    // Commits come newest first; quote the series in order
+    if let Some(commit) = preview.commits.first() {
+        let written = DateTime::from_timestamp(commit.timestamp, 0).unwrap_or(date);
+        let _ = writeln!(
+            body,
+            "On {}, {} wrote:",
+            written.format("%a, %b %-d, %Y at %H:%M"),
+            commit.author
+        );
+        for commit in preview.commits.iter().rev() {
+            let _ = writeln!(body, "> {} {}", commit.short_hash, commit.summary());
+        }
+        body.push('\n');
+    }
+
+    // Branch-level comments read as the opening of the reply
+    for comment in comments.iter().filter(|c| c.file_path.is_none()) {
+        write_thread(&mut body, comment);
+    }
+
+    for file in &diff.files {
+        write_file(&mut body, file, comments);
+    }
+
+    // Anything not anchored to a quoted line
+    for comment in comments.iter().filter(|c| c.file_path.is_some() && !is_quoted(diff, c)) {
+        let _ = writeln!(body, "On {}:", comment.location_display());
+        body.push('\n');
+        write_thread(&mut body, comment);
+    }
+
+    let mut message = String::new();
+    let _ = writeln!(message, "From panko {}", date.format("%a %b %e %H:%M:%S %Y"));
+    let _ = writeln!(message, "From: {}", author);
+    let _ = writeln!(message, "Date: {}", date.to_rfc2822());
+    let _ = writeln!(message, "Subject: {}", subject);
+    message.push_str("MIME-Version: 1.0\nContent-Type: text/plain; charset=utf-8\n\n");
+    for line in body.trim_end().lines() {
+        // mboxrd: escape lines that would read as a message separator
+        if line.trim_start_matches('>').starts_with("From ") {
+            message.push('>');
+        }
+        message.push_str(line);
+        message.push('\n');
+    }
+    message.push('\n');
+    message
+}
+
+/// Quote the parts of a file's diff that carry comments.
+fn write_file(body: &mut String, file: &FileDiff, comments: &[&Comment]) {
+    let file_comments: Vec<&Comment> = comments
+        .iter()
+        .copied()
+        .filter(|c| c.is_on_file(&file.path))
+        .collect();
+    let hunks: Vec<(&Hunk, Option<u32>)> = file
+        .hunks
+        .iter()
+        .map(|hunk| (hunk, last_commented_line(hunk, &file.path, comments)))
+        .filter(|(_, last)| last.is_some())
+        .collect();
+    if file_comments.is_empty() && hunks.is_empty() {
+        return;
+    }
+
+    let old_path = file.old_path.as_deref().unwrap_or(&file.path);
+    let _ = writeln!(body, "> diff --git a/{} b/{}", old_path, file.path);
+    let _ = writeln!(body, "> --- a/{}", old_path);
+    let _ = writeln!(body, "> +++ b/{}", file.path);
+    if !file_comments.is_empty() {
+        body.push('\n');
+        for comment in file_comments {
+            write_thread(body, comment);
+        }
+    }
+
+    for (hunk, last) in hunks {
+        let _ = writeln!(body, "> {}", hunk.header());
+        let mut new_num = hunk.new_start;
+        for line in &hunk.lines {
+            let _ = writeln!(body, "> {}{}", line.prefix(), line.content().trim_end_matches('\n'));
+            if matches!(line, DiffLine::Deletion(_)) {
+                continue;
+            }
+
+            let threads: Vec<&Comment> = comments
+                .iter()
+                .copied()
+                .filter(|c| c.ends_on_line(&file.path, new_num as usize))
+                .collect();
+            if !threads.is_empty() {
+                body.push('\n');
+                for comment in threads {
+                    write_thread(body, comment);
+                }
+            }
+            if Some(new_num) == last {
+                // Trim the rest of the hunk
+                break;
+            }
+            new_num += 1;
+        }
+    }
+    body.push('\n');
+}
+
+/// New-side line number of the last comment ending inside a hunk.
+fn last_commented_line(hunk: &Hunk, path: &str, comments: &[&Comment]) -> Option<u32> {
+    let end = hunk.new_start + hunk.new_lines;
+    comments
+        .iter()
+        .filter(|c| c.file_path.as_deref() == Some(path))
+        .filter_map(|c| c.line_range.map(|(_, line_end)| line_end as u32))
+        .filter(|line| (hunk.new_start..end).contains(line))
+        .max()
+}
+
+/// Whether a comment is written beneath a quoted line or file header.
+fn is_quoted(diff: &Diff, comment: &Comment) -> bool {
+    diff.files.iter().any(|file| {
+        comment.is_on_file(&file.path)
+            || file.hunks.iter().any(|hunk| {
+                let end = hunk.new_start + hunk.new_lines;
+                (hunk.new_start..end).any(|line| comment.ends_on_line(&file.path, line as usize))
+            })
+    })
+}
+
+fn write_thread(body: &mut String, comment: &Comment) {
+    let mut tags: Vec<&str> = comment.severity.map(|s| s.as_str()).into_iter().collect();
+    tags.extend(comment.labels.iter().map(String::as_str));
+    if !tags.is_empty() {
+        let _ = write!(body, "[{}] ", tags.join(", "));
+    }
+    let _ = writeln!(body, "{}", comment.body.trim_end());
+    if comment.status.is_closed() {
+        let _ = writeln!(body, "({})", comment.status.label());
+    }
+    for reply in &comment.replies {
+        body.push('\n');
+        let _ = writeln!(body, "{} replied:", reply.author);
+        for line in reply.body.trim_end().lines() {
+            let _ = writeln!(body, "  {}", line);
+        }
+    }
+    body.push('\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{Commit, DiffStats};
+
+    fn comment(id: i64, author: &str, line: Option<usize>, body: &str) -> Comment {
+        Comment {
+            id,
+            uuid: id.to_string(),
+            body: body.to_string(),
+            author: author.to_string(),
+            ..Comment::test(Some("src/lib.rs"), line)
+        }
+    }
+
+    #[test]
+    fn quotes_commented_hunks_with_threads_inline() {
+        let preview = BranchPreview {
+            current_branch: "feat".to_string(),
+            base_branch: "main".to_string(),
+            merge_base: String::new(),
+            commits: vec![Commit {
+                hash: "abc1234def".to_string(),
+                short_hash: "abc1234".to_string(),
+                message: "Add helpers".to_string(),
+                author: "carol".to_string(),
+                email: "carol@example.com".to_string(),
+                timestamp: 0,
+            }],
+        };
+        let lines = |prefix: &str| (1..=4).map(|i| DiffLine::Addition(format!("{} {}", prefix, i))).collect();
+        let diff = Diff {
+            files: vec![FileDiff {
+                path: "src/lib.rs".to_string(),
+                old_path: None,
+                hunks: vec![
+                    Hunk { old_start: 0, old_lines: 0, new_start: 1, new_lines: 4, lines: lines("first") },
+                    Hunk { old_start: 0, old_lines: 0, new_start: 20, new_lines: 4, lines: lines("second") },
+                ],
+                stats: DiffStats::new(8, 0),
+                is_binary: false,
+            }],
+        };
+        let comments = vec![
+            comment(1, "alice", Some(2), "From now on, use tracing"),
+            comment(2, "bob", None, "needs docs"),
+        ];
+
+        let (mbox, count) = render_mbox(&preview, &diff, &comments, Utc::now());
+        assert_eq!(count, 2);
+        assert_eq!(mbox.matches("\nFrom: ").count(), 2);
+        assert!(mbox.contains("Subject: Re: [PATCH] Add helpers"));
+
+        let alice = &mbox[..mbox.find("From: bob").unwrap()];
+        let quoted = alice.find("> +first 2").unwrap();
+        let reply = alice.find(">From now on, use tracing").unwrap();
+        assert!(quoted < reply);
+        // Trimmed after the comment, and the uncommented hunk is left out
+        assert!(!alice.contains("first 3"));
+        assert!(!alice.contains("second"));
+    }
+}
@@ -0,0 +1,402 @@
+//! Static HTML review report: the branch diff rendered GitHub-style with every
+//! comment thread inline, coloured with the active theme. The output is a
+//! single self-contained file (inline CSS, no scripts or external assets).
+
+use crate::domain::branch_preview::compute_summary;
+use crate::domain::review::{latest_by_author, review_status};
+use crate::domain::{BranchPreview, Comment, Diff, DiffLine, FileDiff, Review};
+use crate::ui::{syntax, theme};
+use ratatui::style::Color;
+use std::collections::HashSet;
+use std::fmt::Write;
+
+/// Render the report for a branch.
+pub fn render(preview: &BranchPreview, diff: &Diff, comments: &[Comment], reviews: &[Review]) -> String {
+    let mut html = String::new();
+    let title = format!("{} → {}", preview.current_branch, preview.base_branch);
+    let _ = write!(
+        html,
+        "<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Review: {}</title>\n<style>{}</style>\n</head>\n<body>\n",
+        escape(&title),
+        stylesheet()
+    );
+
+    render_header(&mut html, preview, diff, reviews);
+    render_conversation(&mut html, comments, reviews);
+
+    // Comments rendered inline; anything left over is listed after its file
+    let mut shown: HashSet<i64> = HashSet::new();
+    for (index, file) in diff.files.iter().enumerate() {
+        render_file(&mut html, index, file, comments, &mut shown);
+    }
+
+    let outside: Vec<&Comment> = comments
+        .iter()
+        .filter(|c| c.file_path.is_some() && !shown.contains(&c.id))
+        .collect();
+    if !outside.is_empty() {
+        html.push_str("<section class=\"file\"><header>Comments on files outside the diff</header>\n");
+        for comment in outside {
+            let _ = write!(
+                html,
+                "<div class=\"location\">{}</div>",
+                escape(&comment.location_display())
+            );
+            render_thread(&mut html, comment);
+        }
+        html.push_str("</section>\n");
+    }
+
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+fn render_header(html: &mut String, preview: &BranchPreview, diff: &Diff, reviews: &[Review]) {
+    let summary = compute_summary(preview, diff);
+    let _ = write!(
+        html,
+        "<header class=\"report\">\n<h1>{} <span class=\"muted\">→ {}</span></h1>\n\
+         <p class=\"summary\">{} commit{} · {} file{} changed · \
+         <span class=\"add\">+{}</span> <span class=\"del\">−{}</span> · {}</p>\n",
+        escape(&preview.current_branch),
+        escape(&preview.base_branch),
+        summary.commit_count,
+        plural(summary.commit_count),
+        summary.file_count,
+        plural(summary.file_count),
+        summary.additions,
+        summary.deletions,
+        review_status(reviews).label(),
+    );
+
+    if !preview.commits.is_empty() {
+        html.push_str("<ul class=\"commits\">\n");
+        for commit in &preview.commits {
+            let _ = writeln!(
+                html,
+                "<li><code>{}</code> {} <span class=\"muted\">{} · {}</span></li>",
+                escape(&commit.short_hash),
+                escape(commit.summary()),
+                escape(&commit.author),
+                format_time(commit.timestamp * 1000),
+            );
+        }
+        html.push_str("</ul>\n");
+    }
+    html.push_str("</header>\n");
+}
+
+/// Review summaries and branch-level comments.
+fn render_conversation(html: &mut String, comments: &[Comment], reviews: &[Review]) {
+    let general: Vec<&Comment> = comments.iter().filter(|c| c.file_path.is_none()).collect();
+    if general.is_empty() && reviews.is_empty() {
+        return;
+    }
+
+    html.push_str("<section class=\"file\"><header>Conversation</header>\n");
+    let deciding: Vec<i64> = latest_by_author(reviews).iter().map(|r| r.id).collect();
+    for review in reviews {
+        let verdict = review.verdict.map(|v| v.label()).unwrap_or("Pending");
+        let _ = write!(
+            html,
+            "<div class=\"thread\"><div class=\"comment\"><div class=\"meta\"><b>{}</b> \
+             <span class=\"badge{}\">{}</span> <span class=\"muted\">{}</span></div>",
+            escape(&review.author),
+            if deciding.contains(&review.id) { " strong" } else { "" },
+            verdict,
+            format_time(review.submitted_at.unwrap_or(review.created_at)),
+        );
+        if !review.body.is_empty() {
+            let _ = write!(html, "<div class=\"body\">{}</div>", escape(&review.body));
+        }
+        html.push_str("</div></div>\n");
+    }
+    for comment in general {
+        render_thread(html, comment);
+    }
+    html.push_str("</section>\n");
+}
+
+fn render_file(
+    html: &mut String,
+    index: usize,
+    file: &FileDiff,
+    comments: &[Comment],
+    shown: &mut HashSet<i64>,
+) {
+    let _ = writeln!(
+        html,
+        "<section class=\"file\" id=\"file-{}\"><header><span class=\"path\">{}</span> \
+         <span class=\"add\">+{}</span> <span class=\"del\">−{}</span></header>",
+        index,
+        escape(file.display_path()),
+        file.stats.additions,
+        file.stats.deletions,
+    );
+
+    for comment in comments.iter().filter(|c| c.is_on_file(&file.path)) {
+        shown.insert(comment.id);
+        render_thread(html, comment);
+    }
+
+    if file.is_binary {
+        html.push_str("<p class=\"muted pad\">Binary file not shown</p>\n</section>\n");
+        return;
+    }
+
+    let extension = syntax::get_extension(&file.path);
+    html.push_str("<table class=\"diff\">\n");
+    for hunk in &file.hunks {
+        let _ = writeln!(
+            html,
+            "<tr class=\"hunk\"><td class=\"num\"></td><td class=\"num\"></td><td>{}</td></tr>",
+            escape(&hunk.header())
+        );
+
+        let mut old_num = hunk.old_start;
+        let mut new_num = hunk.new_start;
+        for line in &hunk.lines {
+            let (class, old, new) = match line {
+                DiffLine::Context(_) => ("ctx", Some(old_num), Some(new_num)),
+                DiffLine::Addition(_) => ("add", None, Some(new_num)),
+                DiffLine::Deletion(_) => ("del", Some(old_num), None),
+            };
+            let _ = writeln!(
+                html,
+                "<tr class=\"{}\"><td class=\"num\">{}</td><td class=\"num\">{}</td>\
+                 <td class=\"code\"><span class=\"prefix\">{}</span>{}</td></tr>",
+                class,
+                old.map(|n| n.to_string()).unwrap_or_default(),
+                new.map(|n| n.to_string()).unwrap_or_default(),
+                line.prefix(),
+                highlight(line.content(), extension),
+            );
+            if old.is_some() {
+                old_num += 1;
+            }
+
+            if let Some(line_num) = new {
+                new_num += 1;
+                let threads: Vec<&Comment> = comments
+                    .iter()
+                    .filter(|c| c.ends_on_line(&file.path, line_num as usize))
+                    .collect();
+                if !threads.is_empty() {
+                    html.push_str("<tr class=\"threads\"><td colspan=\"3\">");
+                    for comment in threads {
+                        shown.insert(comment.id);
+                        render_thread(html, comment);
+                    }
+                    html.push_str("</td></tr>\n");
+                }
+            }
+        }
+    }
+    html.push_str("</table>\n");
+
+    // Line comments whose lines fall outside the diff's hunks
+    let leftover: Vec<&Comment> = comments
+        .iter()
+        .filter(|c| c.file_path.as_deref() == Some(file.path.as_str()) && !shown.contains(&c.id))
+        .collect();
+    for comment in leftover {
+        shown.insert(comment.id);
+        let _ = write!(
+            html,
+            "<div class=\"location\">{}</div>",
+            escape(&comment.location_display())
+        );
+        render_thread(html, comment);
+    }
+    html.push_str("</section>\n");
+}
+
+fn render_thread(html: &mut String, comment: &Comment) {
+    let closed = if comment.status.is_closed() { " closed" } else { "" };
+    let _ = write!(
+        html,
+        "<div class=\"thread{}\"><div class=\"comment\"><div class=\"meta\"><b>{}</b> \
+         <span class=\"badge\">{} {}</span>",
+        closed,
+        escape(&comment.author),
+        comment.status.icon(),
+        comment.status.label(),
+    );
+    if let Some(severity) = comment.severity {
+        let _ = write!(html, " <span class=\"badge strong\">{}</span>", severity.as_str());
+    }
+    for label in &comment.labels {
+        let _ = write!(html, " <span class=\"badge\">{}</span>", escape(label));
+    }
+    if let Some(assignee) = &comment.assignee {
+        let _ = write!(html, " <span class=\"muted\">→ {}</span>", escape(assignee));
+    }
+    let _ = write!(
+        html,
+        " <span class=\"muted\">{}</span></div><div class=\"body\">{}</div></div>",
+        format_time(comment.created_at),
+        escape(&comment.body),
+    );
+
+    for reply in &comment.replies {
+        let _ = write!(
+            html,
+            "<div class=\"comment reply\"><div class=\"meta\"><b>{}</b> <span class=\"muted\">{}</span></div>\
+             <div class=\"body\">{}</div></div>",
+            escape(&reply.author),
+            format_time(reply.created_at),
+            escape(&reply.body),
+        );
+    }
+    html.push_str("</div>\n");
+}
+
+/// Syntax-highlight one line with the active theme as inline-styled spans.
+fn highlight(content: &str, extension: &str) -> String {
+    let mut out = String::new();
+    for span in syntax::highlight_line(content, extension) {
+        let text = escape(span.content.trim_end_matches('\n'));
+        match span.style.fg {
+            Some(color) => {
+                let _ = write!(out, "<span style=\"color:{}\">{}</span>", css(color), text);
+            }
+            None => out.push_str(&text),
+        }
+    }
+    out
+}
+
+/// Page styles derived from the active theme.
+fn stylesheet() -> String {
+    let ui = theme::current_ui();
+    format!(
+        "body{{margin:0;padding:24px;background:{bg};color:{fg};\
+         font:14px/1.5 -apple-system,BlinkMacSystemFont,'Segoe UI',Helvetica,Arial,sans-serif}}\
+         h1{{font-size:24px;margin:0 0 4px}}\
+         code,.diff{{font-family:ui-monospace,SFMono-Regular,Menlo,Consolas,monospace;font-size:12px}}\
+         .muted{{color:{muted}}}.add{{color:{add}}}.del{{color:{del}}}\
+         .report{{margin-bottom:24px}}.commits{{list-style:none;padding:0}}\
+         .commits li{{padding:2px 0}}\
+         .file{{border:1px solid {border};border-radius:6px;margin-bottom:16px;overflow:hidden}}\
+         .file>header{{background:{file_header};padding:8px 12px;border-bottom:1px solid {border}}}\
+         .path{{color:{path};font-weight:600}}.pad{{padding:8px 12px;margin:0}}\
+         .location{{padding:8px 12px 0;color:{muted}}}\
+         .diff{{width:100%;border-collapse:collapse}}\
+         .diff td{{padding:0 8px;white-space:pre-wrap;word-break:break-all;vertical-align:top}}\
+         .diff td.num{{width:1%;min-width:40px;text-align:right;color:{line_num};user-select:none}}\
+         .diff tr.add{{background:{add_line}}}.diff tr.add td.num{{background:{add_margin}}}\
+         .diff tr.del{{background:{del_line}}}.diff tr.del td.num{{background:{del_margin}}}\
+         .diff tr.hunk td{{background:{hunk_bg};color:{hunk}}}\
+         .prefix{{user-select:none;color:{muted};padding-right:8px}}\
+         .threads td{{padding:8px 12px!important;white-space:normal;font-family:inherit;font-size:14px}}\
+         .thread{{border:1px solid {border};border-radius:6px;margin:8px 12px;background:{sidebar}}}\
+         .threads .thread{{margin:0 0 8px}}.thread.closed{{opacity:.6}}\
+         .comment{{padding:8px 12px}}.reply{{border-top:1px solid {border}}}\
+         .body{{white-space:pre-wrap;margin-top:4px}}\
+         .badge{{border:1px solid {border};border-radius:10px;padding:0 6px;font-size:12px;color:{muted}}}\
+         .badge.strong{{color:{hunk};border-color:{hunk}}}",
+        bg = css(ui.bg_default),
+        fg = css(ui.fg_default),
+        muted = css(ui.fg_muted),
+        add = css(ui.fg_addition),
+        del = css(ui.fg_deletion),
+        border = css(ui.fg_border),
+        file_header = css(ui.bg_file_header),
+        path = css(ui.fg_path),
+        line_num = css(ui.fg_line_num),
+        add_line = css(ui.bg_addition_line),
+        add_margin = css(ui.bg_addition_margin),
+        del_line = css(ui.bg_deletion_line),
+        del_margin = css(ui.bg_deletion_margin),
+        hunk_bg = css(ui.bg_hunk_header),
+        hunk = css(ui.fg_hunk),
+        sidebar = css(ui.bg_sidebar),
+    )
+}
+
+/// CSS colour for a theme colour (themes are defined in RGB).
+fn css(color: Color) -> String {
+    match color {
+        Color::Rgb(r, g, b) => format!("#{:02x}{:02x}{:02x}", r, g, b),
+        _ => "inherit".to_string(),
+    }
+}
+
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// Absolute time: a static report outlives "2 hours ago".
+fn format_time(millis: i64) -> String {
+    chrono::DateTime::from_timestamp_millis(millis)
+        .map(|t| t.format("%Y-%m-%d %H:%M UTC").to_string())
+        .unwrap_or_default()
+}
+
+fn plural(n: usize) -> &'static str {
+    if n == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DiffStats, Hunk};
+
+    fn comment(id: i64, file: Option<&str>, line: Option<usize>, body: &str) -> Comment {
+        Comment { id, uuid: id.to_string(), body: body.to_string(), ..Comment::test(file, line) }
+    }
+
+    #[test]
+    fn renders_threads_inline_and_escapes() {
+        let preview = BranchPreview {
+            current_branch: "feat".to_string(),
+            base_branch: "main".to_string(),
+            merge_base: String::new(),
+            commits: vec![],
+        };
+        let diff = Diff {
+            files: vec![FileDiff {
+                path: "src/lib.rs".to_string(),
+                old_path: None,
+                hunks: vec![Hunk {
+                    old_start: 1,
+                    old_lines: 1,
+                    new_start: 1,
+                    new_lines: 2,
+                    lines: vec![
+                        DiffLine::Context("fn a() {}".to_string()),
+                        DiffLine::Addition("fn b<T>() {}".to_string()),
+                    ],
+                }],
+                stats: DiffStats::new(1, 0),
+                is_binary: false,
+            }],
+        };
+        let comments = vec![
+            comment(1, Some("src/lib.rs"), Some(2), "why <T>?"),
+            comment(2, Some("src/lib.rs"), Some(40), "outside"),
+            comment(3, None, None, "ship it"),
+        ];
+
+        let html = render(&preview, &diff, &comments, &[]);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains("why &lt;T&gt;?"));
+        assert!(!html.contains("<T>"));
+        assert!(html.contains("ship it"));
+        assert!(html.contains("outside"));
+
+        // The thread follows the line it ends on
+        let line = html.find("<tr class=\"add\">").unwrap();
+        let thread = html.find("why &lt;T&gt;?").unwrap();
+        assert!(line < thread);
+        assert!(thread < html.find("outside").unwrap());
+    }
+}
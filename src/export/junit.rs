@@ -0,0 +1,80 @@
+//! JUnit XML export, for CI test dashboards.
+//!
+//! Every open comment is a failed test case named after its location, so the
+//! dashboard lists unresolved review items like failing tests.
+
+use super::{escape_xml, thread_text};
+use crate::domain::{Comment, Severity};
+use std::fmt::Write;
+
+/// Render comments as one test suite named after the branch.
+pub fn render(branch: &str, comments: &[Comment]) -> String {
+    let suite = format!("panko review of {}", branch);
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\">",
+        escape_xml(&suite),
+        comments.len(),
+        comments.len()
+    );
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\">",
+        escape_xml(&suite),
+        comments.len(),
+        comments.len()
+    );
+    for comment in comments {
+        let classname = comment.file_path.as_deref().unwrap_or("branch");
+        let name = match comment.line_range {
+            Some((start, end)) if start == end => format!("#{} line {}", comment.id, start),
+            Some((start, end)) => format!("#{} lines {}-{}", comment.id, start, end),
+            None => format!("#{}", comment.id),
+        };
+        let summary = comment.body.lines().next().unwrap_or_default();
+        let _ = writeln!(
+            xml,
+            "    <testcase classname=\"{}\" name=\"{}\">",
+            escape_xml(classname),
+            escape_xml(&name)
+        );
+        let _ = writeln!(
+            xml,
+            "      <failure message=\"{}\" type=\"{}\">{}</failure>",
+            escape_xml(summary),
+            comment.severity.map_or("comment", Severity::as_str),
+            escape_xml(&format!("{}: {}", comment.author, thread_text(comment)))
+        );
+        xml.push_str("    </testcase>\n");
+    }
+    xml.push_str("  </testsuite>\n</testsuites>\n");
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn comment(id: i64, file: Option<&str>, lines: Option<(usize, usize)>) -> Comment {
+        Comment {
+            id,
+            line_range: lines,
+            body: "use a pool\nper request is slow".to_string(),
+            severity: Some(Severity::Blocking),
+            ..Comment::test(file, None)
+        }
+    }
+
+    #[test]
+    fn each_comment_is_a_failing_test_case() {
+        let comments = vec![comment(3, Some("src/db.rs"), Some((10, 12))), comment(4, None, None)];
+        let xml = render("feat/<x>", &comments);
+
+        assert!(xml.contains("<testsuite name=\"panko review of feat/&lt;x&gt;\" tests=\"2\" failures=\"2\">"));
+        assert!(xml.contains("<testcase classname=\"src/db.rs\" name=\"#3 lines 10-12\">"));
+        assert!(xml.contains("<failure message=\"use a pool\" type=\"blocking\">"));
+        assert!(xml.contains("<testcase classname=\"branch\" name=\"#4\">"));
+        assert_eq!(xml.matches("<failure ").count(), 2);
+    }
+}
@@ -0,0 +1,43 @@
+//! Report formats for sharing reviews outside panko.
+//!
+//! `sarif`, `checkstyle` and `junit` report a branch's open comments for CI
+//! dashboards; comments are passed in already filtered.
+
+pub mod checkstyle;
+pub mod email;
+pub mod html;
+pub mod junit;
+pub mod sarif;
+pub mod unified;
+
+use crate::domain::Comment;
+
+/// A comment and its replies as plain text, for report messages.
+fn thread_text(comment: &Comment) -> String {
+    let mut text = comment.body.clone();
+    for reply in &comment.replies {
+        text.push_str(&format!("\n\n{}: {}", reply.author, reply.body));
+    }
+    text
+}
+
+/// Escape text for XML content and attribute values.
+fn escape_xml(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            // Keep newlines in attributes
+            '\n' => escaped.push_str("&#10;"),
+            '\t' | '\r' => escaped.push_str(&format!("&#{};", c as u32)),
+            // Other control characters are not allowed in XML 1.0
+            c if c.is_control() => {}
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
@@ -0,0 +1,132 @@
+//! SARIF 2.1.0 export, for code-scanning viewers.
+//!
+//! Each open comment is a result whose rule is its severity (`comment` when
+//! it has none). Line comments carry a region, file-level comments only the
+//! file, and branch-level comments no location at all.
+
+use super::thread_text;
+use crate::domain::{Comment, Severity};
+use serde_json::{json, Value};
+
+/// Rule ID for comments without a severity.
+const NO_SEVERITY_RULE: &str = "comment";
+
+/// Render comments as a SARIF log with a single panko run.
+pub fn render(comments: &[Comment]) -> String {
+    let rules: Vec<Value> = Severity::ALL
+        .iter()
+        .map(|s| (s.as_str(), Some(*s)))
+        .chain([(NO_SEVERITY_RULE, None)])
+        .map(|(id, severity)| {
+            json!({
+                "id": id,
+                "shortDescription": { "text": format!("Review comment ({})", id) },
+                "defaultConfiguration": { "level": level(severity) },
+            })
+        })
+        .collect();
+
+    let log = json!({
+        "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+        "version": "2.1.0",
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": "panko",
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "rules": rules,
+                },
+            },
+            "results": comments.iter().map(result).collect::<Vec<_>>(),
+        }],
+    });
+    serde_json::to_string_pretty(&log).unwrap_or_default()
+}
+
+fn result(comment: &Comment) -> Value {
+    let locations: Vec<Value> = comment
+        .file_path
+        .iter()
+        .map(|path| {
+            let mut location = json!({ "artifactLocation": { "uri": path } });
+            if let Some((start, end)) = comment.line_range {
+                location["region"] = json!({ "startLine": start, "endLine": end });
+            }
+            json!({ "physicalLocation": location })
+        })
+        .collect();
+
+    json!({
+        "ruleId": comment.severity.map_or(NO_SEVERITY_RULE, Severity::as_str),
+        "level": level(comment.severity),
+        "message": { "text": thread_text(comment) },
+        "locations": locations,
+        // Lets viewers track a comment across runs as lines move
+        "partialFingerprints": { "pankoComment/v1": comment.uuid },
+        "properties": {
+            "author": comment.author,
+            "status": comment.status,
+            "labels": comment.labels,
+        },
+    })
+}
+
+fn level(severity: Option<Severity>) -> &'static str {
+    match severity {
+        Some(Severity::Blocking) => "error",
+        Some(Severity::Suggestion) | None => "warning",
+        Some(Severity::Nit) | Some(Severity::Question) => "note",
+        Some(Severity::Praise) => "none",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{AuthorKind, Reply};
+
+    fn comment(id: i64, file: Option<&str>, lines: Option<(usize, usize)>, severity: Option<Severity>) -> Comment {
+        Comment {
+            id,
+            uuid: format!("uuid-{}", id),
+            line_range: lines,
+            body: "use a pool".to_string(),
+            severity,
+            labels: vec!["perf".to_string()],
+            ..Comment::test(file, None)
+        }
+    }
+
+    #[test]
+    fn comments_become_results_with_locations() {
+        let mut line = comment(1, Some("src/db.rs"), Some((10, 12)), Some(Severity::Blocking));
+        line.replies.push(Reply {
+            id: 1,
+            comment_id: 1,
+            uuid: "r1".to_string(),
+            body: "on it".to_string(),
+            author: "bob".to_string(),
+            author_kind: AuthorKind::Human,
+            created_at: 0,
+        });
+        let comments = vec![line, comment(2, Some("src/db.rs"), None, None), comment(3, None, None, None)];
+
+        let log: Value = serde_json::from_str(&render(&comments)).unwrap();
+        assert_eq!(log["version"], "2.1.0");
+        let results = log["runs"][0]["results"].as_array().unwrap();
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0]["ruleId"], "blocking");
+        assert_eq!(results[0]["level"], "error");
+        assert_eq!(results[0]["message"]["text"], "use a pool\n\nbob: on it");
+        let location = &results[0]["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/db.rs");
+        assert_eq!(location["region"], json!({ "startLine": 10, "endLine": 12 }));
+        assert_eq!(results[0]["partialFingerprints"]["pankoComment/v1"], "uuid-1");
+
+        assert_eq!(results[1]["ruleId"], "comment");
+        assert!(results[1]["locations"][0]["physicalLocation"].get("region").is_none());
+        assert_eq!(results[2]["locations"], json!([]));
+        let rules = log["runs"][0]["tool"]["driver"]["rules"].as_array().unwrap();
+        assert_eq!(rules.len(), Severity::ALL.len() + 1);
+    }
+}
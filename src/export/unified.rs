@@ -0,0 +1,147 @@
+//! Unified diff rendering for `panko diff`, optionally with comment threads
+//! written beneath the lines they are anchored to.
+
+use crate::domain::{Comment, CommentScope, Diff, DiffLine, FileDiff};
+use crossterm::style::Stylize;
+use std::fmt::Write;
+
+/// Render a diff as unified text. Threads from `comments` are interleaved at
+/// their end lines; pass an empty slice for a plain diff. With `color`, lines
+/// carry ANSI colors for a terminal.
+pub fn render(diff: &Diff, comments: &[Comment], color: bool) -> String {
+    let mut out = String::new();
+    for comment in comments.iter().filter(|c| c.scope() == CommentScope::Branch) {
+        write_thread(&mut out, comment, color);
+    }
+    for file in &diff.files {
+        write_file(&mut out, file, comments, color);
+    }
+    out
+}
+
+fn write_file(out: &mut String, file: &FileDiff, comments: &[Comment], color: bool) {
+    let old_path = file.old_path.as_deref().unwrap_or(&file.path);
+    let header = [
+        format!("diff --git a/{} b/{}", old_path, file.path),
+        format!("--- a/{}", old_path),
+        format!("+++ b/{}", file.path),
+    ];
+    for line in header {
+        let _ = writeln!(out, "{}", paint(line, color, |s| s.bold().to_string()));
+    }
+    for comment in comments.iter().filter(|c| c.is_on_file(&file.path)) {
+        write_thread(out, comment, color);
+    }
+    if file.is_binary {
+        let _ = writeln!(out, "Binary files differ");
+    }
+
+    let mut shown = Vec::new();
+    for hunk in &file.hunks {
+        let _ = writeln!(out, "{}", paint(hunk.header(), color, |s| s.cyan().to_string()));
+        let mut new_num = hunk.new_start as usize;
+        for line in &hunk.lines {
+            let text = format!("{}{}", line.prefix(), line.content());
+            let text = match line {
+                DiffLine::Addition(_) => paint(text, color, |s| s.green().to_string()),
+                DiffLine::Deletion(_) => paint(text, color, |s| s.red().to_string()),
+                DiffLine::Context(_) => text,
+            };
+            let _ = writeln!(out, "{}", text);
+            if matches!(line, DiffLine::Deletion(_)) {
+                continue;
+            }
+
+            for comment in comments.iter().filter(|c| c.ends_on_line(&file.path, new_num)) {
+                write_thread(out, comment, color);
+                shown.push(comment.id);
+            }
+            new_num += 1;
+        }
+    }
+
+    // Line comments whose lines fall outside every hunk
+    for comment in comments.iter().filter(|c| {
+        c.scope() == CommentScope::Lines
+            && c.file_path.as_deref() == Some(file.path.as_str())
+            && !shown.contains(&c.id)
+    }) {
+        write_thread(out, comment, color);
+    }
+}
+
+fn write_thread(out: &mut String, comment: &Comment, color: bool) {
+    let mut title = format!("┌─ #{} {} [{}]", comment.id, comment.author, comment.status.as_str());
+    match comment.line_range {
+        Some((start, end)) if start == end => {
+            let _ = write!(title, " line {}", start);
+        }
+        Some((start, end)) => {
+            let _ = write!(title, " lines {}-{}", start, end);
+        }
+        None => {}
+    }
+    if let Some(severity) = comment.severity {
+        let _ = write!(title, " ({})", severity.as_str());
+    }
+    let mut lines = vec![title];
+    lines.extend(comment.body.lines().map(|l| format!("│ {}", l)));
+    for reply in &comment.replies {
+        lines.push(format!("│ ↳ {}:", reply.author));
+        lines.extend(reply.body.lines().map(|l| format!("│   {}", l)));
+    }
+    lines.push("└─".to_string());
+
+    for line in lines {
+        let _ = writeln!(out, "{}", paint(line, color, |s| s.yellow().to_string()));
+    }
+}
+
+fn paint(text: String, color: bool, style: impl Fn(String) -> String) -> String {
+    if color {
+        style(text)
+    } else {
+        text
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::{DiffStats, Hunk};
+
+    #[test]
+    fn interleaves_threads_beneath_their_lines() {
+        let diff = Diff {
+            files: vec![FileDiff {
+                path: "src/lib.rs".to_string(),
+                old_path: None,
+                hunks: vec![Hunk {
+                    old_start: 1,
+                    old_lines: 2,
+                    new_start: 1,
+                    new_lines: 2,
+                    lines: vec![
+                        DiffLine::Context("fn a() {}".to_string()),
+                        DiffLine::Deletion("fn b() {}".to_string()),
+                        DiffLine::Addition("fn c() {}".to_string()),
+                    ],
+                }],
+                stats: DiffStats::new(1, 1),
+                is_binary: false,
+            }],
+        };
+        let comment = Comment { id: 7, body: "why c?".to_string(), ..Comment::test(Some("src/lib.rs"), Some(2)) };
+
+        let plain = render(&diff, &[], false);
+        assert_eq!(
+            plain,
+            "diff --git a/src/lib.rs b/src/lib.rs\n--- a/src/lib.rs\n+++ b/src/lib.rs\n\
+             @@ -1,2 +1,2 @@\n fn a() {}\n-fn b() {}\n+fn c() {}\n"
+        );
+
+        let threaded = render(&diff, &[comment], false);
+        assert!(threaded.ends_with("+fn c() {}\n┌─ #7 alice [open] line 2\n│ why c?\n└─\n"));
+        assert!(render(&diff, &[], true).contains("\x1b["));
+    }
+}
@@ -0,0 +1,205 @@
+//! Git hooks that gate pushes and merges on `panko check`.
+//!
+//! Installing keeps whatever hook was already there: it is renamed to
+//! `<hook>.pre-panko` and run first by the panko hook, with the same
+//! arguments and input. Reinstalling only rewrites panko's own hooks, and
+//! refuses to run if another tool replaced one while a `.pre-panko` is kept.
+
+use anyhow::{bail, Context, Result};
+use git2::Repository;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Hooks that run `panko check`.
+pub const HOOKS: [&str; 2] = ["pre-push", "pre-merge-commit"];
+
+/// Marks a hook file as written by panko.
+const MARKER: &str = "# Installed by `panko init hooks`";
+
+/// Suffix for a hook panko replaced.
+const PRESERVED_SUFFIX: &str = ".pre-panko";
+
+/// What happened to one hook.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Installed {
+    Created(PathBuf),
+    /// An existing panko hook was rewritten
+    Updated(PathBuf),
+    /// A foreign hook was moved aside to the second path and chained
+    Chained(PathBuf, PathBuf),
+}
+
+/// The repository's hooks directory, honoring `core.hooksPath`.
+pub fn hooks_dir(workdir: &Path) -> Result<PathBuf> {
+    let repo = Repository::discover(workdir).context("Git hooks need a git repository")?;
+    let configured = repo
+        .config()
+        .ok()
+        .and_then(|config| config.get_path("core.hooksPath").ok());
+    Ok(match configured {
+        Some(path) if path.is_absolute() => path,
+        Some(path) => repo.workdir().unwrap_or(workdir).join(path),
+        // Linked worktrees live in .git/worktrees/<name> and share the main hooks
+        None if repo.is_worktree() => repo.path().ancestors().nth(2).unwrap_or(repo.path()).join("hooks"),
+        None => repo.path().join("hooks"),
+    })
+}
+
+/// Install every hook in `HOOKS` into `dir`, running `panko check` with
+/// `check_args` (e.g. `--blocking`).
+pub fn install(dir: &Path, check_args: &[&str]) -> Result<Vec<Installed>> {
+    // Check every hook first, so a refusal leaves them all untouched
+    for hook in HOOKS {
+        let path = dir.join(hook);
+        let preserved = preserved_path(dir, hook);
+        let foreign = fs::read_to_string(&path).is_ok_and(|existing| !existing.contains(MARKER));
+        if foreign && preserved.exists() {
+            bail!(
+                "{} is not panko's hook, and {} already holds the hook panko replaced before. \
+                 Merge them into {} and rerun `panko init hooks`",
+                path.display(),
+                preserved.display(),
+                preserved.display()
+            );
+        }
+    }
+
+    fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+    HOOKS.iter().map(|hook| install_hook(dir, hook, check_args)).collect()
+}
+
+fn preserved_path(dir: &Path, hook: &str) -> PathBuf {
+    dir.join(format!("{}{}", hook, PRESERVED_SUFFIX))
+}
+
+fn install_hook(dir: &Path, hook: &str, check_args: &[&str]) -> Result<Installed> {
+    let path = dir.join(hook);
+    let installed = match fs::read_to_string(&path) {
+        Ok(existing) if existing.contains(MARKER) => Installed::Updated(path.clone()),
+        Ok(_) => {
+            let preserved = preserved_path(dir, hook);
+            fs::rename(&path, &preserved)
+                .with_context(|| format!("Failed to move {} aside", path.display()))?;
+            Installed::Chained(path.clone(), preserved)
+        }
+        Err(_) => Installed::Created(path.clone()),
+    };
+
+    fs::write(&path, script(hook, check_args))
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    make_executable(&path)?;
+    Ok(installed)
+}
+
+fn script(hook: &str, check_args: &[&str]) -> String {
+    let command = std::iter::once("panko check")
+        .chain(check_args.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+    format!(
+        r#"#!/bin/sh
+{MARKER}: blocks while the branch has unresolved review comments.
+# A {hook} hook that was here before is kept as {hook}{PRESERVED_SUFFIX} and runs first.
+# Bypass once with --no-verify.
+hook_dir=$(dirname "$0")
+input=$(cat)
+if [ -x "$hook_dir/{hook}{PRESERVED_SUFFIX}" ]; then
+    {{ [ -z "$input" ] || printf '%s\n' "$input"; }} | "$hook_dir/{hook}{PRESERVED_SUFFIX}" "$@" || exit $?
+fi
+if ! command -v panko >/dev/null 2>&1; then
+    echo "panko not found; skipping review check" >&2
+    exit 0
+fi
+exec {command}
+"#
+    )
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+        .with_context(|| format!("Failed to make {} executable", path.display()))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> Result<()> {
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn make_temp_dir(prefix: &str) -> PathBuf {
+        let ts = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should be after UNIX_EPOCH")
+            .as_nanos();
+        let dir = std::env::temp_dir().join(format!("panko-{}-{}-{}", prefix, std::process::id(), ts));
+        fs::create_dir_all(&dir).expect("failed to create temp dir");
+        dir
+    }
+
+    #[test]
+    fn existing_hooks_are_chained_and_reinstalls_update() {
+        let root = make_temp_dir("hooks");
+        Repository::init(&root).unwrap();
+        let dir = hooks_dir(&root).unwrap();
+        assert_eq!(dir, root.join(".git").join("hooks"));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("pre-push"), "#!/bin/sh\nrun-tests\n").unwrap();
+
+        let installed = install(&dir, &["--blocking"]).unwrap();
+        assert_eq!(
+            installed[0],
+            Installed::Chained(dir.join("pre-push"), dir.join("pre-push.pre-panko"))
+        );
+        assert_eq!(installed[1], Installed::Created(dir.join("pre-merge-commit")));
+        assert_eq!(fs::read_to_string(dir.join("pre-push.pre-panko")).unwrap(), "#!/bin/sh\nrun-tests\n");
+        let hook = fs::read_to_string(dir.join("pre-push")).unwrap();
+        assert!(hook.contains("\"$hook_dir/pre-push.pre-panko\" \"$@\""));
+        assert!(hook.ends_with("exec panko check --blocking\n"));
+
+        // The preserved hook is left alone the second time
+        let again = install(&dir, &[]).unwrap();
+        assert_eq!(again[0], Installed::Updated(dir.join("pre-push")));
+        assert_eq!(fs::read_to_string(dir.join("pre-push.pre-panko")).unwrap(), "#!/bin/sh\nrun-tests\n");
+        assert!(fs::read_to_string(dir.join("pre-push")).unwrap().ends_with("exec panko check\n"));
+
+        // Another tool replaced panko's hook: nothing is overwritten or moved
+        fs::write(dir.join("pre-push"), "#!/bin/sh\nlint\n").unwrap();
+        let err = install(&dir, &[]).unwrap_err().to_string();
+        assert!(err.contains("pre-push.pre-panko already holds"), "{}", err);
+        assert_eq!(fs::read_to_string(dir.join("pre-push")).unwrap(), "#!/bin/sh\nlint\n");
+        assert_eq!(fs::read_to_string(dir.join("pre-push.pre-panko")).unwrap(), "#!/bin/sh\nrun-tests\n");
+
+        fs::remove_dir_all(root).ok();
+    }
+
+    #[test]
+    fn hooks_dir_follows_hooks_path_and_linked_worktrees() {
+        let root = make_temp_dir("hooks-dir");
+        let repo = Repository::init(root.join("main")).unwrap();
+        let signature = git2::Signature::now("t", "t@t").unwrap();
+        let tree = repo.find_tree(repo.index().unwrap().write_tree().unwrap()).unwrap();
+        repo.commit(Some("HEAD"), &signature, &signature, "init", &tree, &[]).unwrap();
+        repo.worktree("linked", &root.join("linked"), None).unwrap();
+
+        // Linked worktrees share the main repository's hooks
+        let shared = root.join("main").join(".git").join("hooks");
+        assert_eq!(hooks_dir(&root.join("linked")).unwrap(), shared);
+
+        // core.hooksPath: relative to the working tree, or absolute
+        let mut config = repo.config().unwrap();
+        config.set_str("core.hooksPath", ".githooks").unwrap();
+        assert_eq!(hooks_dir(&root.join("main")).unwrap(), root.join("main").join(".githooks"));
+        assert_eq!(hooks_dir(&root.join("linked")).unwrap(), root.join("linked").join(".githooks"));
+        let absolute = root.join("team-hooks");
+        config.set_str("core.hooksPath", absolute.to_str().unwrap()).unwrap();
+        assert_eq!(hooks_dir(&root.join("main")).unwrap(), absolute);
+
+        fs::remove_dir_all(root).ok();
+    }
+}
@@ -0,0 +1,474 @@
+//! Agent instructions written by `panko init <target>`, from templates.
+//!
+//! A template is a TOML file naming the files it writes and the panko
+//! section of each:
+//!
+//! ```toml
+//! description = "Gemini CLI context file (GEMINI.md)"
+//! note = "Printed after writing the files"
+//!
+//! [[files]]
+//! path = "GEMINI.md"          # relative to the repository root
+//! header = "# Project Instructions\n\n"  # only written when creating the file
+//! section = "{{instructions}}"  # panko's shared agent instructions
+//!
+//! [[settings]]
+//! path = ".claude/settings.json"  # a JSON file panko's entries are merged into
+//! alternatives = [".claude/settings.local.json"]
+//! merge = { permissions.allow = ["Bash(panko comments*)"] }
+//! ```
+//!
+//! Built-in templates ship with panko; `~/.config/panko/init/<target>.toml`
+//! adds a target or replaces the built-in one of the same name. Sections are
+//! written between markers, so re-running only touches panko's part of a file
+//! and `--update` can refresh it in place. Sections written by older versions
+//! without markers are recognized by their heading. Settings are merged
+//! without replacing anything already there.
+
+use crate::output;
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use serde_json::Value;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+const BUILTIN: [(&str, &str); 7] = [
+    ("aider", include_str!("templates/aider.toml")),
+    ("claude", include_str!("templates/claude.toml")),
+    ("claude-mcp", include_str!("templates/claude-mcp.toml")),
+    ("codex", include_str!("templates/codex.toml")),
+    ("cursor", include_str!("templates/cursor.toml")),
+    ("gemini", include_str!("templates/gemini.toml")),
+    ("opencode", include_str!("templates/opencode.toml")),
+];
+
+/// The agent instructions most markdown targets share, written where a
+/// section says `{{instructions}}`.
+const INSTRUCTIONS: &str = include_str!("templates/instructions.md");
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Template {
+    #[serde(skip)]
+    pub name: String,
+    /// Where the template came from (None = built in)
+    #[serde(skip)]
+    pub source: Option<PathBuf>,
+    pub description: String,
+    /// Printed once the files are written
+    pub note: Option<String>,
+    #[serde(default)]
+    pub files: Vec<TemplateFile>,
+    #[serde(default)]
+    pub settings: Vec<SettingsFile>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TemplateFile {
+    pub path: String,
+    #[serde(default)]
+    pub header: String,
+    pub section: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SettingsFile {
+    /// Created when neither it nor any alternative exists
+    pub path: String,
+    /// Merged into instead when `path` doesn't exist but one of these does
+    #[serde(default)]
+    pub alternatives: Vec<String>,
+    /// Objects are merged key by key, arrays gain the items they lack, and
+    /// other values are only set where missing
+    pub merge: Value,
+}
+
+/// What writing one file does.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Change {
+    Created,
+    /// A section was appended to an existing file
+    Added,
+    /// The section was replaced
+    Updated,
+    /// Settings were merged into an existing file
+    Merged,
+    UpToDate,
+    /// The section differs and `--update` was not given; nothing is written
+    Outdated,
+}
+
+/// A file and its content before and after.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Planned {
+    pub path: PathBuf,
+    pub change: Change,
+    pub old: Option<String>,
+    pub new: String,
+}
+
+/// Directory of user templates.
+pub fn user_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("panko").join("init"))
+}
+
+/// Built-in templates plus those in `user_dir`, by name. User templates
+/// replace built-ins of the same name.
+pub fn templates(user_dir: Option<&Path>) -> Result<Vec<Template>> {
+    let mut templates: Vec<Template> = BUILTIN
+        .iter()
+        .map(|(name, text)| parse(name, text, None))
+        .collect::<Result<_>>()?;
+
+    let entries = user_dir.and_then(|dir| fs::read_dir(dir).ok()).into_iter().flatten();
+    for entry in entries {
+        let path = entry?.path();
+        if path.extension().is_none_or(|ext| ext != "toml") {
+            continue;
+        }
+        let Some(name) = path.file_stem().map(|s| s.to_string_lossy().to_string()) else { continue };
+        let text = fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+        let template = parse(&name, &text, Some(path))?;
+        templates.retain(|t| t.name != name);
+        templates.push(template);
+    }
+    templates.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+fn parse(name: &str, text: &str, source: Option<PathBuf>) -> Result<Template> {
+    let origin = source.as_ref().map_or_else(|| format!("built-in template '{}'", name), |p| p.display().to_string());
+    let mut template: Template =
+        toml::from_str(text).map_err(|e| output::usage(format!("Invalid template {}: {}", origin, e)))?;
+    let invalid = |problem: String| output::usage(format!("Invalid template {}: {}", origin, problem));
+    if template.files.is_empty() && template.settings.is_empty() {
+        return Err(invalid("it writes no files".to_string()));
+    }
+    let paths = template.files.iter().map(|f| &f.path);
+    let paths = paths.chain(template.settings.iter().flat_map(|s| std::iter::once(&s.path).chain(&s.alternatives)));
+    for path in paths {
+        if !Path::new(path).components().all(|c| matches!(c, Component::Normal(_))) {
+            return Err(invalid(format!("'{}' must be a path inside the repository", path)));
+        }
+    }
+    if let Some(settings) = template.settings.iter().find(|s| !s.merge.is_object()) {
+        return Err(invalid(format!("the settings merged into '{}' must be a table", settings.path)));
+    }
+    for file in &mut template.files {
+        file.section = file.section.replace("{{instructions}}", INSTRUCTIONS);
+    }
+    template.name = name.to_string();
+    template.source = source;
+    Ok(template)
+}
+
+/// Work out each file of a template under `workdir`.
+pub fn plan(template: &Template, workdir: &Path, update: bool) -> Result<Vec<Planned>> {
+    let files = template.files.iter().map(|file| {
+        let path = workdir.join(&file.path);
+        let old = read(&path)?;
+        let (change, new) = render(old.as_deref(), file, update);
+        Ok(Planned { path, change, old, new })
+    });
+    let settings = template.settings.iter().map(|settings| {
+        let candidates = std::iter::once(&settings.path).chain(&settings.alternatives);
+        for path in candidates.map(|p| workdir.join(p)) {
+            if let Some(old) = read(&path)? {
+                let (change, new) = merge_settings(&old, &settings.merge)
+                    .with_context(|| format!("Cannot merge panko settings into {}", path.display()))?;
+                return Ok(Planned { path, change, old: Some(old), new });
+            }
+        }
+        let new = format!("{}\n", serde_json::to_string_pretty(&settings.merge)?);
+        Ok(Planned { path: workdir.join(&settings.path), change: Change::Created, old: None, new })
+    });
+    files.chain(settings).collect()
+}
+
+fn read(path: &Path) -> Result<Option<String>> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e).with_context(|| format!("Failed to read {}", path.display())),
+    }
+}
+
+/// Write the files that change.
+pub fn apply(planned: &[Planned]) -> Result<()> {
+    for file in planned {
+        if !matches!(file.change, Change::Created | Change::Added | Change::Updated | Change::Merged) {
+            continue;
+        }
+        if let Some(dir) = file.path.parent() {
+            fs::create_dir_all(dir).with_context(|| format!("Failed to create {}", dir.display()))?;
+        }
+        fs::write(&file.path, &file.new).with_context(|| format!("Failed to write {}", file.path.display()))?;
+    }
+    Ok(())
+}
+
+/// The content of a file with the panko section written in, given what is
+/// there now. `new` is the current content when nothing is written.
+fn render(old: Option<&str>, file: &TemplateFile, update: bool) -> (Change, String) {
+    let section = marked_section(&file.section);
+    let Some(old) = old else {
+        return (Change::Created, format!("{}{}", file.header, section));
+    };
+
+    let Some((start, end)) = find_section(old, &file.section) else {
+        let separator = if old.trim().is_empty() { "" } else { "\n\n" };
+        return (Change::Added, format!("{}{}{}", old.trim_end(), separator, section));
+    };
+    // An unmarked section never matches, so --update adds the markers
+    if old[start..end] == section {
+        return (Change::UpToDate, old.to_string());
+    }
+    if !update {
+        return (Change::Outdated, old.to_string());
+    }
+    (Change::Updated, format!("{}{}{}", &old[..start], section, &old[end..]))
+}
+
+/// A JSON settings file with `merge` merged in. An unchanged file is left as
+/// it was, formatting included.
+fn merge_settings(old: &str, merge: &Value) -> Result<(Change, String)> {
+    let mut settings: Value = serde_json::from_str(old).context("Not valid JSON")?;
+    if !settings.is_object() {
+        bail!("Settings must be a JSON object");
+    }
+    let before = settings.clone();
+    merge_json(&mut settings, merge, "")?;
+    if settings == before {
+        return Ok((Change::UpToDate, old.to_string()));
+    }
+    Ok((Change::Merged, format!("{}\n", serde_json::to_string_pretty(&settings)?)))
+}
+
+fn merge_json(value: &mut Value, merge: &Value, at: &str) -> Result<()> {
+    match (value, merge) {
+        (Value::Object(value), Value::Object(merge)) => {
+            for (key, merge) in merge {
+                let at = if at.is_empty() { key.clone() } else { format!("{}.{}", at, key) };
+                match value.get_mut(key) {
+                    Some(existing) => merge_json(existing, merge, &at)?,
+                    None => {
+                        value.insert(key.clone(), merge.clone());
+                    }
+                }
+            }
+        }
+        (Value::Array(items), Value::Array(merge)) => {
+            for item in merge {
+                if !items.contains(item) {
+                    items.push(item.clone());
+                }
+            }
+        }
+        (_, Value::Object(_)) => bail!("{} must be an object", at),
+        (_, Value::Array(_)) => bail!("{} must be an array", at),
+        // Values already set are the user's to keep
+        _ => {}
+    }
+    Ok(())
+}
+
+const BEGIN: &str = "<!-- panko:begin";
+const END: &str = "<!-- panko:end -->";
+
+fn marked_section(section: &str) -> String {
+    format!("{} (written by `panko init`; refresh with --update) -->\n{}\n{}\n", BEGIN, section.trim_end(), END)
+}
+
+/// Byte range of the panko section in `text`: between markers (inclusive,
+/// with the newline after the end marker), or else an unmarked section
+/// starting with the template's heading line and running to the next heading
+/// of the same or a higher level.
+fn find_section(text: &str, section: &str) -> Option<(usize, usize)> {
+    if let Some(start) = line_starting_with(text, BEGIN) {
+        let end = text[start..].find(END).map(|i| start + i + END.len())?;
+        let end = if text[end..].starts_with('\n') { end + 1 } else { end };
+        return Some((start, end));
+    }
+
+    let heading = section.lines().next().filter(|line| line.starts_with('#'))?;
+    let start = line_starting_with(text, heading).filter(|&i| text[i..].lines().next() == Some(heading))?;
+    let level = heading_level(heading);
+    let mut end = text.len();
+    let mut offset = start;
+    let mut in_code = false;
+    for (i, line) in text[start..].split_inclusive('\n').enumerate() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+        }
+        if i > 0 && !in_code && heading_level(line).is_some_and(|l| Some(l) <= level) {
+            end = offset;
+            break;
+        }
+        offset += line.len();
+    }
+    // Blank lines before the next heading stay outside the section
+    let end = start + text[start..end].trim_end().len();
+    Some((start, if text[end..].starts_with('\n') { end + 1 } else { end }))
+}
+
+fn line_starting_with(text: &str, prefix: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        if line.starts_with(prefix) {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+fn heading_level(line: &str) -> Option<usize> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    (level > 0 && line[level..].starts_with(' ')).then_some(level)
+}
+
+/// A unified diff of a planned file against what is on disk.
+pub fn preview(planned: &Planned, workdir: &Path) -> String {
+    let name = planned.path.strip_prefix(workdir).unwrap_or(&planned.path).display().to_string();
+    let old = planned.old.as_deref().unwrap_or_default();
+    if old == planned.new {
+        return String::new();
+    }
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = planned.new.lines().collect();
+
+    // Each plan changes one region, so one hunk around it is the whole diff
+    let prefix = old_lines.iter().zip(&new_lines).take_while(|(a, b)| a == b).count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let start = prefix.saturating_sub(3);
+    let old_end = (old_lines.len() - suffix + 3).min(old_lines.len());
+    let new_end = (new_lines.len() - suffix + 3).min(new_lines.len());
+
+    let from = if planned.old.is_some() { format!("a/{}", name) } else { "/dev/null".to_string() };
+    let mut out = format!("--- {}\n+++ b/{}\n", from, name);
+    let range = |start: usize, end: usize| {
+        let len = end - start;
+        format!("{},{}", if len == 0 { start } else { start + 1 }, len)
+    };
+    out.push_str(&format!("@@ -{} +{} @@\n", range(start, old_end), range(start, new_end)));
+    for line in &old_lines[start..prefix] {
+        out.push_str(&format!(" {}\n", line));
+    }
+    for line in &old_lines[prefix..old_lines.len() - suffix] {
+        out.push_str(&format!("-{}\n", line));
+    }
+    for line in &new_lines[prefix..new_lines.len() - suffix] {
+        out.push_str(&format!("+{}\n", line));
+    }
+    for line in &new_lines[new_lines.len() - suffix..new_end] {
+        out.push_str(&format!(" {}\n", line));
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    fn file(section: &str) -> TemplateFile {
+        TemplateFile { path: "AGENTS.md".to_string(), header: "# Project\n\n".to_string(), section: section.to_string() }
+    }
+
+    #[test]
+    fn sections_are_written_once_and_refreshed_in_place() {
+        let v1 = file("## panko\n\nrun panko resolve\n");
+        let (change, created) = render(None, &v1, false);
+        assert_eq!(change, Change::Created);
+        assert!(created.starts_with("# Project\n\n<!-- panko:begin"));
+        assert_eq!(render(Some(&created), &v1, true), (Change::UpToDate, created.clone()));
+
+        let (change, added) = render(Some("# Notes\n\nbe nice\n"), &v1, false);
+        assert_eq!(change, Change::Added);
+        assert!(added.starts_with("# Notes\n\nbe nice\n\n<!-- panko:begin"));
+        let edited = format!("{}\n## Later\n", added);
+
+        let v2 = file("## panko\n\nrun panko status addressed\n");
+        assert_eq!(render(Some(&edited), &v2, false), (Change::Outdated, edited.clone()));
+        let (change, updated) = render(Some(&edited), &v2, true);
+        assert_eq!(change, Change::Updated);
+        assert_eq!(updated, edited.replace("panko resolve", "panko status addressed"));
+
+        // A section from before markers is found by its heading, up to the next heading
+        let legacy = "# Notes\n\n## panko\n\n```sh\n# not a heading\n```\n\n## Later\n";
+        assert_eq!(render(Some(legacy), &v2, false).0, Change::Outdated);
+        let (_, migrated) = render(Some(legacy), &v2, true);
+        assert!(migrated.starts_with("# Notes\n\n<!-- panko:begin"));
+        assert!(migrated.ends_with("status addressed\n<!-- panko:end -->\n\n## Later\n"));
+
+        let planned = Planned { path: "/repo/AGENTS.md".into(), change, old: Some(edited), new: updated };
+        let diff = preview(&planned, Path::new("/repo"));
+        assert!(diff.starts_with("--- a/AGENTS.md\n+++ b/AGENTS.md\n@@ -5,7 +5,7 @@\n"));
+        assert!(diff.contains("\n-run panko resolve\n+run panko status addressed\n"));
+    }
+
+    #[test]
+    fn user_templates_add_and_replace_targets() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!("panko-init-{}-{}", std::process::id(), ts));
+        fs::create_dir_all(&dir).unwrap();
+        let template = "description = \"Ours\"\n[[files]]\npath = \"docs/AI.md\"\nsection = \"## panko\"\n";
+        fs::write(dir.join("codex.toml"), template).unwrap();
+        fs::write(dir.join("team.toml"), template).unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let builtin = templates(None).unwrap();
+        assert_eq!(builtin.len(), BUILTIN.len());
+        let all = templates(Some(&dir)).unwrap();
+        assert_eq!(all.len(), BUILTIN.len() + 1);
+        let codex = all.iter().find(|t| t.name == "codex").unwrap();
+        assert_eq!((codex.description.as_str(), codex.source.as_ref()), ("Ours", Some(&dir.join("codex.toml"))));
+        assert!(all.iter().any(|t| t.name == "team"));
+
+        fs::write(dir.join("bad.toml"), template.replace("docs/AI.md", "../outside.md")).unwrap();
+        assert!(templates(Some(&dir)).is_err());
+        fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn settings_are_merged_without_replacing_anything() {
+        let ts = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!("panko-settings-{}-{}", std::process::id(), ts));
+        let builtin = templates(None).unwrap();
+        let target = |name: &str| builtin.iter().find(|t| t.name == name).unwrap();
+        assert_eq!(target("codex").files[0].section, INSTRUCTIONS);
+        assert_eq!(target("cursor").files[0].section, INSTRUCTIONS);
+        let claude = target("claude");
+
+        let created = plan(claude, &dir, false).unwrap();
+        let settings = created.last().unwrap();
+        assert_eq!((settings.path.clone(), settings.change), (dir.join(".claude/settings.json"), Change::Created));
+        assert!(settings.new.starts_with("{\n  \"$schema\""));
+
+        // An existing settings.local.json is merged into, keeping the user's values
+        let local = dir.join(".claude/settings.local.json");
+        fs::create_dir_all(dir.join(".claude")).unwrap();
+        fs::write(&local, r#"{"permissions": {"allow": ["Bash(ls*)"]}, "env": {"PANKO_AUTHOR_KIND": "human"}}"#).unwrap();
+        let planned = plan(claude, &dir, false).unwrap();
+        let merged = planned.last().unwrap();
+        assert_eq!((merged.path.clone(), merged.change), (local.clone(), Change::Merged));
+        let json: Value = serde_json::from_str(&merged.new).unwrap();
+        assert_eq!(json["permissions"]["allow"][0], "Bash(ls*)");
+        assert!(json["permissions"]["allow"].as_array().unwrap().contains(&"Bash(panko reply*)".into()));
+        assert_eq!(json["env"]["PANKO_AUTHOR_KIND"], "human");
+        apply(&planned).unwrap();
+        assert_eq!(plan(claude, &dir, false).unwrap().last().unwrap().change, Change::UpToDate);
+
+        fs::write(&local, r#"{"permissions": ["Bash(ls*)"]}"#).unwrap();
+        let err = plan(claude, &dir, false).unwrap_err();
+        assert!(format!("{:#}", err).ends_with("permissions must be an object"), "{:#}", err);
+        fs::remove_dir_all(dir).ok();
+    }
+}
@@ -17,18 +17,24 @@ pub enum Context {
     DiffView = 2,
     /// Filter input has focus
     FilterInput = 3,
+    /// Comments panel has focus
+    CommentsPanel = 4,
     /// Visual selection mode
-    Visual = 4,
+    Visual = 5,
     /// Comment input mode (new comment or reply)
-    CommentInput = 5,
+    CommentInput = 6,
     /// A comment is focused (can reply, resolve, delete)
-    CommentFocused = 6,
+    CommentFocused = 7,
     /// Help overlay is shown
-    Help = 7,
+    Help = 8,
     /// Theme picker overlay is shown
-    ThemePicker = 8,
+    ThemePicker = 9,
     /// Fuzzy search overlay is shown
-    FuzzySearch = 9,
+    FuzzySearch = 10,
+    /// Submit review dialog is shown
+    ReviewSubmit = 11,
+    /// Branch conversation panel is shown
+    Conversation = 12,
 }
 
 /// Categories for grouping keybindings in help display.
@@ -72,17 +78,20 @@ pub enum Action {
     GotoBottom,
     NextFile,
     PrevFile,
+    NextUncoveredLine,
 
     // Focus
     SwitchPane,
     FocusFileTree,
     FocusDiffView,
     FocusFilter,
+    FocusCommentsPanel,
 
     // View toggles
     ToggleSplitView,
     ToggleSidebar,
     ToggleComments,
+    ToggleCommentsPanel,
     CycleDiffSource,
 
     // File actions
@@ -94,11 +103,26 @@ pub enum Action {
     EnterVisualMode,
     ExitVisualMode,
     StartComment,
+    StartFileComment,
+    ToggleConversation,
+    CycleSeverity,
     SubmitInput,
     CancelInput,
     ReplyToComment,
     ToggleResolved,
     DeleteComment,
+    NextOpenComment,
+    PrevOpenComment,
+
+    // Comments panel
+    JumpToComment,
+    CyclePanelStatus,
+    CyclePanelAuthor,
+
+    // Reviews
+    StartReview,
+    CycleVerdict,
+    DiscardReview,
 
     // Filter input
     FilterBackspace,
@@ -341,12 +365,14 @@ pub fn build_default_keymap() -> Keymap {
     km.bind(ch('G', Action::GotoBottom).help(Navigation, "Go to bottom"));
     km.bind(ch('n', Action::NextFile).help(Navigation, "Next file"));
     km.bind(ch('p', Action::PrevFile).help(Navigation, "Previous file"));
+    km.bind(ch('U', Action::NextUncoveredLine).help(Navigation, "Next uncovered added line"));
 
     // === Actions (shown in help) ===
     km.bind(key(KeyCode::Enter, Action::SelectFile).help(Actions, "Select file / toggle collapse"));
     km.bind(key(KeyCode::Tab, Action::SwitchPane).help(Actions, "Switch pane focus"));
     km.bind(ch('1', Action::FocusFileTree).help(Actions, "Focus file tree"));
     km.bind(ch('2', Action::FocusDiffView).help(Actions, "Focus diff view"));
+    km.bind(ch('3', Action::FocusCommentsPanel).help(Actions, "Focus comments panel"));
     km.bind(ch('/', Action::FocusFilter).help(Actions, "Focus filter input"));
     km.bind(ch('x', Action::ToggleViewed).help(Actions, "Mark file as viewed"));
     km.bind(ch('c', Action::ToggleCollapse).help(Actions, "Collapse/expand file"));
@@ -362,7 +388,13 @@ pub fn build_default_keymap() -> Keymap {
     km.bind(ch('r', Action::ReplyToComment).in_context(Context::CommentFocused).help(Comments, "Reply to comment"));
     km.bind(ch('R', Action::ToggleResolved).help(Comments, "Toggle comment resolved"));
     km.bind(ch('D', Action::DeleteComment).in_context(Context::CommentFocused).help(Comments, "Delete comment"));
-    km.bind(ch('C', Action::ToggleComments).help(Comments, "Show/hide comments"));
+    km.bind(ch('C', Action::ToggleComments).help(Comments, "Filter/hide comments"));
+    km.bind(ch('F', Action::StartFileComment).in_context(Context::DiffView).help(Comments, "Comment on current file"));
+    km.bind(ch('M', Action::ToggleConversation).help(Comments, "Branch conversation"));
+    km.bind(ch('L', Action::ToggleCommentsPanel).help(Comments, "Comments panel"));
+    km.bind(ch(']', Action::NextOpenComment).help(Comments, "Next open comment"));
+    km.bind(ch('[', Action::PrevOpenComment).help(Comments, "Previous open comment"));
+    km.bind(ch('S', Action::StartReview).help(Comments, "Start / submit review"));
 
     // === General (shown in help) ===
     km.bind(ch('?', Action::ShowHelp).help(General, "Toggle help"));
@@ -426,6 +458,18 @@ pub fn build_default_keymap() -> Keymap {
     km.bind(key(KeyCode::Enter, Action::SubmitInput).in_context(Context::FilterInput));
     km.bind(key(KeyCode::Backspace, Action::FilterBackspace).in_context(Context::FilterInput));
 
+    // === Comments panel ===
+    km.bind(key(KeyCode::Down, Action::MoveDown).in_context(Context::CommentsPanel));
+    km.bind(ch('j', Action::MoveDown).in_context(Context::CommentsPanel));
+    km.bind(key(KeyCode::Up, Action::MoveUp).in_context(Context::CommentsPanel));
+    km.bind(ch('k', Action::MoveUp).in_context(Context::CommentsPanel));
+    km.bind(ch('g', Action::GotoTop).in_context(Context::CommentsPanel));
+    km.bind(ch('G', Action::GotoBottom).in_context(Context::CommentsPanel));
+    km.bind(key(KeyCode::Enter, Action::JumpToComment).in_context(Context::CommentsPanel));
+    km.bind(ch('f', Action::CyclePanelStatus).in_context(Context::CommentsPanel));
+    km.bind(ch('a', Action::CyclePanelAuthor).in_context(Context::CommentsPanel));
+    km.bind(key(KeyCode::Esc, Action::ToggleCommentsPanel).in_context(Context::CommentsPanel));
+
     // === Visual mode ===
     km.bind(key(KeyCode::Esc, Action::ExitVisualMode).in_context(Context::Visual));
     km.bind(key(KeyCode::Down, Action::MoveDown).in_context(Context::Visual));
@@ -438,6 +482,23 @@ pub fn build_default_keymap() -> Keymap {
     km.bind(key(KeyCode::Esc, Action::CancelInput).in_context(Context::CommentInput));
     km.bind(key(KeyCode::Enter, Action::SubmitInput).in_context(Context::CommentInput));
     km.bind(key(KeyCode::Backspace, Action::InputBackspace).in_context(Context::CommentInput));
+    km.bind(key(KeyCode::Tab, Action::CycleSeverity).in_context(Context::CommentInput));
+
+    // === Branch conversation panel ===
+    km.bind(key(KeyCode::Esc, Action::ToggleConversation).in_context(Context::Conversation));
+    km.bind(ch('q', Action::ToggleConversation).in_context(Context::Conversation));
+    km.bind(ch('c', Action::StartComment).in_context(Context::Conversation));
+    km.bind(ch('j', Action::MoveDown).in_context(Context::Conversation));
+    km.bind(ch('k', Action::MoveUp).in_context(Context::Conversation));
+    km.bind(key(KeyCode::Down, Action::MoveDown).in_context(Context::Conversation));
+    km.bind(key(KeyCode::Up, Action::MoveUp).in_context(Context::Conversation));
+
+    // === Submit review dialog ===
+    km.bind(key(KeyCode::Esc, Action::CancelInput).in_context(Context::ReviewSubmit));
+    km.bind(key(KeyCode::Enter, Action::SubmitInput).in_context(Context::ReviewSubmit));
+    km.bind(key(KeyCode::Backspace, Action::InputBackspace).in_context(Context::ReviewSubmit));
+    km.bind(key(KeyCode::Tab, Action::CycleVerdict).in_context(Context::ReviewSubmit));
+    km.bind(ch('d', Action::DiscardReview).with_ctrl().in_context(Context::ReviewSubmit));
 
     // === Fuzzy search mode ===
     // '/' in DiffView opens fuzzy search (higher specificity than Global FocusFilter)
@@ -493,6 +554,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::field_reassign_with_default)]
     fn test_ctrl_modifier() {
         let km = build_default_keymap();
         let contexts = vec![Context::Global, Context::DiffView];
@@ -577,4 +639,18 @@ mod tests {
         let action = km.lookup(KeyCode::Char('k'), KeyModifiers::default(), &contexts);
         assert_eq!(action, Some(Action::MoveUp));
     }
+
+    #[test]
+    fn test_comments_panel_enter_jumps() {
+        let km = build_default_keymap();
+
+        let contexts = vec![Context::Global, Context::CommentsPanel];
+        let action = km.lookup(KeyCode::Enter, KeyModifiers::default(), &contexts);
+        assert_eq!(action, Some(Action::JumpToComment));
+
+        // Comment focus inside the diff still takes precedence over the panel
+        let contexts = vec![Context::Global, Context::CommentsPanel, Context::CommentFocused];
+        let action = km.lookup(KeyCode::Char('r'), KeyModifiers::default(), &contexts);
+        assert_eq!(action, Some(Action::ReplyToComment));
+    }
 }
@@ -5,15 +5,27 @@
 
 mod adapters;
 mod app;
+mod archive;
+mod batch;
+mod diagnostics;
 mod domain;
+mod export;
+mod hooks;
+mod init;
 mod keymap;
+mod mcp;
+mod output;
 mod ports;
+mod reviewer;
 mod search;
+mod sync;
 mod ui;
+mod watch;
 
-use adapters::{CrosstermTerminal, Git2Repo, JjRepo, NotifyFileWatcher, SqliteStateStore};
-use anyhow::{Context, Result};
-use clap::{Parser, Subcommand};
+use adapters::{CrosstermTerminal, Git2Repo, GitNotes, JjRepo, NotifyFileWatcher, SqliteStateStore};
+use anyhow::{bail, Context, Result};
+use clap::builder::PossibleValue;
+use clap::{Parser, Subcommand, ValueEnum};
 use ports::{GitRepo, StateStore};
 use ui::theme;
 use crossterm::{
@@ -21,10 +33,16 @@ use crossterm::{
     execute,
     terminal::{disable_raw_mode, LeaveAlternateScreen},
 };
-use std::io;
+use domain::branch_preview::BranchSummary;
+use domain::context::CodeContext;
+use domain::progress::ReviewProgress;
+use domain::policy::{Actor, CommentAction, Policy};
+use std::collections::HashMap;
+use std::fmt::Write as _;
+use std::io::{self, IsTerminal};
 use std::panic;
 use std::sync::Arc;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[derive(Parser, Debug)]
 #[command(name = "panko")]
@@ -43,37 +61,109 @@ struct Args {
     #[arg(long, global = true)]
     theme: Option<String>,
 
+    /// lcov or Cobertura report to mark added lines covered/uncovered
+    /// (default: `panko.coverage` in git config)
+    #[arg(long, value_name = "PATH")]
+    coverage: Option<PathBuf>,
+
     #[command(subcommand)]
     command: Option<Command>,
 }
 
-#[derive(Subcommand, Debug, Clone)]
-enum InitTarget {
-    /// Set up Claude Code integration (.claude/skills + settings.json)
-    Claude,
-    /// Set up OpenAI Codex integration (AGENTS.md)
-    Codex,
-    /// Set up OpenCode integration (AGENTS.md)
-    Opencode,
+/// Options of `panko init`; each applies to some targets only.
+#[derive(clap::Args, Debug, Clone, Copy)]
+struct InitFlags {
+    /// claude: register the `panko mcp` server (.mcp.json) instead of allowing panko shell commands
+    #[arg(long)]
+    mcp: bool,
+
+    /// Refresh panko sections written by an earlier `panko init`
+    #[arg(long)]
+    update: bool,
+
+    /// Show the changes as a diff without writing anything
+    #[arg(long)]
+    dry_run: bool,
+
+    /// hooks: only block on unresolved blocking comments
+    #[arg(long)]
+    blocking: bool,
+
+    /// hooks: also block while changed files are unviewed
+    #[arg(long)]
+    viewed: bool,
 }
 
 #[derive(Subcommand, Debug)]
 enum Command {
     /// Initialize panko integration for AI coding tools
     Init {
-        #[command(subcommand)]
-        target: InitTarget,
+        /// claude, codex, opencode, cursor, gemini, aider, a template in
+        /// ~/.config/panko/init/, or hooks (omit to list them)
+        target: Option<String>,
+
+        #[command(flatten)]
+        flags: InitFlags,
     },
 
     /// List all comments for the current branch (for AI agents)
     Comments {
-        /// Output format: text (default) or json
-        #[arg(short, long, default_value = "text")]
-        format: String,
-
-        /// Filter by status: all (default), open, resolved
-        #[arg(short, long, default_value = "all")]
-        status: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Filter by status (unresolved = anything not resolved or won't fix)
+        #[arg(short, long, value_enum, ignore_case = true, default_value_t = StatusFilter::All)]
+        status: StatusFilter,
+
+        /// Only show comments with this severity
+        #[arg(long, value_enum, ignore_case = true)]
+        severity: Option<domain::Severity>,
+
+        /// Only show comments carrying this label (repeatable; all must match)
+        #[arg(long = "label")]
+        labels: Vec<String>,
+
+        /// Only comments on files matching this glob, e.g. "src/**/*.rs"
+        /// (`*` stays within a directory; patterns without `/` match file names)
+        #[arg(long, value_name = "GLOB")]
+        file: Option<String>,
+
+        /// Only comments by this author (repeatable; any may match)
+        #[arg(long = "author")]
+        authors: Vec<String>,
+
+        /// Only comments created at or after: RFC 3339, YYYY-MM-DD, or an age (30m, 12h, 3d, 2w)
+        #[arg(long, value_name = "TIME", value_parser = parse_time_arg)]
+        since: Option<i64>,
+
+        /// Only comments created at or before (same formats as --since)
+        #[arg(long, value_name = "TIME", value_parser = parse_time_arg)]
+        until: Option<i64>,
+
+        /// Only comments with at least one reply
+        #[arg(long)]
+        has_replies: bool,
+
+        /// Only threads whose last message is not yours (git user): the ones waiting on you
+        #[arg(long)]
+        unreplied: bool,
+
+        /// Only these comment IDs (repeatable)
+        #[arg(long = "id")]
+        ids: Vec<i64>,
+
+        /// Sort order (ascending)
+        #[arg(long, value_enum, default_value_t = domain::query::CommentSort::File)]
+        sort: domain::query::CommentSort,
+
+        /// Reverse the sort order
+        #[arg(long)]
+        reverse: bool,
+
+        /// Include the anchored source lines plus N lines around them, from the working tree
+        #[arg(long, value_name = "N")]
+        context: Option<usize>,
     },
 
     /// Resolve a comment by ID (for AI agents)
@@ -102,16 +192,27 @@ enum Command {
         author: Option<String>,
     },
 
-    /// Add a new comment (for AI agents)
+    /// Add a new comment on lines, a whole file, or the branch (for AI agents)
     Comment {
         /// File path (relative to repo root)
-        file: String,
+        #[arg(required_unless_present = "general")]
+        file: Option<String>,
 
         /// Start line number (1-indexed, in the NEW version of the file)
-        start: usize,
+        #[arg(required_unless_present_any = ["file_level", "general"])]
+        start: Option<usize>,
 
         /// End line number (1-indexed, in the NEW version of the file)
-        end: usize,
+        #[arg(required_unless_present_any = ["file_level", "general"])]
+        end: Option<usize>,
+
+        /// Comment on the whole file instead of a line range
+        #[arg(long, conflicts_with_all = ["start", "end", "general"])]
+        file_level: bool,
+
+        /// Comment on the branch as a whole (conversation thread)
+        #[arg(long, conflicts_with_all = ["file", "start", "end"])]
+        general: bool,
 
         /// Comment message
         #[arg(short, long)]
@@ -120,6 +221,47 @@ enum Command {
         /// Author name (default: git user or "Agent")
         #[arg(short, long)]
         author: Option<String>,
+
+        /// Severity
+        #[arg(long, value_enum, ignore_case = true)]
+        severity: Option<domain::Severity>,
+
+        /// Free-form label (repeatable)
+        #[arg(long = "label")]
+        labels: Vec<String>,
+    },
+
+    /// Claim a comment: assign it and mark it in progress (for AI agents)
+    Claim {
+        /// Comment ID to claim
+        id: i64,
+
+        /// Who is claiming it (default: git user or "Agent")
+        #[arg(short, long)]
+        assignee: Option<String>,
+
+        /// Take over a comment already claimed by someone else
+        #[arg(long)]
+        force: bool,
+    },
+
+    /// Summarize review progress, or set a comment's status with `<id> <status>`
+    Status {
+        /// Comment ID whose status to set
+        #[arg(requires = "status")]
+        id: Option<i64>,
+
+        /// New status: open, in-progress, addressed, wont-fix, resolved
+        #[arg(value_enum, ignore_case = true)]
+        status: Option<domain::CommentStatus>,
+
+        /// Output format for the summary
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text, conflicts_with = "id")]
+        format: OutputFormat,
+
+        /// One line, for shell prompts and status bars
+        #[arg(long, conflicts_with_all = ["id", "format"])]
+        short: bool,
     },
 
     /// Delete a comment by ID (for AI agents)
@@ -128,19 +270,350 @@ enum Command {
         id: i64,
     },
 
+    /// Exit non-zero while the review is unfinished: unresolved comments, or unviewed files (for CI and git hooks)
+    Check {
+        /// Only fail on unresolved blocking comments
+        #[arg(long)]
+        blocking: bool,
+
+        /// Also fail while changed files are unviewed
+        #[arg(long)]
+        viewed: bool,
+
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+    },
+
+    /// Stream comment events (added, replies, resolved, unresolved, deleted) as JSON lines
+    Watch {
+        /// Replay events after this cursor (default: only new events)
+        #[arg(long)]
+        since: Option<i64>,
+
+        /// Exit once no comments are open or in progress
+        #[arg(long)]
+        until_open_zero: bool,
+
+        /// Milliseconds between checks for new events
+        #[arg(long, default_value_t = 500)]
+        interval: u64,
+    },
+
+    /// Serve review comments to coding agents over the Model Context Protocol (stdio)
+    Mcp {
+        /// Author of comments and replies made through the server (default: git user or "Agent")
+        #[arg(short, long)]
+        author: Option<String>,
+    },
+
+    /// Apply a JSON array of operations from stdin in one transaction (for AI agents)
+    ///
+    /// Operations: comment, reply, resolve, unresolve, delete. Prints a result
+    /// per operation; exits 1 if any failed.
+    Batch {
+        /// Roll back every operation if any of them fails
+        #[arg(long)]
+        atomic: bool,
+
+        /// Default author (default: git user or "Agent")
+        #[arg(short, long)]
+        author: Option<String>,
+    },
+
     /// Show a specific comment thread by ID (for AI agents)
     Show {
         /// Comment ID to show
         id: i64,
 
-        /// Output format: text (default) or json
-        #[arg(short, long, default_value = "text")]
-        format: String,
+        /// Output format
+        #[arg(short, long, value_enum, default_value_t = OutputFormat::Text)]
+        format: OutputFormat,
+
+        /// Include the anchored source lines plus N lines around them, from the working tree
+        #[arg(long, value_name = "N")]
+        context: Option<usize>,
+    },
+
+    /// Inspect pending and submitted reviews, or run an automated reviewer with --with
+    #[command(args_conflicts_with_subcommands = true)]
+    Review {
+        #[command(subcommand)]
+        action: Option<ReviewAction>,
+
+        /// Reviewer command, run by the shell with the branch diff on stdin.
+        /// It prints findings as JSON lines: {"file", "line", "end_line", "body", "severity"}
+        #[arg(long = "with", value_name = "COMMAND")]
+        with: Option<String>,
+
+        /// Diff on the reviewer's stdin: json (as `panko diff -f json`) or text (unified diff)
+        #[arg(long, value_enum, default_value_t = ReviewerInput::Json)]
+        input: ReviewerInput,
+
+        /// Which changes: committed (merge-base to HEAD), uncommitted or all
+        #[arg(long, value_enum, default_value_t = app::DiffSource::Committed)]
+        source: app::DiffSource,
+
+        /// Author of the reviewer's comments (default: the command's program name)
+        #[arg(short, long)]
+        author: Option<String>,
+    },
+
+    /// List submitted reviews for the current branch
+    Reviews {
+        /// Output format (--json alone is short for --format json)
+        #[arg(
+            short,
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Text,
+            alias = "json",
+            num_args = 0..=1,
+            default_missing_value = "json"
+        )]
+        format: OutputFormat,
+    },
+
+    /// Print the branch diff, optionally with comment threads (for AI agents and pipelines)
+    Diff {
+        /// Only include files at or under these paths
+        paths: Vec<String>,
+
+        /// Output format: text (plain unified diff), color or json
+        #[arg(short, long, value_enum, default_value_t = DiffFormat::Text)]
+        format: DiffFormat,
+
+        /// Which changes: committed (merge-base to HEAD), uncommitted or all
+        #[arg(long, value_enum, default_value_t = app::DiffSource::Committed)]
+        source: app::DiffSource,
+
+        /// Interleave comment threads beneath the lines they are anchored to
+        #[arg(long)]
+        comments: bool,
+    },
+
+    /// Export this branch's review: a lossless JSON archive, an HTML report or an mbox
+    Export {
+        /// Output format: json (archive for `panko import`), html (report),
+        /// email (mailing-list replies, written to <branch>.mbox by default),
+        /// or sarif, checkstyle or junit (open comments, for CI dashboards)
+        #[arg(short, long, value_enum, default_value_t = ExportFormat::Json)]
+        format: ExportFormat,
+
+        /// Write to a file instead of stdout
+        #[arg(short, long)]
+        output: Option<String>,
+    },
+
+    /// Import an archive written by `panko export` (safe to run repeatedly)
+    Import {
+        /// Archive file ("-" for stdin)
+        file: String,
+
+        /// Branch to import into (default: the branch recorded in the archive)
+        #[arg(long)]
+        branch: Option<String>,
+    },
+
+    /// Add linter/compiler findings on changed lines as comments (SARIF or cargo JSON).
+    /// Re-importing reopens findings that came back and resolves ones that are gone
+    ImportDiagnostics {
+        /// Diagnostics file ("-" for stdin)
+        #[arg(default_value = "-")]
+        file: String,
+
+        /// Input format: auto (SARIF or cargo), sarif, cargo (`--message-format=json`, also rustc's) or clippy (`cargo clippy --message-format=json`)
+        #[arg(short, long, value_enum, default_value_t = DiagnosticsFormat::Auto)]
+        format: DiagnosticsFormat,
+
+        /// Changed lines to keep findings on: all (merge-base to working tree), committed or uncommitted
+        #[arg(long, value_enum, default_value_t = app::DiffSource::All)]
+        source: app::DiffSource,
+
+        /// Author every finding as this tool, e.g. clippy for `cargo clippy` output
+        #[arg(long)]
+        tool: Option<String>,
+    },
+
+    /// Share this branch's comments on a remote as git notes (refs/notes/panko)
+    PushNotes {
+        /// Remote name or URL
+        #[arg(default_value = "origin")]
+        remote: String,
+    },
+
+    /// Merge comments shared on a remote (git notes) into local state
+    PullNotes {
+        /// Remote name or URL
+        #[arg(default_value = "origin")]
+        remote: String,
+    },
+
+    /// Print the JSON Schema for `--format json` output and error documents
+    Schema,
+}
+
+#[derive(Subcommand, Debug)]
+enum ReviewAction {
+    /// Show the overall review status and each reviewer's verdict
+    Status {
+        /// Output format (--json alone is short for --format json)
+        #[arg(
+            short,
+            long,
+            value_enum,
+            default_value_t = OutputFormat::Text,
+            alias = "json",
+            num_args = 0..=1,
+            default_missing_value = "json"
+        )]
+        format: OutputFormat,
     },
 }
 
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// `panko diff` output: a unified diff, colored for terminals, or JSON.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum DiffFormat {
+    Text,
+    Color,
+    Json,
+}
+
+/// `panko export` output.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ExportFormat {
+    Json,
+    Html,
+    Email,
+    Sarif,
+    Checkstyle,
+    Junit,
+}
+
+/// The diff `panko review --with` gives the reviewer.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum ReviewerInput {
+    Json,
+    Text,
+}
+
+/// `panko import-diagnostics` input; auto tells SARIF from cargo output.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+enum DiagnosticsFormat {
+    Auto,
+    Sarif,
+    #[value(alias = "rustc")]
+    Cargo,
+    Clippy,
+}
+
+/// `--source` values, named as in `panko diff -f json` output.
+impl ValueEnum for app::DiffSource {
+    fn value_variants<'a>() -> &'a [Self] {
+        &[Self::Committed, Self::Uncommitted, Self::All]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        Some(PossibleValue::new(diff_source_name(*self)))
+    }
+}
+
+fn diff_source_name(source: app::DiffSource) -> &'static str {
+    match source {
+        app::DiffSource::Committed => "committed",
+        app::DiffSource::Uncommitted => "uncommitted",
+        app::DiffSource::All => "all",
+    }
+}
+
+/// `--status` filter: a single status, or a group of them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StatusFilter {
+    All,
+    Unresolved,
+    Is(domain::CommentStatus),
+}
+
+impl ValueEnum for StatusFilter {
+    fn value_variants<'a>() -> &'a [Self] {
+        use domain::CommentStatus::*;
+        &[
+            Self::All,
+            Self::Unresolved,
+            Self::Is(Open),
+            Self::Is(InProgress),
+            Self::Is(Addressed),
+            Self::Is(WontFix),
+            Self::Is(Resolved),
+        ]
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        match self {
+            Self::All => Some(PossibleValue::new("all")),
+            Self::Unresolved => Some(PossibleValue::new("unresolved")),
+            Self::Is(status) => status.to_possible_value(),
+        }
+    }
+}
+
+/// CLI values for domain enums, from their stable `as_str` identifiers.
+macro_rules! value_enum_from_as_str {
+    ($($ty:ty),*) => {$(
+        impl ValueEnum for $ty {
+            fn value_variants<'a>() -> &'a [Self] {
+                &<$ty>::ALL
+            }
+
+            fn to_possible_value(&self) -> Option<PossibleValue> {
+                Some(PossibleValue::new(self.as_str()))
+            }
+        }
+    )*};
+}
+
+value_enum_from_as_str!(domain::Severity, domain::query::CommentSort);
+
+impl ValueEnum for domain::CommentStatus {
+    fn value_variants<'a>() -> &'a [Self] {
+        &Self::ALL
+    }
+
+    fn to_possible_value(&self) -> Option<PossibleValue> {
+        // Also accept `_` in place of `-` (e.g. "wont_fix")
+        let value = PossibleValue::new(self.as_str());
+        Some(match self {
+            Self::InProgress => value.alias("in_progress"),
+            Self::WontFix => value.alias("wont_fix"),
+            _ => value,
+        })
+    }
+}
+
+fn parse_time_arg(s: &str) -> Result<i64, String> {
+    domain::query::parse_time(s, chrono::Utc::now().timestamp_millis()).ok_or_else(|| {
+        format!("invalid time '{}' (use RFC 3339, YYYY-MM-DD, or an age like 30m, 12h, 3d, 2w)", s)
+    })
+}
+
 fn main() -> Result<()> {
     let args = Args::parse();
+    // JSON consumers get failures as error documents with a typed exit code,
+    // including ones before the command runs, like a missing repository
+    let json = args.command.as_ref().is_some_and(wants_json);
+    match run(args) {
+        Err(err) if json => std::process::exit(output::print_error(&err)),
+        result => result,
+    }
+}
+
+fn run(args: Args) -> Result<()> {
     theme::init_from_env_and_arg(args.theme.as_deref())
         .map_err(|err| anyhow::anyhow!(err))?;
 
@@ -150,7 +623,7 @@ fn main() -> Result<()> {
 
     // Handle subcommands (CLI mode for agents)
     if let Some(command) = args.command {
-        return run_cli_command(command, git.as_ref());
+        return run_cli_command(command, git.as_ref(), args.base.as_deref());
     }
 
     // TUI mode: set up panic hook to restore terminal on panic
@@ -185,26 +658,67 @@ fn main() -> Result<()> {
         Err(_) => None,
     };
 
+    // Coverage overlay: an explicit report must load, a configured one may not exist yet
+    let coverage = match args.coverage {
+        Some(path) => Some((app::load_coverage(&path, git.as_ref())?, path)),
+        None => configured_coverage_path(git.as_ref()).and_then(|path| {
+            match app::load_coverage(&path, git.as_ref()) {
+                Ok(coverage) => Some((coverage, path)),
+                Err(e) => {
+                    eprintln!("Warning: {:#}. Coverage will not be shown.", e);
+                    None
+                }
+            }
+        }),
+    };
+
+    // Automatic sharing: pick up teammates' comments now, publish ours on exit
+    let auto_sync = state_store.as_ref().and_then(|_| auto_sync_notes(git.as_ref()));
+    if let (Some(store), Some((notes, remote))) = (&state_store, &auto_sync) {
+        sync_notes_or_warn(store.as_ref(), notes, git.as_ref(), remote, false);
+    }
+
     // Initialize terminal
     let mut terminal = CrosstermTerminal::new().context("Failed to initialize terminal")?;
 
     // Create and run app
-    let mut app = app::App::new(git.as_ref(), args.base.as_deref(), state_store, file_watcher)
+    let mut app = app::App::new(git.as_ref(), args.base.as_deref(), state_store.clone(), file_watcher)
         .context("Failed to initialize app. Do you have commits ahead of the base branch?")?;
+    if let Some((coverage, path)) = coverage {
+        app = app.with_coverage(path, coverage);
+    }
 
     let result = app.run(&mut terminal, git.as_ref());
 
     // Terminal cleanup happens in Drop
+    drop(terminal);
+
+    if let (Some(store), Some((notes, remote))) = (&state_store, &auto_sync) {
+        sync_notes_or_warn(store.as_ref(), notes, git.as_ref(), remote, true);
+    }
 
     result
 }
 
 /// Run CLI commands (for AI agents)
-fn run_cli_command(command: Command, git: &dyn GitRepo) -> Result<()> {
+fn run_cli_command(command: Command, git: &dyn GitRepo, base: Option<&str>) -> Result<()> {
     // Handle init command separately (doesn't need branch/state)
-    if let Command::Init { target } = command {
+    if let Command::Init { target, flags } = command {
         let workdir = git.workdir()?;
-        return run_init_command(target, &workdir);
+        return run_init_command(target.as_deref(), flags, &workdir);
+    }
+    if let Command::Schema = command {
+        print!("{}", output::SCHEMA);
+        return Ok(());
+    }
+    if let Command::Mcp { author } = command {
+        // Long-running, so comments are not synced automatically; stdout carries the protocol
+        let state_store = SqliteStateStore::new().context("Failed to initialize state store")?;
+        // Clients of the server are agents unless PANKO_AUTHOR_KIND says otherwise
+        let actor = cli_actor(git, Some(domain::AuthorKind::Agent))?;
+        let actor = Actor { name: author.unwrap_or(actor.name), ..actor };
+        let server = mcp::Server::new(git, &state_store, base, actor)?;
+        return server.serve(io::stdin().lock(), io::stdout().lock());
     }
 
     let state_store = SqliteStateStore::new()
@@ -213,88 +727,728 @@ fn run_cli_command(command: Command, git: &dyn GitRepo) -> Result<()> {
     let repo_path = git.workdir()?.to_string_lossy().trim_end_matches('/').to_string();
     let branch = git.current_branch()?;
 
-    match command {
-        Command::Init { .. } => unreachable!(),
+    // Automatic sharing (git config panko.autoSync): pull first, push after changes
+    let auto_sync = match command {
+        Command::PushNotes { .. } | Command::PullNotes { .. } => None,
+        _ => auto_sync_notes(git),
+    };
+    if let Some((notes, remote)) = &auto_sync {
+        sync_notes_or_warn(&state_store, notes, git, remote, false);
+    }
+    let mutating = matches!(
+        command,
+        Command::Resolve { .. }
+            | Command::Unresolve { .. }
+            | Command::Reply { .. }
+            | Command::Comment { .. }
+            | Command::Claim { .. }
+            | Command::Status { id: Some(_), .. }
+            | Command::Delete { .. }
+            | Command::Batch { .. }
+            | Command::Review { with: Some(_), .. }
+            | Command::Import { .. }
+            | Command::ImportDiagnostics { .. }
+    );
+    // Set by `panko batch` and `panko check` to exit 1 after a normal report
+    let mut failed = false;
+    let actor = cli_actor(git, None)?;
 
-        Command::Comments { format, status } => {
+    match command {
+        Command::Init { .. } | Command::Schema | Command::Mcp { .. } => unreachable!(),
+
+        Command::Comments {
+            format,
+            status,
+            severity,
+            labels,
+            file,
+            authors,
+            since,
+            until,
+            has_replies,
+            unreplied,
+            ids,
+            sort,
+            reverse,
+            context,
+        } => {
             let comments = state_store.get_comments(&repo_path, &branch)?;
+            let query = domain::query::CommentQuery {
+                statuses: match status {
+                    StatusFilter::All => None,
+                    StatusFilter::Unresolved => Some(
+                        domain::CommentStatus::ALL.into_iter().filter(|s| !s.is_closed()).collect(),
+                    ),
+                    StatusFilter::Is(status) => Some(vec![status]),
+                },
+                severity,
+                labels,
+                file,
+                authors,
+                since,
+                until,
+                has_replies,
+                unreplied_by: unreplied.then(|| get_git_user(git)),
+                ids,
+            };
+
+            let mut filtered: Vec<_> = comments.iter().filter(|c| query.matches(c)).collect();
+            sort.sort(&mut filtered);
+            if reverse {
+                filtered.reverse();
+            }
 
-            let filtered: Vec<_> = comments
-                .iter()
-                .filter(|c| match status.as_str() {
-                    "open" => !c.resolved,
-                    "resolved" => c.resolved,
-                    _ => true, // "all"
-                })
-                .collect();
-
-            if format == "json" {
-                print_comments_json(&filtered);
+            let contexts = context.map(|radius| load_contexts(&repo_path, &filtered, radius));
+            if format == OutputFormat::Json {
+                output::print(&output::CommentList {
+                    comments: filtered
+                        .iter()
+                        .map(|c| output::CommentEntry {
+                            comment: c,
+                            context: contexts.as_ref().and_then(|ctx| ctx.get(&c.id)),
+                        })
+                        .collect(),
+                })?;
             } else {
-                print_comments_text(&filtered);
+                print_comments_text(&filtered, contexts.as_ref());
             }
         }
 
         Command::Resolve { id } => {
+            let status = domain::CommentStatus::Resolved;
+            authorize(&state_store, &repo_path, &branch, &actor, id, CommentAction::SetStatus(status))?;
             state_store.resolve_comment(id)?;
             println!("Resolved comment #{}", id);
         }
 
         Command::Unresolve { id } => {
+            let status = domain::CommentStatus::Open;
+            authorize(&state_store, &repo_path, &branch, &actor, id, CommentAction::SetStatus(status))?;
             state_store.unresolve_comment(id)?;
             println!("Unresolved comment #{}", id);
         }
 
         Command::Reply { id, message, author } => {
-            let author = author.unwrap_or_else(|| get_git_user(git));
+            authorize(&state_store, &repo_path, &branch, &actor, id, CommentAction::Reply)?;
+            let author = author.unwrap_or(actor.name);
             let reply_id = state_store.add_reply(ports::NewReply {
                 comment_id: id,
                 body: message,
                 author,
+                author_kind: actor.kind,
+                uuid: domain::new_uuid(),
             })?;
             println!("Added reply #{} to comment #{}", reply_id, id);
         }
 
-        Command::Comment { file, start, end, message, author } => {
-            let author = author.unwrap_or_else(|| get_git_user(git));
+        Command::Comment { file, start, end, message, author, severity, labels, .. } => {
+            let author = author.unwrap_or(actor.name);
+            // clap guarantees start/end are both present unless --file-level/--general
+            let line_range = start.zip(end);
+            let anchor = match (&file, line_range) {
+                (Some(file), Some(range)) => batch::line_anchor(&repo_path, file, range)?,
+                _ => None,
+            };
             let comment_id = state_store.add_comment(&repo_path, &branch, ports::NewComment {
                 file_path: file.clone(),
-                start_line: start,
-                end_line: end,
+                line_range,
+                anchor,
                 body: message,
                 author,
+                author_kind: actor.kind,
+                severity,
+                labels,
+                review_id: None,
+                uuid: domain::new_uuid(),
             })?;
-            println!("Added comment #{} on {} lines {}-{}", comment_id, file, start, end);
+            match (file, line_range) {
+                (Some(file), Some((start, end))) => {
+                    println!("Added comment #{} on {} lines {}-{}", comment_id, file, start, end)
+                }
+                (Some(file), None) => println!("Added file-level comment #{} on {}", comment_id, file),
+                (None, _) => println!("Added general comment #{}", comment_id),
+            }
+        }
+
+        Command::Claim { id, assignee, force } => {
+            let assignee = assignee.unwrap_or_else(|| get_git_user(git));
+            let comments = state_store.get_comments(&repo_path, &branch)?;
+            let Some(comment) = comments.iter().find(|c| c.id == id) else {
+                return Err(output::not_found(format!("Comment #{} not found", id)));
+            };
+            actor.check(CommentAction::Claim, comment.author_kind).map_err(output::forbidden)?;
+            if let Some(owner) = &comment.assignee {
+                if owner != &assignee && comment.status == domain::CommentStatus::InProgress && !force {
+                    bail!("Comment #{} is already claimed by {} (use --force to take it over)", id, owner);
+                }
+            }
+            state_store.assign_comment(id, Some(&assignee))?;
+            state_store.set_comment_status(id, domain::CommentStatus::InProgress)?;
+            println!("Claimed comment #{} for {}", id, assignee);
+        }
+
+        Command::Status { id: Some(id), status, .. } => {
+            // clap requires a status along with the ID
+            let status = status.expect("status is required with an id");
+            authorize(&state_store, &repo_path, &branch, &actor, id, CommentAction::SetStatus(status))?;
+            state_store.set_comment_status(id, status)?;
+            println!("Comment #{} is now {}", id, status.as_str());
+        }
+
+        Command::Status { id: None, format, short, .. } => {
+            let (preview, summary, progress) = load_status(git, &state_store, &repo_path, &branch, base)?;
+            if format == OutputFormat::Json {
+                output::print(&output::StatusReport {
+                    branch: &branch,
+                    base: &preview.base_branch,
+                    merge_base: &preview.merge_base,
+                    summary: &summary,
+                    progress: &progress,
+                })?;
+            } else if short {
+                println!("{}", status_line(&branch, &summary, &progress));
+            } else {
+                print_status_text(&preview, &summary, &progress);
+            }
         }
 
         Command::Delete { id } => {
+            authorize(&state_store, &repo_path, &branch, &actor, id, CommentAction::Delete)?;
             state_store.delete_comment(id)?;
             println!("Deleted comment #{}", id);
         }
 
-        Command::Show { id, format } => {
+        Command::Check { blocking, viewed, format } => {
+            // Without --viewed no diff is needed, so a missing base branch is fine
+            let diff = if viewed {
+                let (_, merge_base) = resolve_merge_base(git, base)?;
+                git.diff_to_base(&merge_base)?
+            } else {
+                domain::Diff::default()
+            };
+            let viewed_files = state_store.get_viewed_files(&repo_path, &branch)?;
+            let viewed_files: Vec<&str> = viewed_files.iter().map(|v| v.file_path.as_str()).collect();
             let comments = state_store.get_comments(&repo_path, &branch)?;
-            let comment = comments.iter().find(|c| c.id == id);
-
-            match comment {
-                Some(c) => {
-                    if format == "json" {
-                        print_comment_json(c);
-                    } else {
-                        print_comment_text(c);
+
+            let progress = domain::progress::review_progress(&diff, &viewed_files, &comments);
+            let rules = domain::progress::CheckRules { blocking_only: blocking, require_viewed: viewed };
+            let failures = domain::progress::check(&progress, rules);
+            if format == OutputFormat::Json {
+                output::print(&output::CheckReport {
+                    branch: &branch,
+                    passed: failures.is_empty(),
+                    failures: &failures,
+                })?;
+            } else if failures.is_empty() {
+                println!("✓ Review of {} is complete", branch);
+            } else {
+                println!("✗ Review of {} is not complete:", branch);
+                for failure in &failures {
+                    println!("  - {}", failure.message());
+                }
+                println!("\nSee `panko comments --status unresolved` (or `panko status`).");
+            }
+            failed = !failures.is_empty();
+        }
+
+        Command::Watch { since, until_open_zero, interval } => {
+            let options = watch::WatchOptions {
+                since,
+                until_open_zero,
+                interval: std::time::Duration::from_millis(interval),
+            };
+            watch::watch(&state_store, &repo_path, &branch, options, io::stdout().lock())?;
+        }
+
+        Command::Batch { atomic, author } => {
+            let input = io::read_to_string(io::stdin()).context("Failed to read stdin")?;
+            let operations = batch::parse(&input)?;
+            let actor = domain::policy::Actor { name: author.unwrap_or(actor.name), ..actor };
+            let outcome = batch::apply(&state_store, &repo_path, &branch, &actor, &operations, atomic)?;
+            output::print(&outcome)?;
+            failed = outcome.failed() > 0;
+        }
+
+        Command::Show { id, format, context } => {
+            let comments = state_store.get_comments(&repo_path, &branch)?;
+            let Some(c) = comments.iter().find(|c| c.id == id) else {
+                return Err(output::not_found(format!("Comment #{} not found", id)));
+            };
+
+            let contexts = context.map(|radius| load_contexts(&repo_path, &[c], radius));
+            if format == OutputFormat::Json {
+                output::print(&output::CommentShow {
+                    comment: output::CommentEntry {
+                        comment: c,
+                        context: contexts.as_ref().and_then(|ctx| ctx.get(&c.id)),
+                    },
+                })?;
+            } else {
+                print_comment_text(c, contexts.as_ref());
+            }
+        }
+
+        Command::Review { action: Some(ReviewAction::Status { format }), .. } => {
+            let reviews = state_store.get_reviews(&repo_path, &branch)?;
+            let pending = state_store.get_pending_review(&repo_path, &branch, &get_git_user(git))?;
+            let pending_drafts = match &pending {
+                Some(review) => Some((review.id, state_store.get_review_comments(review.id)?.len())),
+                None => None,
+            };
+
+            if format == OutputFormat::Json {
+                output::print(&output::ReviewStatusReport {
+                    status: domain::review::review_status(&reviews),
+                    reviewers: domain::review::latest_by_author(&reviews)
+                        .into_iter()
+                        .map(|r| output::Reviewer {
+                            author: &r.author,
+                            verdict: r.verdict,
+                            review_id: r.id,
+                            submitted_at: r.submitted_at,
+                        })
+                        .collect(),
+                    pending_review: pending_drafts
+                        .map(|(id, draft_count)| output::PendingReview { id, draft_count }),
+                })?;
+            } else {
+                print_review_status_text(&reviews, pending_drafts);
+            }
+        }
+
+        Command::Review { action: None, with: Some(command), input, source, author } => {
+            let (base_branch, merge_base, diff) = load_diff(git, base, source, &[])?;
+            let diff_input = match input {
+                ReviewerInput::Json => output::document(&output::DiffReport::new(
+                    &branch,
+                    &base_branch,
+                    &merge_base,
+                    diff_source_name(source),
+                    &diff.files,
+                    None,
+                ))?
+                .to_string(),
+                ReviewerInput::Text => export::unified::render(&diff, &[], false),
+            };
+
+            let reviewer_name = author.unwrap_or_else(|| reviewer::default_name(&command));
+            let env = [
+                ("PANKO_BRANCH", branch.as_str()),
+                ("PANKO_BASE", base_branch.as_str()),
+                ("PANKO_MERGE_BASE", merge_base.as_str()),
+            ];
+            let findings = reviewer::run(&command, diff_input.as_bytes(), &git.workdir()?, &env)?;
+            let findings = reviewer::parse(&findings)?;
+            let ingested = reviewer::ingest(&state_store, &repo_path, &branch, &reviewer_name, &findings)?;
+
+            print!(
+                "Added {} comment{} from {}",
+                ingested.added,
+                if ingested.added == 1 { "" } else { "s" },
+                reviewer_name
+            );
+            if ingested.duplicates > 0 {
+                print!(" ({} already reported)", ingested.duplicates);
+            }
+            println!();
+        }
+
+        Command::Review { action: None, with: None, .. } => {
+            return Err(output::usage("Expected a review subcommand or --with <command>"));
+        }
+
+        Command::Reviews { format } => {
+            let reviews = state_store.get_reviews(&repo_path, &branch)?;
+            let mut entries = Vec::with_capacity(reviews.len());
+            for review in &reviews {
+                entries.push((review, state_store.get_review_comments(review.id)?.len()));
+            }
+
+            if format == OutputFormat::Json {
+                output::print(&output::ReviewList {
+                    reviews: entries
+                        .iter()
+                        .map(|&(review, comment_count)| output::ReviewEntry { review, comment_count })
+                        .collect(),
+                })?;
+            } else {
+                print_reviews_text(&entries);
+            }
+        }
+
+        Command::Diff { paths, format, source, comments } => {
+            let (base_branch, merge_base, diff) = load_diff(git, base, source, &paths)?;
+            let with_comments = comments;
+            let comments = if with_comments {
+                state_store.get_comments(&repo_path, &branch)?
+            } else {
+                Vec::new()
+            };
+
+            match format {
+                DiffFormat::Text | DiffFormat::Color => {
+                    print!("{}", export::unified::render(&diff, &comments, format == DiffFormat::Color))
+                }
+                DiffFormat::Json => {
+                    output::print(&output::DiffReport::new(
+                        &branch,
+                        &base_branch,
+                        &merge_base,
+                        diff_source_name(source),
+                        &diff.files,
+                        with_comments.then_some(comments.as_slice()),
+                    ))?;
+                }
+            }
+        }
+
+        Command::Export { format, output } => {
+            let branch_preview = || -> Result<(domain::BranchPreview, domain::Diff)> {
+                let (base_branch, merge_base) = resolve_merge_base(git, base)?;
+                let preview = domain::BranchPreview {
+                    current_branch: branch.clone(),
+                    base_branch,
+                    commits: git.commits_since(&merge_base)?,
+                    merge_base: merge_base.clone(),
+                };
+                Ok((preview, git.diff_to_base(&merge_base)?))
+            };
+            let content = match format {
+                ExportFormat::Json => archive::export(&state_store, &repo_path, &branch)?.to_json()?,
+                ExportFormat::Html => {
+                    let (preview, diff) = branch_preview()?;
+                    let comments = state_store.get_comments(&repo_path, &branch)?;
+                    let reviews = state_store.get_reviews(&repo_path, &branch)?;
+                    export::html::render(&preview, &diff, &comments, &reviews)
+                }
+                ExportFormat::Email => {
+                    // mbox always goes to disk so mail tools can pick it up
+                    let (preview, diff) = branch_preview()?;
+                    let comments = state_store.get_comments(&repo_path, &branch)?;
+                    if comments.is_empty() {
+                        bail!("No comments on {} to export", branch);
                     }
+                    let (mbox, messages) =
+                        export::email::render_mbox(&preview, &diff, &comments, chrono::Utc::now());
+                    let path = output.unwrap_or_else(|| format!("{}.mbox", branch.replace('/', "-")));
+                    std::fs::write(&path, mbox).with_context(|| format!("Failed to write {}", path))?;
+                    eprintln!("Wrote {} message{} to {}", messages, if messages == 1 { "" } else { "s" }, path);
+                    return Ok(());
                 }
-                None => {
-                    eprintln!("Comment #{} not found", id);
-                    std::process::exit(1);
+                ExportFormat::Sarif | ExportFormat::Checkstyle | ExportFormat::Junit => {
+                    let mut comments = state_store.get_comments(&repo_path, &branch)?;
+                    comments.retain(|c| !c.status.is_closed());
+                    match format {
+                        ExportFormat::Sarif => export::sarif::render(&comments),
+                        ExportFormat::Checkstyle => export::checkstyle::render(&comments),
+                        _ => export::junit::render(&branch, &comments),
+                    }
+                }
+            };
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, content).with_context(|| format!("Failed to write {}", path))?;
+                    eprintln!("Wrote {}", path);
                 }
+                None => println!("{}", content.trim_end()),
+            }
+        }
+
+        Command::Import { file, branch: target } => {
+            let json = if file == "-" {
+                io::read_to_string(io::stdin())?
+            } else {
+                std::fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file))?
+            };
+            let archive = archive::ReviewArchive::from_json(&json)?;
+            let target = target.unwrap_or_else(|| archive.branch.clone());
+            let stats = archive::import(&state_store, &repo_path, &target, &archive)?;
+            println!("Imported {} into {}: {}", file, target, stats.summary());
+        }
+
+        Command::ImportDiagnostics { file, format, source, tool } => {
+            let input = if file == "-" {
+                io::read_to_string(io::stdin())?
+            } else {
+                std::fs::read_to_string(&file).with_context(|| format!("Failed to read {}", file))?
+            };
+            let format = match format {
+                DiagnosticsFormat::Auto => None,
+                DiagnosticsFormat::Sarif => Some(diagnostics::Format::Sarif),
+                DiagnosticsFormat::Cargo => Some(diagnostics::Format::Cargo),
+                DiagnosticsFormat::Clippy => Some(diagnostics::Format::Clippy),
+            };
+            let (_, _, diff) = load_diff(git, base, source, &[])?;
+
+            let mut report = diagnostics::parse(&input, format, &repo_path)?;
+            if let Some(tool) = &tool {
+                report = report.with_tool(tool);
             }
+            let total = report.diagnostics.len();
+            let report = report.on_changed_lines(&diff);
+            let imported = diagnostics::import(&state_store, &repo_path, &branch, &report)?;
+
+            for (tool, counts) in &imported {
+                println!(
+                    "{}: {} added, {} moved, {} unchanged, {} reopened, {} resolved",
+                    tool, counts.added, counts.moved, counts.unchanged, counts.reopened, counts.resolved
+                );
+            }
+            let skipped = total - report.diagnostics.len();
+            if skipped > 0 {
+                println!("Skipped {} finding{} outside the changed lines", skipped, if skipped == 1 { "" } else { "s" });
+            }
+        }
+
+        Command::PushNotes { remote } => {
+            let notes = GitNotes::open(&git.workdir()?)?;
+            let stats = sync::push_notes(&state_store, &notes, &repo_path, &branch, &remote)?;
+            println!("Pushed comments for {} to {} (merged first: {})", branch, remote, stats.summary());
+        }
+
+        Command::PullNotes { remote } => {
+            let notes = GitNotes::open(&git.workdir()?)?;
+            let stats = sync::pull_notes(&state_store, &notes, &repo_path, &branch, &remote)?;
+            println!("Pulled comments for {} from {}: {}", branch, remote, stats.summary());
+        }
+    }
+
+    if mutating {
+        if let Some((notes, remote)) = &auto_sync {
+            sync_notes_or_warn(&state_store, notes, git, remote, true);
         }
     }
+    if failed {
+        std::process::exit(output::ErrorCode::Error.exit_code());
+    }
 
     Ok(())
 }
 
+/// Git notes transport and remote, if automatic sharing is enabled.
+fn auto_sync_notes(git: &dyn GitRepo) -> Option<(GitNotes, String)> {
+    let notes = GitNotes::open(&git.workdir().ok()?).ok()?;
+    let remote = notes.auto_sync_remote()?;
+    Some((notes, remote))
+}
+
+/// Coverage report set with `git config panko.coverage <path>`, relative to
+/// the repository root.
+fn configured_coverage_path(git: &dyn GitRepo) -> Option<PathBuf> {
+    let workdir = git.workdir().ok()?;
+    let config = git2::Repository::discover(&workdir).ok()?.config().ok()?;
+    let path = config.get_path("panko.coverage").ok()?;
+    Some(workdir.join(path))
+}
+
+/// Who runs a command: the git user, under the policy set with
+/// `git config panko.agentPolicy` (default: guarded). See `env_actor`.
+fn cli_actor(git: &dyn GitRepo, default: Option<domain::AuthorKind>) -> Result<Actor> {
+    let kind = std::env::var("PANKO_AUTHOR_KIND").ok();
+    env_actor(get_git_user(git), kind.as_deref(), default, io::stdin().is_terminal(), agent_policy(git)?)
+}
+
+/// An actor of `kind` (`PANKO_AUTHOR_KIND`: human, agent or tool), else
+/// `default`. Without either, someone at a terminal is a person; other
+/// callers (agents shelling out, scripts) write as a person but are held to
+/// the agent rules for what the policy restricts.
+fn env_actor(
+    name: String,
+    kind: Option<&str>,
+    default: Option<domain::AuthorKind>,
+    interactive: bool,
+    policy: Policy,
+) -> Result<Actor> {
+    let kind = match kind {
+        Some(kind) => Some(
+            domain::AuthorKind::parse(kind)
+                .ok_or_else(|| output::usage(format!("Unknown PANKO_AUTHOR_KIND '{}' (human, agent or tool)", kind)))?,
+        ),
+        None => default.or(interactive.then_some(domain::AuthorKind::Human)),
+    };
+    Ok(Actor { name, kind: kind.unwrap_or_default(), declared: kind.is_some(), policy })
+}
+
+fn agent_policy(git: &dyn GitRepo) -> Result<Policy> {
+    let configured = git
+        .workdir()
+        .ok()
+        .and_then(|workdir| git2::Repository::discover(workdir).ok()?.config().ok())
+        .and_then(|config| config.get_string("panko.agentPolicy").ok());
+    match configured {
+        Some(value) => Policy::parse(&value).ok_or_else(|| {
+            output::usage(format!("Unknown panko.agentPolicy '{}' (open, guarded or reply-only)", value))
+        }),
+        None => Ok(Policy::default()),
+    }
+}
+
+/// Check the actor's policy for an action on comment `id` of the branch.
+/// Unrestricted actors skip the lookup, leaving missing IDs to the store.
+fn authorize(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    actor: &Actor,
+    id: i64,
+    action: CommentAction,
+) -> Result<()> {
+    if actor.unrestricted() {
+        return Ok(());
+    }
+    let comments = store.get_comments(repo_path, branch)?;
+    let Some(comment) = comments.iter().find(|c| c.id == id) else {
+        return Err(output::not_found(format!("Comment #{} not found", id)));
+    };
+    actor.check(action, comment.author_kind).map_err(output::forbidden)
+}
+
+/// Pull (or push) shared comments for automatic sharing. Failures only warn,
+/// so an unreachable remote never blocks local work.
+fn sync_notes_or_warn(
+    store: &dyn StateStore,
+    notes: &GitNotes,
+    git: &dyn GitRepo,
+    remote: &str,
+    push: bool,
+) {
+    let result = git.repo_path().and_then(|repo_path| {
+        let branch = git.current_branch()?;
+        if push {
+            sync::push_notes(store, notes, &repo_path, &branch, remote)
+        } else {
+            sync::pull_notes(store, notes, &repo_path, &branch, remote)
+        }
+    });
+    if let Err(e) = result {
+        let verb = if push { "push comments to" } else { "pull comments from" };
+        eprintln!("Warning: could not {} {}: {:#}", verb, remote, e);
+    }
+}
+
+fn print_review_status_text(reviews: &[domain::Review], pending: Option<(i64, usize)>) {
+    let status = domain::review::review_status(reviews);
+    println!("Review status: {}", status.label());
+
+    for review in domain::review::latest_by_author(reviews) {
+        let verdict = review.verdict.map(|v| v.label()).unwrap_or("Pending");
+        println!("  {} — {} ({})", review.author, verdict, review.relative_time());
+    }
+
+    if let Some((id, drafts)) = pending {
+        println!("\nPending review #{}: {} draft comment(s)", id, drafts);
+    }
+}
+
+fn print_reviews_text(reviews: &[(&domain::Review, usize)]) {
+    if reviews.is_empty() {
+        println!("No reviews found.");
+        return;
+    }
+
+    for (review, comment_count) in reviews {
+        let verdict = review.verdict.map(|v| v.label()).unwrap_or("Pending");
+        println!("──────────────────────────────────────");
+        println!("#{} [{}]", review.id, verdict);
+        println!("  Author: {} ({})", review.author, review.relative_time());
+        println!("  Comments: {}", comment_count);
+        if !review.body.is_empty() {
+            println!("  ");
+            for line in review.body.lines() {
+                println!("  {}", line);
+            }
+        }
+    }
+    println!("──────────────────────────────────────");
+    println!("\nTotal: {} review(s)", reviews.len());
+}
+
+/// Branch preview, diff summary and review progress, as `panko status` reports them.
+fn load_status(
+    git: &dyn GitRepo,
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    base: Option<&str>,
+) -> Result<(domain::BranchPreview, BranchSummary, ReviewProgress)> {
+    let (base_branch, merge_base) = resolve_merge_base(git, base)?;
+    let preview = domain::BranchPreview {
+        current_branch: branch.to_string(),
+        base_branch,
+        commits: git.commits_since(&merge_base)?,
+        merge_base: merge_base.clone(),
+    };
+    let diff = git.diff_to_base(&merge_base)?;
+    let viewed = store.get_viewed_files(repo_path, branch)?;
+    let viewed: Vec<&str> = viewed.iter().map(|v| v.file_path.as_str()).collect();
+    let comments = store.get_comments(repo_path, branch)?;
+
+    let summary = domain::branch_preview::compute_summary(&preview, &diff);
+    let progress = domain::progress::review_progress(&diff, &viewed, &comments);
+    Ok((preview, summary, progress))
+}
+
+/// One-line summary: `feat: 3/5 viewed, 2 open (1 blocking)`.
+fn status_line(branch: &str, summary: &BranchSummary, progress: &ReviewProgress) -> String {
+    let mut line = format!("{}: {}/{} viewed", branch, progress.viewed_files, summary.file_count);
+    let open = progress.comments.open;
+    if open > 0 {
+        let _ = write!(line, ", {} open", open);
+        let blocking = progress.comments.open_with(Some(domain::Severity::Blocking));
+        if blocking > 0 {
+            let _ = write!(line, " ({} blocking)", blocking);
+        }
+    }
+    line
+}
+
+fn print_status_text(preview: &domain::BranchPreview, summary: &BranchSummary, progress: &ReviewProgress) {
+    println!(
+        "{} → {} (merge-base {})",
+        preview.current_branch,
+        preview.base_branch,
+        &preview.merge_base[..preview.merge_base.len().min(7)]
+    );
+    println!(
+        "{} commit(s), {} file(s) changed, +{} -{}",
+        summary.commit_count, summary.file_count, summary.additions, summary.deletions
+    );
+    println!("Viewed: {}/{} files", progress.viewed_files, summary.file_count);
+
+    let counts = &progress.comments;
+    println!("Comments: {} open, {} resolved", counts.open, counts.resolved);
+    for entry in counts.by_severity.iter().filter(|c| c.open + c.resolved > 0) {
+        println!(
+            "  {:<12} {} open, {} resolved",
+            entry.severity.map_or("(none)", |s| s.as_str()),
+            entry.open,
+            entry.resolved
+        );
+    }
+
+    if !progress.unresolved_files.is_empty() {
+        println!("\nUnresolved threads:");
+        for file in &progress.unresolved_files {
+            println!("  {} ({})", file.path, file.unresolved);
+        }
+    }
+}
+
+/// Whether the command was asked for `--format json` output.
+fn wants_json(command: &Command) -> bool {
+    match command {
+        Command::Comments { format, .. }
+        | Command::Status { id: None, format, .. }
+        | Command::Check { format, .. }
+        | Command::Show { format, .. }
+        | Command::Reviews { format }
+        | Command::Review { action: Some(ReviewAction::Status { format }), .. } => *format == OutputFormat::Json,
+        Command::Diff { format, .. } => *format == DiffFormat::Json,
+        Command::Batch { .. } | Command::Watch { .. } => true,
+        _ => false,
+    }
+}
+
 fn get_git_user(git: &dyn GitRepo) -> String {
     git.user_name().unwrap_or_else(|_| "Agent".to_string())
 }
@@ -320,20 +1474,104 @@ fn open_repo(path: Option<&str>) -> Result<Box<dyn GitRepo>> {
     Ok(Box::new(jj))
 }
 
-fn print_comments_text(comments: &[&domain::Comment]) {
+/// Base branch (given or detected) and its merge-base with HEAD.
+fn resolve_merge_base(git: &dyn GitRepo, base: Option<&str>) -> Result<(String, String)> {
+    let base_branch = base
+        .map(String::from)
+        .unwrap_or_else(|| git.detect_base_branch().unwrap_or_else(|_| "main".to_string()));
+    let merge_base = git.merge_base(&base_branch)?;
+    Ok((base_branch, merge_base))
+}
+
+/// Base branch, merge-base and the diff from `source` (committed, uncommitted
+/// or all), limited to files under `paths` when any are given.
+fn load_diff(
+    git: &dyn GitRepo,
+    base: Option<&str>,
+    source: app::DiffSource,
+    paths: &[String],
+) -> Result<(String, String, domain::Diff)> {
+    let (base_branch, merge_base) = resolve_merge_base(git, base)?;
+    let mut diff = match source {
+        app::DiffSource::Committed => git.diff_to_base(&merge_base)?,
+        app::DiffSource::Uncommitted => git.uncommitted_diff()?,
+        app::DiffSource::All => git.diff_to_workdir(&merge_base)?,
+    };
+    diff.files.retain(|file| {
+        paths.is_empty()
+            || paths.iter().any(|p| {
+                path_matches(&file.path, p)
+                    || file.old_path.as_deref().is_some_and(|old| path_matches(old, p))
+            })
+    });
+    Ok((base_branch, merge_base, diff))
+}
+
+/// Whether `path` is `filter` itself or inside the `filter` directory.
+fn path_matches(path: &str, filter: &str) -> bool {
+    let filter = filter.trim_start_matches("./").trim_end_matches('/');
+    filter.is_empty()
+        || path == filter
+        || path.strip_prefix(filter).is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Current code around each line comment, keyed by comment ID.
+type Contexts = HashMap<i64, CodeContext>;
+
+/// Read the working-tree source for each line comment (each file once).
+fn load_contexts(repo_path: &str, comments: &[&domain::Comment], radius: usize) -> Contexts {
+    let mut sources: HashMap<&str, String> = HashMap::new();
+    let mut contexts = HashMap::new();
+    for comment in comments {
+        let Some(path) = comment.file_path.as_deref() else {
+            continue;
+        };
+        // A deleted file reads as empty, so its anchors show up as changed
+        let source = sources.entry(path).or_insert_with(|| {
+            std::fs::read_to_string(Path::new(repo_path).join(path)).unwrap_or_default()
+        });
+        if let Some(context) = domain::context::code_context(comment, source, radius) {
+            contexts.insert(comment.id, context);
+        }
+    }
+    contexts
+}
+
+fn print_code_context(context: &CodeContext) {
+    let width = context.lines.last().map_or(1, |l| l.line.to_string().len());
+    println!("  ");
+    for line in &context.lines {
+        let marker = if line.anchored { '>' } else { ' ' };
+        println!("  {} {:>width$} │ {}", marker, line.line, line.text);
+    }
+    if context.changed == Some(true) {
+        println!("  ⚠ These lines changed since the comment was written");
+    }
+}
+
+/// An author's name, marked when an agent or tool wrote the message.
+fn author_label(name: &str, kind: domain::AuthorKind) -> String {
+    if kind.is_human() {
+        name.to_string()
+    } else {
+        format!("{} [{}]", name, kind.as_str())
+    }
+}
+
+fn print_comments_text(comments: &[&domain::Comment], contexts: Option<&Contexts>) {
     if comments.is_empty() {
         println!("No comments found.");
         return;
     }
 
     for comment in comments {
-        let status = if comment.resolved { "RESOLVED" } else { "OPEN" };
-        let status_icon = if comment.resolved { "✓" } else { "○" };
-
         println!("──────────────────────────────────────");
-        println!("{} #{} [{}]", status_icon, comment.id, status);
-        println!("  File: {} {}", comment.file_path, comment.line_range_display());
-        println!("  Author: {} ({})", comment.author, comment.relative_time());
+        print_comment_status(comment);
+        print_comment_location(comment);
+        println!("  Author: {} ({})", author_label(&comment.author, comment.author_kind), comment.relative_time());
+        if let Some(context) = contexts.and_then(|c| c.get(&comment.id)) {
+            print_code_context(context);
+        }
         println!("  ");
         for line in comment.body.lines() {
             println!("  {}", line);
@@ -342,7 +1580,7 @@ fn print_comments_text(comments: &[&domain::Comment]) {
         // Print replies
         for reply in &comment.replies {
             println!("  ");
-            println!("    ↳ {} ({})", reply.author, reply.relative_time());
+            println!("    ↳ {} ({})", author_label(&reply.author, reply.author_kind), reply.relative_time());
             for line in reply.body.lines() {
                 println!("      {}", line);
             }
@@ -352,69 +1590,37 @@ fn print_comments_text(comments: &[&domain::Comment]) {
     println!("\nTotal: {} comment(s)", comments.len());
 }
 
-fn print_comments_json(comments: &[&domain::Comment]) {
-    // Simple JSON output without serde
-    println!("[");
-    for (i, comment) in comments.iter().enumerate() {
-        let replies_json: Vec<String> = comment
-            .replies
-            .iter()
-            .map(|r| {
-                format!(
-                    r#"    {{"id": {}, "author": "{}", "body": "{}", "created_at": {}}}"#,
-                    r.id,
-                    escape_json(&r.author),
-                    escape_json(&r.body),
-                    r.created_at
-                )
-            })
-            .collect();
-
-        println!(
-            r#"  {{
-    "id": {},
-    "file_path": "{}",
-    "start_line": {},
-    "end_line": {},
-    "body": "{}",
-    "author": "{}",
-    "created_at": {},
-    "resolved": {},
-    "replies": [
-{}
-    ]
-  }}{}"#,
-            comment.id,
-            escape_json(&comment.file_path),
-            comment.start_line,
-            comment.end_line,
-            escape_json(&comment.body),
-            escape_json(&comment.author),
-            comment.created_at,
-            comment.resolved,
-            replies_json.join(",\n"),
-            if i < comments.len() - 1 { "," } else { "" }
-        );
+fn print_comment_status(comment: &domain::Comment) {
+    let status = comment.status.as_str().to_uppercase();
+    match &comment.assignee {
+        Some(assignee) => {
+            println!("{} #{} [{}] → {}", comment.status.icon(), comment.id, status, assignee)
+        }
+        None => println!("{} #{} [{}]", comment.status.icon(), comment.id, status),
     }
-    println!("]");
 }
 
-fn escape_json(s: &str) -> String {
-    s.replace('\\', "\\\\")
-        .replace('"', "\\\"")
-        .replace('\n', "\\n")
-        .replace('\r', "\\r")
-        .replace('\t', "\\t")
+fn print_comment_location(comment: &domain::Comment) {
+    match comment.scope() {
+        domain::CommentScope::Branch => println!("  On: branch (general comment)"),
+        _ => println!("  File: {}", comment.location_display()),
+    }
+    if let Some(severity) = comment.severity {
+        println!("  Severity: {}", severity.as_str());
+    }
+    if !comment.labels.is_empty() {
+        println!("  Labels: {}", comment.labels.join(", "));
+    }
 }
 
-fn print_comment_text(comment: &domain::Comment) {
-    let status = if comment.resolved { "RESOLVED" } else { "OPEN" };
-    let status_icon = if comment.resolved { "✓" } else { "○" };
-
+fn print_comment_text(comment: &domain::Comment, contexts: Option<&Contexts>) {
     println!("──────────────────────────────────────");
-    println!("{} #{} [{}]", status_icon, comment.id, status);
-    println!("  File: {} {}", comment.file_path, comment.line_range_display());
-    println!("  Author: {} ({})", comment.author, comment.relative_time());
+    print_comment_status(comment);
+    print_comment_location(comment);
+    println!("  Author: {} ({})", author_label(&comment.author, comment.author_kind), comment.relative_time());
+    if let Some(context) = contexts.and_then(|c| c.get(&comment.id)) {
+        print_code_context(context);
+    }
     println!();
     for line in comment.body.lines() {
         println!("  {}", line);
@@ -426,7 +1632,7 @@ fn print_comment_text(comment: &domain::Comment) {
         println!("  Replies ({}):", comment.replies.len());
         for reply in &comment.replies {
             println!();
-            println!("    ↳ {} ({})", reply.author, reply.relative_time());
+            println!("    ↳ {} ({})", author_label(&reply.author, reply.author_kind), reply.relative_time());
             for line in reply.body.lines() {
                 println!("      {}", line);
             }
@@ -435,272 +1641,145 @@ fn print_comment_text(comment: &domain::Comment) {
     println!("──────────────────────────────────────");
 }
 
-fn print_comment_json(comment: &domain::Comment) {
-    let replies_json: Vec<String> = comment
-        .replies
-        .iter()
-        .map(|r| {
-            format!(
-                r#"    {{"id": {}, "author": "{}", "body": "{}", "created_at": {}}}"#,
-                r.id,
-                escape_json(&r.author),
-                escape_json(&r.body),
-                r.created_at
-            )
-        })
-        .collect();
-
-    println!(
-        r#"{{
-  "id": {},
-  "file_path": "{}",
-  "start_line": {},
-  "end_line": {},
-  "body": "{}",
-  "author": "{}",
-  "created_at": {},
-  "resolved": {},
-  "replies": [
-{}
-  ]
-}}"#,
-        comment.id,
-        escape_json(&comment.file_path),
-        comment.start_line,
-        comment.end_line,
-        escape_json(&comment.body),
-        escape_json(&comment.author),
-        comment.created_at,
-        comment.resolved,
-        replies_json.join(",\n"),
-    );
-}
-
 // ─── Init command ───────────────────────────────────────────────────────────
 
-fn run_init_command(target: InitTarget, workdir: &Path) -> Result<()> {
-    match target {
-        InitTarget::Claude => init_claude(workdir),
-        InitTarget::Codex => init_codex(workdir),
-        InitTarget::Opencode => init_opencode(workdir),
-    }
-}
-
-/// Panko permissions to auto-approve in Claude settings
-const PANKO_PERMISSIONS: &[&str] = &[
-    "Bash(panko comments*)",
-    "Bash(panko show*)",
-    "Bash(panko resolve*)",
-    "Bash(panko unresolve*)",
-    "Bash(panko reply*)",
-    "Bash(panko comment*)",
-    "Bash(panko delete*)",
-];
-
-fn merge_panko_permissions(settings_path: &Path) -> Result<()> {
-    use std::fs;
-
-    let content = fs::read_to_string(settings_path)
-        .context("Failed to read settings file")?;
-    let mut json: serde_json::Value = serde_json::from_str(&content)
-        .context("Failed to parse settings JSON")?;
-
-    // Get or create permissions.allow array
-    let permissions = json
-        .as_object_mut()
-        .context("Settings must be a JSON object")?
-        .entry("permissions")
-        .or_insert_with(|| serde_json::json!({}));
-
-    let allow = permissions
-        .as_object_mut()
-        .context("permissions must be an object")?
-        .entry("allow")
-        .or_insert_with(|| serde_json::json!([]));
-
-    let allow_array = allow
-        .as_array_mut()
-        .context("permissions.allow must be an array")?;
+fn run_init_command(target: Option<&str>, flags: InitFlags, workdir: &Path) -> Result<()> {
+    let templates = init::templates(init::user_dir().as_deref())?;
+    let Some(target) = target else {
+        println!("Targets (panko init <target>):");
+        for template in &templates {
+            let source = template.source.as_ref().map(|p| format!(" ({})", p.display())).unwrap_or_default();
+            println!("  {:<12} {}{}", template.name, template.description, source);
+        }
+        println!("  {:<12} pre-push and pre-merge-commit hooks running `panko check`", "hooks");
+        return Ok(());
+    };
 
-    // Add new permissions if not already present
-    for perm in PANKO_PERMISSIONS {
-        let perm_val = serde_json::Value::String(perm.to_string());
-        if !allow_array.contains(&perm_val) {
-            allow_array.push(perm_val);
+    if flags.mcp && target != "claude" {
+        return Err(output::usage("--mcp only applies to `panko init claude`"));
+    }
+    if (flags.blocking || flags.viewed) && target != "hooks" {
+        return Err(output::usage("--blocking and --viewed only apply to `panko init hooks`"));
+    }
+    if target == "hooks" {
+        if flags.dry_run {
+            return Err(output::usage("--dry-run is not supported for hooks"));
         }
+        return init_hooks(workdir, flags.blocking, flags.viewed);
     }
 
-    fs::write(settings_path, serde_json::to_string_pretty(&json)?)
-        .context("Failed to write settings file")?;
-
-    Ok(())
-}
-
-fn init_claude(workdir: &Path) -> Result<()> {
-    use std::fs;
-
-    let claude_dir = workdir.join(".claude");
-    let skills_dir = claude_dir.join("skills");
-
-    // Create directories
-    fs::create_dir_all(&skills_dir)
-        .context("Failed to create .claude/skills directory")?;
-
-    // Write skill file
-    let skill_path = skills_dir.join("panko.md");
-    fs::write(&skill_path, CLAUDE_SKILL_CONTENT)
-        .context("Failed to write skill file")?;
-    println!("Created {}", skill_path.display());
-
-    // Write or merge settings
-    let settings_path = claude_dir.join("settings.json");
-    let settings_local_path = claude_dir.join("settings.local.json");
-
-    if settings_path.exists() {
-        // Merge into existing settings.json
-        merge_panko_permissions(&settings_path)?;
-        println!("Merged panko permissions into {}", settings_path.display());
-    } else if settings_local_path.exists() {
-        // Merge into existing settings.local.json
-        merge_panko_permissions(&settings_local_path)?;
-        println!("Merged panko permissions into {}", settings_local_path.display());
-    } else {
-        // Create new settings.json
-        fs::write(&settings_path, CLAUDE_SETTINGS_CONTENT)
-            .context("Failed to write settings file")?;
-        println!("Created {}", settings_path.display());
+    let name = if target == "claude" && flags.mcp { "claude-mcp" } else { target };
+    let template = templates
+        .iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| output::usage(format!("Unknown init target '{}' (run `panko init` to list them)", target)))?;
+    let planned = init::plan(template, workdir, flags.update)?;
+    if !flags.dry_run {
+        init::apply(&planned)?;
+    }
+    for file in &planned {
+        let path = file.path.display();
+        match (file.change, flags.dry_run) {
+            (init::Change::UpToDate, _) => println!("{} is up to date", path),
+            (init::Change::Outdated, _) => println!("{} has an outdated panko section", path),
+            (_, true) => print!("{}", init::preview(file, workdir)),
+            (init::Change::Created, false) => println!("Created {}", path),
+            (init::Change::Added, false) => println!("Added panko section to {}", path),
+            (init::Change::Updated, false) => println!("Updated panko section in {}", path),
+            (init::Change::Merged, false) => println!("Merged panko settings into {}", path),
+        }
     }
 
-    println!("\nClaude Code integration ready. Use /panko to address review comments.");
+    if planned.iter().any(|f| f.change == init::Change::Outdated) {
+        println!("\nRun `panko init {} --update` to refresh it.", target);
+    } else if let Some(note) = template.note.as_ref().filter(|_| !flags.dry_run) {
+        println!("\n{}", note);
+    }
     Ok(())
 }
 
-fn init_codex(workdir: &Path) -> Result<()> {
-    init_agents_md(workdir, "Codex")
-}
-
-fn init_opencode(workdir: &Path) -> Result<()> {
-    init_agents_md(workdir, "OpenCode")
-}
-
-fn init_agents_md(workdir: &Path, tool_name: &str) -> Result<()> {
-    use std::fs;
-
-    let agents_path = workdir.join("AGENTS.md");
-
-    if agents_path.exists() {
-        // Check if panko section already exists
-        let content = fs::read_to_string(&agents_path)
-            .context("Failed to read existing AGENTS.md")?;
+fn init_hooks(workdir: &Path, blocking: bool, viewed: bool) -> Result<()> {
+    let mut check_args = Vec::new();
+    if blocking {
+        check_args.push("--blocking");
+    }
+    if viewed {
+        check_args.push("--viewed");
+    }
 
-        if content.contains("## panko") || content.contains("## Panko") {
-            println!("AGENTS.md already contains panko instructions.");
-            return Ok(());
+    let dir = hooks::hooks_dir(workdir)?;
+    for installed in hooks::install(&dir, &check_args)? {
+        match installed {
+            hooks::Installed::Created(path) => println!("Created {}", path.display()),
+            hooks::Installed::Updated(path) => println!("Updated {}", path.display()),
+            hooks::Installed::Chained(path, preserved) => {
+                println!("Created {} (runs the existing hook, moved to {})", path.display(), preserved.display())
+            }
         }
-
-        // Append panko section
-        let new_content = format!("{}\n\n{}", content.trim_end(), AGENTS_MD_SECTION);
-        fs::write(&agents_path, new_content)
-            .context("Failed to update AGENTS.md")?;
-        println!("Added panko section to {}", agents_path.display());
-    } else {
-        // Create new file
-        fs::write(&agents_path, AGENTS_MD_CONTENT)
-            .context("Failed to write AGENTS.md")?;
-        println!("Created {}", agents_path.display());
     }
 
-    println!("\n{} integration ready.", tool_name);
+    println!("\nPushes and merges now wait for the review (bypass once with --no-verify).");
     Ok(())
 }
 
-const CLAUDE_SKILL_CONTENT: &str = r#"# panko - Code Review Comments
-
-Manages code review comments via the panko CLI. Use when the user asks to check, address, resolve, or reply to review comments on the current branch.
-
-## Commands
-
-```bash
-panko comments                      # List all comments
-panko comments --status open        # List unresolved comments
-panko comments --format json        # JSON output for parsing
-
-panko show <id>                     # Show a specific comment thread
-panko resolve <id>                  # Mark comment as resolved
-panko unresolve <id>                # Reopen a resolved comment
-panko reply <id> --message "text"   # Reply to a comment
-panko delete <id>                   # Delete a comment
-
-panko comment <file> <start> <end> --message "text"  # Add new comment
-```
-
-## Workflow
-
-When addressing review comments:
-
-1. List open comments: `panko comments --status open`
-2. Read and understand each comment
-3. Make the code changes
-4. Reply explaining what you did: `panko reply <id> --message "Fixed by..."`
-5. Resolve: `panko resolve <id>`
-
-## Notes
-
-- Comments are scoped to repo + branch
-- Line numbers refer to source file lines (new/right side of diff)
-- The `--author` flag identifies the commenter (defaults to git user)
-"#;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use domain::{AuthorKind, CommentStatus};
+    use ports::NewComment;
+
+    #[test]
+    fn callers_without_a_kind_or_terminal_cannot_close_peoples_comments() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let id = store
+            .add_comment(
+                "/repo",
+                "main",
+                NewComment {
+                    file_path: None,
+                    line_range: None,
+                    anchor: None,
+                    body: "split this up".to_string(),
+                    author: "alice".to_string(),
+                    author_kind: AuthorKind::Human,
+                    severity: None,
+                    labels: vec![],
+                    review_id: None,
+                    uuid: domain::new_uuid(),
+                },
+            )
+            .unwrap();
+        let check = |actor: &Actor, action| authorize(&store, "/repo", "main", actor, id, action);
+        let actor = |kind: Option<&str>, interactive: bool| {
+            env_actor("bob".to_string(), kind, None, interactive, Policy::Guarded).unwrap()
+        };
 
-const CLAUDE_SETTINGS_CONTENT: &str = r#"{
-  "$schema": "https://json.schemastore.org/claude-code-settings.json",
-  "permissions": {
-    "allow": [
-      "Bash(panko comments*)",
-      "Bash(panko show*)",
-      "Bash(panko resolve*)",
-      "Bash(panko unresolve*)",
-      "Bash(panko reply*)",
-      "Bash(panko comment*)",
-      "Bash(panko delete*)"
-    ]
-  }
+        // What an agent shelling out to `panko resolve` looks like
+        let caller = actor(None, false);
+        assert_eq!((caller.kind, caller.declared), (AuthorKind::Human, false));
+        let err = check(&caller, CommentAction::Delete).unwrap_err();
+        assert_eq!(output::error_code(&err), output::ErrorCode::Forbidden);
+        assert_eq!(
+            err.to_string(),
+            "The guarded agent policy does not let agents delete a person's comment; mark it addressed and \
+             leave closing it to a person (set PANKO_AUTHOR_KIND=human if a person is running panko)"
+        );
+        assert!(check(&caller, CommentAction::SetStatus(CommentStatus::Resolved)).is_err());
+        assert!(check(&caller, CommentAction::Reply).is_ok());
+        assert!(check(&caller, CommentAction::SetStatus(CommentStatus::Addressed)).is_ok());
+
+        // Someone at a terminal, or saying they're a person, may close them
+        assert!(check(&actor(None, true), CommentAction::Delete).is_ok());
+        assert!(check(&actor(Some("human"), false), CommentAction::Delete).is_ok());
+        let agent = actor(Some("agent"), true);
+        assert!(check(&agent, CommentAction::Delete).unwrap_err().to_string().ends_with("leave closing it to a person"));
+        let open = env_actor("bob".to_string(), None, None, false, Policy::Open).unwrap();
+        assert!(check(&open, CommentAction::Delete).is_ok());
+
+        // The MCP server's clients are agents even from a terminal
+        let mcp = env_actor("bob".to_string(), None, Some(AuthorKind::Agent), true, Policy::Guarded).unwrap();
+        assert_eq!((mcp.kind, mcp.declared), (AuthorKind::Agent, true));
+        let err = env_actor("bob".to_string(), Some("robot"), None, true, Policy::Guarded).unwrap_err();
+        assert_eq!(output::error_code(&err), output::ErrorCode::Usage);
+    }
 }
-"#;
-
-const AGENTS_MD_SECTION: &str = r#"## panko - Code Review Comments
-
-This project uses `panko` for code review comments. Use these commands to manage review feedback:
-
-```bash
-panko comments                      # List all comments
-panko comments --status open        # List unresolved comments
-panko resolve <id>                  # Mark comment as resolved
-panko reply <id> --message "text"   # Reply to a comment
-```
-
-When addressing review comments:
-1. List open comments: `panko comments --status open`
-2. Make the code changes to address each comment
-3. Reply explaining what you did: `panko reply <id> --message "Fixed by..."`
-4. Resolve: `panko resolve <id>`
-"#;
-
-const AGENTS_MD_CONTENT: &str = r#"# Project Instructions
-
-## panko - Code Review Comments
-
-This project uses `panko` for code review comments. Use these commands to manage review feedback:
-
-```bash
-panko comments                      # List all comments
-panko comments --status open        # List unresolved comments
-panko resolve <id>                  # Mark comment as resolved
-panko reply <id> --message "text"   # Reply to a comment
-```
-
-When addressing review comments:
-1. List open comments: `panko comments --status open`
-2. Make the code changes to address each comment
-3. Reply explaining what you did: `panko reply <id> --message "Fixed by..."`
-4. Resolve: `panko resolve <id>`
-"#;
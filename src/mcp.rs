@@ -0,0 +1,722 @@
+//! Model Context Protocol server for coding agents (`panko mcp`).
+//!
+//! Speaks JSON-RPC 2.0 over stdio, one message per line. Tools cover the
+//! comment workflow (list, show, add, reply, resolve), the branch diff and
+//! file context; resources expose the review summary. Results are the same
+//! versioned documents as `--format json` output. Every call looks up the
+//! current branch, so the server follows checkouts. Changes to people's
+//! comments are checked against the agent policy.
+
+use crate::app::DiffSource;
+use crate::batch::{self, Operation};
+use crate::domain::context::lines_around;
+use crate::domain::policy::{Actor, CommentAction};
+use crate::domain::query::CommentQuery;
+use crate::domain::{Comment, CommentStatus, Severity};
+use crate::output;
+use crate::ports::{GitRepo, StateStore};
+use anyhow::{Context, Result};
+use clap::ValueEnum;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+/// Protocol revisions this server speaks, newest first.
+const PROTOCOL_VERSIONS: [&str; 3] = ["2025-06-18", "2025-03-26", "2024-11-05"];
+
+// JSON-RPC and MCP error codes
+const PARSE_ERROR: i64 = -32700;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const RESOURCE_NOT_FOUND: i64 = -32002;
+
+type RpcResult = std::result::Result<Value, (i64, String)>;
+
+pub struct Server<'a> {
+    git: &'a dyn GitRepo,
+    store: &'a dyn StateStore,
+    repo_path: String,
+    base: Option<String>,
+    /// Author of comments and replies that don't name one, and whose kind
+    /// the agent policy applies to
+    actor: Actor,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ListArgs {
+    status: Option<String>,
+    severity: Option<String>,
+    label: Option<String>,
+    file: Option<String>,
+    #[serde(default)]
+    unreplied: bool,
+    context: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct ShowArgs {
+    id: i64,
+    context: Option<usize>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StatusArgs {
+    id: i64,
+    status: String,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct DiffArgs {
+    #[serde(default)]
+    paths: Vec<String>,
+    source: Option<String>,
+    #[serde(default)]
+    comments: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct FileArgs {
+    path: String,
+    start: usize,
+    end: Option<usize>,
+    radius: Option<usize>,
+}
+
+impl<'a> Server<'a> {
+    pub fn new(git: &'a dyn GitRepo, store: &'a dyn StateStore, base: Option<&str>, actor: Actor) -> Result<Self> {
+        let repo_path = git.workdir()?.to_string_lossy().trim_end_matches('/').to_string();
+        Ok(Self {
+            git,
+            store,
+            repo_path,
+            base: base.map(String::from),
+            actor,
+        })
+    }
+
+    /// Answer messages from `input` until it closes.
+    pub fn serve(&self, input: impl BufRead, mut output: impl Write) -> Result<()> {
+        for line in input.lines() {
+            let line = line.context("Failed to read MCP message")?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let response = match serde_json::from_str::<Value>(&line) {
+                Ok(message) => self.handle(&message),
+                Err(e) => Some(error_response(Value::Null, PARSE_ERROR, &e.to_string())),
+            };
+            if let Some(response) = response {
+                writeln!(output, "{}", response)?;
+                output.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Response to one message. Notifications, and responses from the
+    /// client, get none.
+    pub fn handle(&self, message: &Value) -> Option<Value> {
+        let method = message.get("method")?.as_str()?;
+        let id = message.get("id")?.clone();
+        let params = message.get("params").cloned().unwrap_or_else(|| json!({}));
+
+        let result = match method {
+            "initialize" => Ok(initialize(&params)),
+            "ping" => Ok(json!({})),
+            "tools/list" => Ok(json!({ "tools": tools() })),
+            "tools/call" => self.call_tool(&params),
+            "resources/list" => Ok(json!({ "resources": resources() })),
+            "resources/read" => self.read_resource(&params),
+            _ => Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", method))),
+        };
+        Some(match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err((code, message)) => error_response(id, code, &message),
+        })
+    }
+
+    fn call_tool(&self, params: &Value) -> RpcResult {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "Missing tool name".to_string()))?;
+        let args = params.get("arguments").cloned().unwrap_or_else(|| json!({}));
+
+        let outcome = match name {
+            "list_comments" => parse_args(args).and_then(|a| self.list_comments(a)),
+            "show_comment" => parse_args(args).and_then(|a: ShowArgs| self.show_comment(a.id, a.context)),
+            "add_comment" => self.apply("comment", args).and_then(|id| self.show_comment(id, None)),
+            "reply_to_comment" => {
+                let id = args.get("id").and_then(Value::as_i64);
+                self.apply("reply", args).and_then(|_| self.show_comment(id.unwrap_or_default(), None))
+            }
+            "resolve_comment" => self.apply("resolve", args).and_then(|id| self.show_comment(id, None)),
+            "set_comment_status" => parse_args(args).and_then(|a| self.set_comment_status(a)),
+            "get_diff" => parse_args(args).and_then(|a| self.get_diff(a)),
+            "get_file_context" => parse_args(args).and_then(|a| self.get_file_context(a)),
+            _ => return Err((INVALID_PARAMS, format!("Unknown tool '{}'", name))),
+        };
+
+        // Failed calls are results the model can read and act on
+        Ok(match outcome {
+            Ok(document) => json!({
+                "content": [{ "type": "text", "text": document.to_string() }],
+                "isError": false,
+            }),
+            Err(err) => json!({
+                "content": [{ "type": "text", "text": format!("{:#}", err) }],
+                "isError": true,
+            }),
+        })
+    }
+
+    fn read_resource(&self, params: &Value) -> RpcResult {
+        let uri = params
+            .get("uri")
+            .and_then(Value::as_str)
+            .ok_or((INVALID_PARAMS, "Missing resource uri".to_string()))?;
+        let document = match uri {
+            "panko://status" => self.status(),
+            "panko://comments" => self.list_comments(ListArgs {
+                status: Some("unresolved".to_string()),
+                severity: None,
+                label: None,
+                file: None,
+                unreplied: false,
+                context: None,
+            }),
+            _ => return Err((RESOURCE_NOT_FOUND, format!("Unknown resource '{}'", uri))),
+        }
+        .map_err(|err| (INVALID_PARAMS, format!("{:#}", err)))?;
+
+        Ok(json!({
+            "contents": [{ "uri": uri, "mimeType": "application/json", "text": document.to_string() }]
+        }))
+    }
+
+    fn comments(&self) -> Result<Vec<Comment>> {
+        self.store.get_comments(&self.repo_path, &self.git.current_branch()?)
+    }
+
+    fn list_comments(&self, args: ListArgs) -> Result<Value> {
+        let comments = self.comments()?;
+        let statuses = match args.status.as_deref() {
+            None | Some("all") => None,
+            Some("unresolved") => Some(CommentStatus::ALL.into_iter().filter(|s| !s.is_closed()).collect()),
+            Some(status) => Some(vec![parse_status(status)?]),
+        };
+        let severity = match args.severity.as_deref() {
+            Some(s) => Some(Severity::parse(s).ok_or_else(|| output::usage(format!("Unknown severity '{}'", s)))?),
+            None => None,
+        };
+        let query = CommentQuery {
+            statuses,
+            severity,
+            labels: args.label.into_iter().collect(),
+            file: args.file,
+            unreplied_by: args.unreplied.then(|| self.actor.name.clone()),
+            ..Default::default()
+        };
+
+        let matching: Vec<&Comment> = comments.iter().filter(|c| query.matches(c)).collect();
+        let contexts = args.context.map(|radius| crate::load_contexts(&self.repo_path, &matching, radius));
+        output::document(&output::CommentList {
+            comments: matching
+                .iter()
+                .map(|c| output::CommentEntry {
+                    comment: c,
+                    context: contexts.as_ref().and_then(|ctx| ctx.get(&c.id)),
+                })
+                .collect(),
+        })
+    }
+
+    fn show_comment(&self, id: i64, context: Option<usize>) -> Result<Value> {
+        let comments = self.comments()?;
+        let comment = comments
+            .iter()
+            .find(|c| c.id == id)
+            .ok_or_else(|| output::not_found(format!("Comment #{} not found", id)))?;
+        let contexts = context.map(|radius| crate::load_contexts(&self.repo_path, &[comment], radius));
+        output::document(&output::CommentShow {
+            comment: output::CommentEntry {
+                comment,
+                context: contexts.as_ref().and_then(|ctx| ctx.get(&id)),
+            },
+        })
+    }
+
+    /// Apply a batch operation built from tool arguments, returning its ID.
+    fn apply(&self, op: &str, mut args: Value) -> Result<i64> {
+        if let Some(fields) = args.as_object_mut() {
+            fields.insert("op".to_string(), op.into());
+        }
+        let operation: Operation = serde_json::from_value(args).map_err(|e| output::usage(e.to_string()))?;
+        let branch = self.git.current_branch()?;
+        batch::apply_single(self.store, &self.repo_path, &branch, &self.actor, &operation)
+    }
+
+    fn set_comment_status(&self, args: StatusArgs) -> Result<Value> {
+        let status = parse_status(&args.status)?;
+        let comments = self.comments()?;
+        let Some(comment) = comments.iter().find(|c| c.id == args.id) else {
+            return Err(output::not_found(format!("Comment #{} not found", args.id)));
+        };
+        self.actor
+            .check(CommentAction::SetStatus(status), comment.author_kind)
+            .map_err(output::forbidden)?;
+        self.store.set_comment_status(args.id, status)?;
+        self.show_comment(args.id, None)
+    }
+
+    fn get_diff(&self, args: DiffArgs) -> Result<Value> {
+        let branch = self.git.current_branch()?;
+        let source = args.source.as_deref().map_or(Ok(DiffSource::Committed), parse_source)?;
+        let (base, merge_base, diff) = crate::load_diff(self.git, self.base.as_deref(), source, &args.paths)?;
+        let comments = if args.comments { self.comments()? } else { Vec::new() };
+        output::document(&output::DiffReport::new(
+            &branch,
+            &base,
+            &merge_base,
+            crate::diff_source_name(source),
+            &diff.files,
+            args.comments.then_some(comments.as_slice()),
+        ))
+    }
+
+    fn get_file_context(&self, args: FileArgs) -> Result<Value> {
+        let end = args.end.unwrap_or(args.start);
+        let source = std::fs::read_to_string(Path::new(&self.repo_path).join(&args.path))
+            .with_context(|| format!("Failed to read {}", args.path))?;
+        let lines = lines_around(&source, args.start, end, args.radius.unwrap_or(3));
+
+        let (first, last) = match (lines.first(), lines.last()) {
+            (Some(first), Some(last)) => (first.line, last.line),
+            _ => (0, 0),
+        };
+        let comments = self.comments()?;
+        output::document(&output::FileContext {
+            path: &args.path,
+            lines,
+            comments: comments
+                .iter()
+                .filter(|c| c.file_path.as_deref() == Some(args.path.as_str()))
+                .filter(|c| c.line_range.is_none_or(|(start, end)| start <= last && end >= first))
+                .collect(),
+        })
+    }
+
+    fn status(&self) -> Result<Value> {
+        let branch = self.git.current_branch()?;
+        let (preview, summary, progress) =
+            crate::load_status(self.git, self.store, &self.repo_path, &branch, self.base.as_deref())?;
+        output::document(&output::StatusReport {
+            branch: &branch,
+            base: &preview.base_branch,
+            merge_base: &preview.merge_base,
+            summary: &summary,
+            progress: &progress,
+        })
+    }
+}
+
+fn parse_args<T: DeserializeOwned>(args: Value) -> Result<T> {
+    serde_json::from_value(args).map_err(|e| output::usage(format!("Invalid arguments: {}", e)))
+}
+
+fn parse_status(s: &str) -> Result<CommentStatus> {
+    CommentStatus::parse(s).ok_or_else(|| output::usage(format!("Unknown status '{}'", s)))
+}
+
+fn parse_source(s: &str) -> Result<DiffSource> {
+    DiffSource::from_str(s, false).map_err(|_| output::usage(format!("Unknown diff source '{}'", s)))
+}
+
+fn error_response(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+fn initialize(params: &Value) -> Value {
+    let requested = params.get("protocolVersion").and_then(Value::as_str);
+    let version = requested
+        .filter(|v| PROTOCOL_VERSIONS.contains(v))
+        .unwrap_or(PROTOCOL_VERSIONS[0]);
+    json!({
+        "protocolVersion": version,
+        "capabilities": { "tools": {}, "resources": {} },
+        "serverInfo": { "name": "panko", "version": env!("CARGO_PKG_VERSION") },
+        "instructions": "Code review comments on the current git branch. List unresolved comments, \
+            fix the code, reply explaining the change, then resolve. Line numbers are 1-based in the \
+            new version of the file; address blocking comments first.",
+    })
+}
+
+fn tools() -> Value {
+    let id = json!({ "type": "integer", "description": "Comment ID" });
+    let context = json!({
+        "type": "integer",
+        "minimum": 0,
+        "description": "Include the commented source lines plus this many lines around them",
+    });
+    let severity = json!({ "enum": ["blocking", "suggestion", "nit", "question", "praise"] });
+    let status = json!({ "enum": ["open", "in-progress", "addressed", "wont-fix", "resolved"] });
+    json!([
+        {
+            "name": "list_comments",
+            "description": "List review comments on the current branch, by file and line.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "status": {
+                        "enum": ["all", "unresolved", "open", "in-progress", "addressed", "wont-fix", "resolved"],
+                        "description": "Default: all. unresolved = open, in progress or addressed",
+                    },
+                    "severity": severity,
+                    "label": { "type": "string" },
+                    "file": {
+                        "type": "string",
+                        "description": "Glob over file paths; * stays within a directory, ** spans them",
+                    },
+                    "unreplied": { "type": "boolean", "description": "Only threads where someone else spoke last" },
+                    "context": context,
+                },
+            },
+        },
+        {
+            "name": "show_comment",
+            "description": "Show one comment thread with its replies.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": id, "context": context },
+                "required": ["id"],
+            },
+        },
+        {
+            "name": "add_comment",
+            "description": "Comment on lines of a file (file and start), a whole file (file only) \
+                or the branch (neither). Lines are 1-based in the new version of the file.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "file": { "type": "string", "description": "Path relative to the repository root" },
+                    "start": { "type": "integer", "minimum": 1 },
+                    "end": { "type": "integer", "minimum": 1, "description": "Default: start" },
+                    "message": { "type": "string" },
+                    "severity": severity,
+                    "labels": { "type": "array", "items": { "type": "string" } },
+                },
+                "required": ["message"],
+            },
+        },
+        {
+            "name": "reply_to_comment",
+            "description": "Reply to a comment thread, e.g. to explain how it was addressed.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": id, "message": { "type": "string" } },
+                "required": ["id", "message"],
+            },
+        },
+        {
+            "name": "resolve_comment",
+            "description": "Mark a comment as resolved. People's comments may only be closed \
+                by people under the default agent policy; mark them addressed instead.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": id },
+                "required": ["id"],
+            },
+        },
+        {
+            "name": "set_comment_status",
+            "description": "Move a comment to a workflow status, e.g. in-progress while working on it, \
+                addressed once the change is made, or wont-fix after replying with why not.",
+            "inputSchema": {
+                "type": "object",
+                "properties": { "id": id, "status": status },
+                "required": ["id", "status"],
+            },
+        },
+        {
+            "name": "get_diff",
+            "description": "The branch diff against its base (merge-base to HEAD by default).",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "paths": {
+                        "type": "array",
+                        "items": { "type": "string" },
+                        "description": "Only these files or directories",
+                    },
+                    "source": { "enum": ["committed", "uncommitted", "all"] },
+                    "comments": { "type": "boolean", "description": "Include comment threads" },
+                },
+            },
+        },
+        {
+            "name": "get_file_context",
+            "description": "Current lines of a file in the working tree, with the comments on them.",
+            "inputSchema": {
+                "type": "object",
+                "properties": {
+                    "path": { "type": "string" },
+                    "start": { "type": "integer", "minimum": 1 },
+                    "end": { "type": "integer", "minimum": 1, "description": "Default: start" },
+                    "radius": { "type": "integer", "minimum": 0, "description": "Extra lines on each side (default 3)" },
+                },
+                "required": ["path", "start"],
+            },
+        },
+    ])
+}
+
+fn resources() -> Value {
+    json!([
+        {
+            "uri": "panko://status",
+            "name": "Review status",
+            "description": "Commits, changed and viewed files, and comment counts by severity",
+            "mimeType": "application/json",
+        },
+        {
+            "uri": "panko://comments",
+            "name": "Unresolved comments",
+            "description": "Unresolved comment threads on the current branch",
+            "mimeType": "application/json",
+        },
+    ])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SqliteStateStore;
+    use crate::domain::policy::Policy;
+    use crate::domain::{AuthorKind, Commit, Diff};
+    use std::path::PathBuf;
+
+    struct FakeRepo;
+
+    impl GitRepo for FakeRepo {
+        fn repo_path(&self) -> Result<String> {
+            Ok("/repo".to_string())
+        }
+        fn current_branch(&self) -> Result<String> {
+            Ok("feat".to_string())
+        }
+        fn detect_base_branch(&self) -> Result<String> {
+            Ok("main".to_string())
+        }
+        fn merge_base(&self, _base: &str) -> Result<String> {
+            Ok("abc123".to_string())
+        }
+        fn commits_since(&self, _merge_base: &str) -> Result<Vec<Commit>> {
+            Ok(vec![])
+        }
+        fn diff_to_base(&self, _merge_base: &str) -> Result<Diff> {
+            Ok(Diff::default())
+        }
+        fn commit_diff(&self, _hash: &str) -> Result<Diff> {
+            Ok(Diff::default())
+        }
+        fn workdir(&self) -> Result<PathBuf> {
+            Ok(PathBuf::from("/repo"))
+        }
+        fn uncommitted_diff(&self) -> Result<Diff> {
+            Ok(Diff::default())
+        }
+        fn diff_to_workdir(&self, _merge_base: &str) -> Result<Diff> {
+            Ok(Diff::default())
+        }
+        fn user_name(&self) -> Result<String> {
+            Ok("alice".to_string())
+        }
+    }
+
+    fn agent() -> Actor {
+        Actor { name: "agent".to_string(), kind: AuthorKind::Agent, declared: true, policy: Policy::Guarded }
+    }
+
+    /// Run a session and return the responses.
+    fn session(messages: &[Value]) -> Vec<Value> {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let server = Server::new(&FakeRepo, &store, None, agent()).unwrap();
+        let input: String = messages.iter().map(|m| format!("{}\n", m)).collect();
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+        String::from_utf8(output).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect()
+    }
+
+    fn call(id: i64, tool: &str, arguments: Value) -> Value {
+        json!({ "jsonrpc": "2.0", "id": id, "method": "tools/call", "params": { "name": tool, "arguments": arguments } })
+    }
+
+    /// The JSON document a tool call returned.
+    fn document(response: &Value) -> Value {
+        serde_json::from_str(response["result"]["content"][0]["text"].as_str().unwrap()).unwrap()
+    }
+
+    #[test]
+    fn agents_can_comment_reply_and_resolve() {
+        let responses = session(&[
+            json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize", "params": { "protocolVersion": "2024-11-05" } }),
+            json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }),
+            json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" }),
+            call(3, "add_comment", json!({ "file": "src/db.rs", "start": 4, "message": "use a pool", "severity": "blocking" })),
+            call(4, "reply_to_comment", json!({ "id": 1, "message": "done" })),
+            call(5, "resolve_comment", json!({ "id": 1 })),
+            call(6, "list_comments", json!({ "status": "unresolved" })),
+            call(7, "show_comment", json!({ "id": 42 })),
+            json!({ "jsonrpc": "2.0", "id": 8, "method": "resources/read", "params": { "uri": "panko://status" } }),
+            json!({ "jsonrpc": "2.0", "id": 9, "method": "bogus" }),
+        ]);
+
+        // The notification gets no response
+        assert_eq!(responses.len(), 9);
+        assert_eq!(responses[0]["result"]["protocolVersion"], "2024-11-05");
+        assert_eq!(responses[1]["result"]["tools"].as_array().unwrap().len(), 8);
+
+        let added = document(&responses[2]);
+        assert_eq!(added["schema_version"], 1);
+        assert_eq!(added["comment"]["author"], "agent");
+        assert_eq!(added["comment"]["severity"], "blocking");
+        assert_eq!(document(&responses[3])["comment"]["replies"][0]["body"], "done");
+        assert_eq!(document(&responses[4])["comment"]["status"], "resolved");
+        assert_eq!(document(&responses[5])["comments"], json!([]));
+
+        assert_eq!(responses[6]["result"]["isError"], true);
+        assert_eq!(responses[6]["result"]["content"][0]["text"], "Comment #42 not found");
+
+        let status: Value = serde_json::from_str(responses[7]["result"]["contents"][0]["text"].as_str().unwrap()).unwrap();
+        assert_eq!(status["comments"]["resolved"], 1);
+        assert_eq!(responses[8]["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[test]
+    fn agents_mark_human_comments_addressed() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let human = Actor { name: "alice".to_string(), kind: AuthorKind::Human, declared: true, policy: Policy::Guarded };
+        let operation = Operation::Comment {
+            file: None,
+            start: None,
+            end: None,
+            message: "add tests".to_string(),
+            severity: None,
+            labels: vec![],
+            author: None,
+        };
+        let id = batch::apply_single(&store, "/repo", "feat", &human, &operation).unwrap();
+        let server = Server::new(&FakeRepo, &store, None, agent()).unwrap();
+
+        let resolved = server.handle(&call(1, "resolve_comment", json!({ "id": id }))).unwrap();
+        assert_eq!(resolved["result"]["isError"], true);
+        let addressed = server.handle(&call(2, "set_comment_status", json!({ "id": id, "status": "addressed" })));
+        assert_eq!(document(&addressed.unwrap())["comment"]["status"], "addressed");
+    }
+
+    #[test]
+    fn policy_refusals_are_tool_errors_and_change_nothing() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let human = Actor { name: "alice".to_string(), kind: AuthorKind::Human, ..agent() };
+        let operation = Operation::Comment {
+            file: Some("src/db.rs".to_string()),
+            start: Some(2),
+            end: None,
+            message: "use a pool".to_string(),
+            severity: None,
+            labels: vec![],
+            author: None,
+        };
+        let id = batch::apply_single(&store, "/repo", "feat", &human, &operation).unwrap();
+        let server = Server::new(&FakeRepo, &store, None, agent()).unwrap();
+        let respond = |message: Value| server.handle(&message).unwrap();
+
+        for refused in [
+            call(1, "resolve_comment", json!({ "id": id })),
+            call(2, "set_comment_status", json!({ "id": id, "status": "wont-fix" })),
+            call(3, "set_comment_status", json!({ "id": id, "status": "resolved" })),
+        ] {
+            let response = respond(refused);
+            assert_eq!(response["result"]["isError"], true);
+            let text = response["result"]["content"][0]["text"].as_str().unwrap();
+            assert!(text.starts_with("The guarded agent policy does not let agents"), "{}", text);
+        }
+        assert_eq!(store.get_comments("/repo", "feat").unwrap()[0].status, CommentStatus::Open);
+
+        // Replies are allowed, and the agent keeps control of its own comments
+        let replied = respond(call(4, "reply_to_comment", json!({ "id": id, "message": "pooled" })));
+        assert_eq!(document(&replied)["comment"]["replies"][0]["author_kind"], "agent");
+        let own = respond(call(5, "add_comment", json!({ "message": "follow-up" })));
+        let own_id = document(&own)["comment"]["id"].as_i64().unwrap();
+        let resolved = respond(call(6, "resolve_comment", json!({ "id": own_id })));
+        assert_eq!(document(&resolved)["comment"]["status"], "resolved");
+
+        // Bad arguments and missing comments are tool errors too
+        for (message, expected) in [
+            (call(7, "reply_to_comment", json!({ "id": 99, "message": "?" })), "Comment #99 not found"),
+            (call(8, "set_comment_status", json!({ "id": id, "status": "done" })), "Unknown status 'done'"),
+            (call(9, "add_comment", json!({ "file": "a.rs", "start": 5, "end": 2, "message": "x" })), "Invalid line range"),
+            (call(10, "show_comment", json!({ "id": id, "verbose": true })), "Invalid arguments"),
+        ] {
+            let response = respond(message);
+            assert_eq!(response["result"]["isError"], true);
+            let text = response["result"]["content"][0]["text"].as_str().unwrap();
+            assert!(text.contains(expected), "{} does not mention {}", text, expected);
+        }
+    }
+
+    #[test]
+    fn protocol_errors_and_resources() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let server = Server::new(&FakeRepo, &store, None, agent()).unwrap();
+        server.handle(&call(1, "add_comment", json!({ "message": "open" }))).unwrap();
+        server.handle(&call(2, "add_comment", json!({ "message": "closed" }))).unwrap();
+        server.handle(&call(3, "resolve_comment", json!({ "id": 2 }))).unwrap();
+
+        let input = [
+            "{not json",
+            "",
+            r#"{"jsonrpc": "2.0", "id": 4, "result": {}}"#,
+            r#"{"jsonrpc": "2.0", "id": 5, "method": "initialize", "params": {"protocolVersion": "1999-01-01"}}"#,
+            r#"{"jsonrpc": "2.0", "id": 6, "method": "tools/call", "params": {"arguments": {}}}"#,
+            r#"{"jsonrpc": "2.0", "id": 7, "method": "tools/call", "params": {"name": "delete_everything"}}"#,
+            r#"{"jsonrpc": "2.0", "id": 8, "method": "resources/list"}"#,
+            r#"{"jsonrpc": "2.0", "id": 9, "method": "resources/read", "params": {"uri": "panko://comments"}}"#,
+            r#"{"jsonrpc": "2.0", "id": 10, "method": "resources/read", "params": {"uri": "panko://secrets"}}"#,
+            r#"{"jsonrpc": "2.0", "id": 11, "method": "resources/read"}"#,
+            r#"{"jsonrpc": "2.0", "id": "twelve", "method": "ping"}"#,
+        ]
+        .join("\n");
+        let mut output = Vec::new();
+        server.serve(input.as_bytes(), &mut output).unwrap();
+        let responses: Vec<Value> =
+            String::from_utf8(output).unwrap().lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+
+        // Blank lines and the client's own responses get no answer
+        assert_eq!(responses.len(), 9);
+        assert_eq!(responses[0]["error"]["code"], PARSE_ERROR);
+        assert_eq!(responses[0]["id"], Value::Null);
+        assert_eq!(responses[1]["result"]["protocolVersion"], PROTOCOL_VERSIONS[0]);
+        assert_eq!(responses[2]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[3]["error"]["code"], INVALID_PARAMS);
+        assert_eq!(responses[3]["error"]["message"], "Unknown tool 'delete_everything'");
+        assert_eq!(responses[4]["result"]["resources"].as_array().unwrap().len(), 2);
+
+        let contents = &responses[5]["result"]["contents"][0];
+        assert_eq!(contents["uri"], "panko://comments");
+        let unresolved: Value = serde_json::from_str(contents["text"].as_str().unwrap()).unwrap();
+        let bodies: Vec<&Value> = unresolved["comments"].as_array().unwrap().iter().map(|c| &c["body"]).collect();
+        assert_eq!(bodies, vec!["open"]);
+
+        assert_eq!(responses[6]["error"]["code"], RESOURCE_NOT_FOUND);
+        assert_eq!(responses[7]["error"]["code"], INVALID_PARAMS);
+        assert_eq!((&responses[8]["id"], &responses[8]["result"]), (&json!("twelve"), &json!({})));
+    }
+}
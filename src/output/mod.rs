@@ -0,0 +1,392 @@
+//! Versioned JSON output contract for the CLI.
+//!
+//! Every `--format json` document is an object with a top-level `schema_version`.
+//! Failures become error documents whose `exit_code` is also the process exit
+//! status. `schema.json` describes all of them and is printed by `panko schema`.
+
+use crate::domain::branch_preview::BranchSummary;
+use crate::domain::context::{CodeContext, ContextLine};
+use crate::domain::progress::{CheckFailure, ReviewProgress};
+use crate::domain::review::ReviewStatus;
+use crate::domain::{Comment, CommentEvent, FileDiff, Review, ReviewVerdict};
+use anyhow::Result;
+use serde::Serialize;
+use std::fmt;
+
+/// Bumped only on incompatible changes to the documents below.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// JSON Schema (draft 2020-12) for every document.
+pub const SCHEMA: &str = include_str!("schema.json");
+
+#[derive(Serialize)]
+struct Versioned<'a, T> {
+    schema_version: u32,
+    #[serde(flatten)]
+    body: &'a T,
+}
+
+/// Print a document with its `schema_version`.
+pub fn print<T: Serialize>(body: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(&document(body)?)?);
+    Ok(())
+}
+
+/// A document with its `schema_version`, as a JSON value.
+pub fn document<T: Serialize>(body: &T) -> Result<serde_json::Value> {
+    let document = Versioned {
+        schema_version: SCHEMA_VERSION,
+        body,
+    };
+    Ok(serde_json::to_value(document)?)
+}
+
+/// A comment, with code context when `--context` was given.
+#[derive(Serialize)]
+pub struct CommentEntry<'a> {
+    #[serde(flatten)]
+    pub comment: &'a Comment,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<&'a CodeContext>,
+}
+
+/// `panko comments`
+#[derive(Serialize)]
+pub struct CommentList<'a> {
+    pub comments: Vec<CommentEntry<'a>>,
+}
+
+/// `panko show`
+#[derive(Serialize)]
+pub struct CommentShow<'a> {
+    pub comment: CommentEntry<'a>,
+}
+
+#[derive(Serialize)]
+pub struct ReviewEntry<'a> {
+    #[serde(flatten)]
+    pub review: &'a Review,
+    pub comment_count: usize,
+}
+
+/// `panko reviews`
+#[derive(Serialize)]
+pub struct ReviewList<'a> {
+    pub reviews: Vec<ReviewEntry<'a>>,
+}
+
+/// `panko review status`
+#[derive(Serialize)]
+pub struct ReviewStatusReport<'a> {
+    pub status: ReviewStatus,
+    pub reviewers: Vec<Reviewer<'a>>,
+    pub pending_review: Option<PendingReview>,
+}
+
+/// A reviewer's deciding review.
+#[derive(Serialize)]
+pub struct Reviewer<'a> {
+    pub author: &'a str,
+    pub verdict: Option<ReviewVerdict>,
+    pub review_id: i64,
+    pub submitted_at: Option<i64>,
+}
+
+#[derive(Serialize)]
+pub struct PendingReview {
+    pub id: i64,
+    pub draft_count: usize,
+}
+
+/// `panko diff`; comments are only included with `--comments`.
+#[derive(Serialize)]
+pub struct DiffReport<'a> {
+    pub branch: &'a str,
+    pub base: &'a str,
+    pub merge_base: &'a str,
+    pub source: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<Vec<&'a Comment>>,
+    pub files: Vec<DiffFile<'a>>,
+}
+
+#[derive(Serialize)]
+pub struct DiffFile<'a> {
+    #[serde(flatten)]
+    pub file: &'a FileDiff,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comments: Option<Vec<&'a Comment>>,
+}
+
+impl<'a> DiffReport<'a> {
+    /// With `comments`, branch-level ones go at the top and the rest under
+    /// their files.
+    pub fn new(
+        branch: &'a str,
+        base: &'a str,
+        merge_base: &'a str,
+        source: &'a str,
+        files: &'a [FileDiff],
+        comments: Option<&'a [Comment]>,
+    ) -> Self {
+        let on_file = |path: Option<&str>| -> Option<Vec<&'a Comment>> {
+            comments.map(|all| all.iter().filter(|c| c.file_path.as_deref() == path).collect())
+        };
+        DiffReport {
+            branch,
+            base,
+            merge_base,
+            source,
+            comments: on_file(None),
+            files: files
+                .iter()
+                .map(|file| DiffFile {
+                    file,
+                    comments: on_file(Some(&file.path)),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Current lines of a file with the comments on them (MCP `get_file_context`).
+#[derive(Serialize)]
+pub struct FileContext<'a> {
+    pub path: &'a str,
+    pub lines: Vec<ContextLine>,
+    pub comments: Vec<&'a Comment>,
+}
+
+/// `panko status`
+#[derive(Serialize)]
+pub struct StatusReport<'a> {
+    pub branch: &'a str,
+    pub base: &'a str,
+    pub merge_base: &'a str,
+    #[serde(flatten)]
+    pub summary: &'a BranchSummary,
+    #[serde(flatten)]
+    pub progress: &'a ReviewProgress,
+}
+
+/// `panko check`; exits 1 unless `passed`.
+#[derive(Serialize)]
+pub struct CheckReport<'a> {
+    pub branch: &'a str,
+    pub passed: bool,
+    pub failures: &'a [CheckFailure],
+}
+
+/// One line of `panko watch`, with the comment as it is now (None once deleted).
+#[derive(Serialize)]
+pub struct WatchEvent<'a> {
+    #[serde(flatten)]
+    pub event: &'a CommentEvent,
+    pub comment: Option<&'a Comment>,
+}
+
+/// Error classes, each with its own exit code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    /// Anything else (I/O, git, database)
+    Error,
+    /// Invalid argument values
+    Usage,
+    /// The requested comment or review does not exist
+    NotFound,
+    /// The agent policy does not allow the change
+    Forbidden,
+}
+
+impl ErrorCode {
+    pub fn exit_code(self) -> i32 {
+        match self {
+            ErrorCode::Error => 1,
+            ErrorCode::Usage => 2,
+            ErrorCode::NotFound => 3,
+            ErrorCode::Forbidden => 4,
+        }
+    }
+}
+
+/// An error with a known class; anything else counts as `ErrorCode::Error`.
+#[derive(Debug)]
+pub struct CliError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl std::error::Error for CliError {}
+
+pub fn usage(message: impl Into<String>) -> anyhow::Error {
+    CliError { code: ErrorCode::Usage, message: message.into() }.into()
+}
+
+pub fn not_found(message: impl Into<String>) -> anyhow::Error {
+    CliError { code: ErrorCode::NotFound, message: message.into() }.into()
+}
+
+pub fn forbidden(message: impl Into<String>) -> anyhow::Error {
+    CliError { code: ErrorCode::Forbidden, message: message.into() }.into()
+}
+
+/// Class of an error, from the first `CliError` in its chain.
+pub fn error_code(err: &anyhow::Error) -> ErrorCode {
+    err.chain()
+        .find_map(|e| e.downcast_ref::<CliError>())
+        .map_or(ErrorCode::Error, |e| e.code)
+}
+
+#[derive(Serialize)]
+struct ErrorReport {
+    error: ErrorBody,
+}
+
+#[derive(Serialize)]
+struct ErrorBody {
+    code: ErrorCode,
+    exit_code: i32,
+    message: String,
+}
+
+/// Print an error document and return the exit code to use.
+pub fn print_error(err: &anyhow::Error) -> i32 {
+    let code = error_code(err);
+    let report = ErrorReport {
+        error: ErrorBody {
+            code,
+            exit_code: code.exit_code(),
+            message: format!("{:#}", err),
+        },
+    };
+    if print(&report).is_err() {
+        eprintln!("Error: {:#}", err);
+    }
+    code.exit_code()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::context::code_context;
+    use crate::domain::{AuthorKind, CommentStatus, Reply, Severity};
+    use serde_json::Value;
+
+    fn schema_def(name: &str) -> Value {
+        let schema: Value = serde_json::from_str(SCHEMA).unwrap();
+        schema["$defs"][name].clone()
+    }
+
+    /// Keys of a serialized value, sorted.
+    fn keys(value: &Value) -> Vec<String> {
+        let mut keys: Vec<String> = value.as_object().unwrap().keys().cloned().collect();
+        keys.sort();
+        keys
+    }
+
+    /// Sorted property names declared for a schema definition.
+    fn schema_keys(name: &str) -> Vec<String> {
+        keys(&schema_def(name)["properties"])
+    }
+
+    #[test]
+    fn documents_match_the_schema() {
+        let comment = Comment {
+            id: 3,
+            uuid: "c3".to_string(),
+            anchor: Some("fn main() {}".to_string()),
+            body: "quote \" and bell \u{7}".to_string(),
+            created_at: 1,
+            status: CommentStatus::WontFix,
+            resolved_at: Some(2),
+            updated_at: 2,
+            severity: Some(Severity::Nit),
+            replies: vec![Reply {
+                id: 4,
+                uuid: "r4".to_string(),
+                comment_id: 3,
+                body: "ok".to_string(),
+                author: "bob".to_string(),
+                author_kind: AuthorKind::Human,
+                created_at: 3,
+            }],
+            ..Comment::test(Some("src/lib.rs"), Some(1))
+        };
+        let review = Review {
+            id: 1,
+            uuid: "v1".to_string(),
+            author: "alice".to_string(),
+            body: String::new(),
+            verdict: Some(ReviewVerdict::RequestChanges),
+            created_at: 1,
+            submitted_at: Some(2),
+        };
+
+        let value = serde_json::to_value(&comment).unwrap();
+        assert_eq!(keys(&value), schema_keys("comment"));
+        assert_eq!(keys(&value["replies"][0]), schema_keys("reply"));
+        assert_eq!(value["status"], "wont-fix");
+        assert_eq!(value["severity"], "nit");
+        // Control characters survive a round trip
+        assert_eq!(value["body"], comment.body.as_str());
+
+        let context = code_context(&comment, "fn main() {}\n", 0).unwrap();
+        assert_eq!(keys(&serde_json::to_value(&context).unwrap()), schema_keys("code_context"));
+        assert_eq!(keys(&serde_json::to_value(&review).unwrap()), schema_keys("review"));
+        assert_eq!(serde_json::to_value(review.verdict).unwrap(), "request_changes");
+
+        let result = crate::batch::OpResult {
+            index: 0,
+            op: Some("reply"),
+            status: crate::batch::OpStatus::Failed,
+            id: Some(3),
+            error: Some(crate::batch::OpError { code: ErrorCode::NotFound, message: String::new() }),
+        };
+        assert_eq!(keys(&serde_json::to_value(&result).unwrap()), schema_keys("batch_result"));
+
+        let diff = crate::domain::Diff::default();
+        let progress = crate::domain::progress::review_progress(&diff, &[], std::slice::from_ref(&comment));
+        let summary = BranchSummary { commit_count: 1, file_count: 0, additions: 0, deletions: 0 };
+        let status = StatusReport { branch: "feat", base: "main", merge_base: "abc", summary: &summary, progress: &progress };
+        let document = Versioned { schema_version: SCHEMA_VERSION, body: &status };
+        assert_eq!(keys(&serde_json::to_value(&document).unwrap()), schema_keys("status_output"));
+
+        let file = FileContext { path: "src/db.rs", lines: vec![], comments: vec![&comment] };
+        let document = Versioned { schema_version: SCHEMA_VERSION, body: &file };
+        assert_eq!(keys(&serde_json::to_value(&document).unwrap()), schema_keys("file_context_output"));
+
+        let event = crate::domain::CommentEvent {
+            cursor: 4,
+            kind: crate::domain::CommentEventKind::Resolved,
+            comment_id: 3,
+            comment_uuid: "c3".to_string(),
+            reply_id: None,
+            created_at: 1,
+        };
+        let line = WatchEvent { event: &event, comment: None };
+        let document = Versioned { schema_version: SCHEMA_VERSION, body: &line };
+        assert_eq!(keys(&serde_json::to_value(&document).unwrap()), schema_keys("watch_event"));
+        let kinds: Vec<Value> = crate::domain::CommentEventKind::ALL
+            .iter()
+            .map(|k| serde_json::to_value(k).unwrap())
+            .collect();
+        assert_eq!(Value::from(kinds), schema_def("watch_event")["properties"]["event"]["enum"]);
+
+        let statuses: Vec<Value> = CommentStatus::ALL.iter().map(|s| serde_json::to_value(s).unwrap()).collect();
+        assert_eq!(Value::from(statuses), schema_def("comment")["properties"]["status"]["enum"]);
+    }
+
+    #[test]
+    fn errors_carry_their_exit_code() {
+        assert_eq!(error_code(&not_found("Comment #9 not found")).exit_code(), 3);
+        assert_eq!(error_code(&usage("bad").context("while parsing")), ErrorCode::Usage);
+        assert_eq!(error_code(&anyhow::anyhow!("disk full")), ErrorCode::Error);
+    }
+}
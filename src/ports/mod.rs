@@ -1,9 +1,11 @@
 pub mod file_watcher;
 pub mod git;
+pub mod notes;
 pub mod state_store;
 pub mod terminal;
 
 pub use file_watcher::{FileEvent, FileWatcher};
 pub use git::GitRepo;
+pub use notes::NotesTransport;
 pub use state_store::{NewComment, NewReply, StateStore, ViewedFile};
 pub use terminal::{KeyCode, KeyEvent, KeyModifiers, MouseEvent, Terminal, TerminalEvent};
@@ -0,0 +1,20 @@
+//! Review notes port (trait).
+//! Defines the interface for sharing branch snapshots through a git remote.
+
+use anyhow::Result;
+
+/// Port for storing branch snapshots in git and exchanging them with remotes.
+pub trait NotesTransport {
+    /// Read the snapshot stored for a branch, if any.
+    fn read_note(&self, branch: &str) -> Result<Option<String>>;
+
+    /// Store a branch snapshot, replacing the previous one.
+    fn write_note(&self, branch: &str, content: &str) -> Result<()>;
+
+    /// Fetch the remote's notes, replacing the local notes ref.
+    fn fetch_notes(&self, remote: &str) -> Result<()>;
+
+    /// Push the local notes to a remote. Fails if the remote moved on since
+    /// the last fetch.
+    fn push_notes(&self, remote: &str) -> Result<()>;
+}
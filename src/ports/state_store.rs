@@ -1,7 +1,7 @@
 //! State store port (trait).
 //! Defines the interface for persisting application state.
 
-use crate::domain::Comment;
+use crate::domain::{AuthorKind, Comment, CommentEvent, CommentStatus, Reply, Review, ReviewVerdict, Severity};
 use anyhow::Result;
 
 /// Information about when a file was viewed.
@@ -14,11 +14,21 @@ pub struct ViewedFile {
 /// Input for creating a new comment (without id, timestamps).
 #[derive(Debug, Clone)]
 pub struct NewComment {
-    pub file_path: String,
-    pub start_line: usize,
-    pub end_line: usize,
+    /// None = branch-level comment
+    pub file_path: Option<String>,
+    /// None = whole-file (or branch-level) comment
+    pub line_range: Option<(usize, usize)>,
+    /// Text of the anchored lines, to notice later when they change
+    pub anchor: Option<String>,
     pub body: String,
     pub author: String,
+    pub author_kind: AuthorKind,
+    pub severity: Option<Severity>,
+    pub labels: Vec<String>,
+    /// Pending review the comment is drafted in (None = publish immediately)
+    pub review_id: Option<i64>,
+    /// Stable identifier (see `domain::new_uuid`)
+    pub uuid: String,
 }
 
 /// Input for creating a new reply (without id, timestamps).
@@ -27,6 +37,9 @@ pub struct NewReply {
     pub comment_id: i64,
     pub body: String,
     pub author: String,
+    pub author_kind: AuthorKind,
+    /// Stable identifier (see `domain::new_uuid`)
+    pub uuid: String,
 }
 
 /// Port for persisting application state.
@@ -40,28 +53,100 @@ pub trait StateStore: Send + Sync {
     /// Get all viewed files for a repo/branch.
     fn get_viewed_files(&self, repo_path: &str, branch: &str) -> Result<Vec<ViewedFile>>;
 
+    /// Mark a file as viewed at a given time, keeping a later existing timestamp.
+    fn import_viewed(&self, repo_path: &str, branch: &str, file: &ViewedFile) -> Result<()>;
+
+    /// Run `f` as one transaction. The calls it makes on the store it is
+    /// given are committed together when it returns `Ok(true)`, and rolled
+    /// back otherwise (including when it panics). Other callers wait until
+    /// the transaction ends.
+    fn transaction(&self, f: &mut dyn FnMut(&dyn StateStore) -> Result<bool>) -> Result<()>;
+
     // ─── Comment methods ───
 
     /// Add a new comment, returns the comment ID.
     fn add_comment(&self, repo_path: &str, branch: &str, comment: NewComment) -> Result<i64>;
 
-    /// Get all comments for a repo/branch.
+    /// Get all published comments for a repo/branch.
+    /// Drafts belonging to pending reviews are excluded.
     fn get_comments(&self, repo_path: &str, branch: &str) -> Result<Vec<Comment>>;
 
+    /// Move a comment to a new workflow status.
+    fn set_comment_status(&self, comment_id: i64, status: CommentStatus) -> Result<()>;
 
     /// Mark a comment as resolved.
-    fn resolve_comment(&self, comment_id: i64) -> Result<()>;
+    fn resolve_comment(&self, comment_id: i64) -> Result<()> {
+        self.set_comment_status(comment_id, CommentStatus::Resolved)
+    }
 
-    /// Mark a comment as unresolved.
-    fn unresolve_comment(&self, comment_id: i64) -> Result<()>;
+    /// Reopen a comment.
+    fn unresolve_comment(&self, comment_id: i64) -> Result<()> {
+        self.set_comment_status(comment_id, CommentStatus::Open)
+    }
 
-    /// Delete a comment.
+    /// Assign a comment to someone (None = unassign).
+    fn assign_comment(&self, comment_id: i64, assignee: Option<&str>) -> Result<()>;
+
+    /// Delete a comment, leaving a tombstone for its UUID.
     fn delete_comment(&self, comment_id: i64) -> Result<()>;
 
+    // ─── Sharing methods ───
+
+    /// Insert a published comment from another machine as-is: UUID,
+    /// timestamps, workflow state, replies and `review_id` (a local review ID)
+    /// are kept. Returns the local ID.
+    fn import_comment(&self, repo_path: &str, branch: &str, comment: &Comment) -> Result<i64>;
+
+    /// Overwrite a comment's status, assignee, position and timestamps from
+    /// another copy.
+    fn update_comment_state(&self, comment_id: i64, comment: &Comment) -> Result<()>;
+
+    /// Re-anchor a line comment to other lines, e.g. after edits above it.
+    fn move_comment(&self, comment_id: i64, line_range: (usize, usize), anchor: Option<&str>) -> Result<()>;
+
+    /// UUIDs of comments deleted on a repo/branch.
+    fn get_deleted_comments(&self, repo_path: &str, branch: &str) -> Result<Vec<String>>;
+
+    /// Record that a comment was deleted elsewhere.
+    fn record_deleted_comment(&self, repo_path: &str, branch: &str, uuid: &str) -> Result<()>;
 
     // ─── Reply methods ───
 
     /// Add a reply to a comment, returns the reply ID.
     fn add_reply(&self, reply: NewReply) -> Result<i64>;
 
+    /// Insert a reply from another machine as-is, returns the local ID.
+    fn import_reply(&self, comment_id: i64, reply: &Reply) -> Result<i64>;
+
+    // ─── Event methods ───
+
+    /// Changes to a repo/branch's published comments after `cursor`, oldest first.
+    fn get_comment_events(&self, repo_path: &str, branch: &str, cursor: i64) -> Result<Vec<CommentEvent>>;
+
+    /// Cursor of the latest event on any branch (0 when there are none).
+    fn latest_event_cursor(&self) -> Result<i64>;
+
+    // ─── Review methods ───
+
+    /// Start a pending review, returns the review ID.
+    fn start_review(&self, repo_path: &str, branch: &str, author: &str) -> Result<i64>;
+
+    /// Get the author's pending review for a repo/branch, if any.
+    fn get_pending_review(&self, repo_path: &str, branch: &str, author: &str)
+        -> Result<Option<Review>>;
+
+    /// Get all comments made in a review (drafts, if the review is pending).
+    fn get_review_comments(&self, review_id: i64) -> Result<Vec<Comment>>;
+
+    /// Publish a pending review with a summary and verdict.
+    fn submit_review(&self, review_id: i64, body: &str, verdict: ReviewVerdict) -> Result<()>;
+
+    /// Discard a pending review along with its draft comments.
+    fn discard_review(&self, review_id: i64) -> Result<()>;
+
+    /// Get all submitted reviews for a repo/branch, oldest first.
+    fn get_reviews(&self, repo_path: &str, branch: &str) -> Result<Vec<Review>>;
+
+    /// Insert a submitted review from another machine as-is, returns the local ID.
+    fn import_review(&self, repo_path: &str, branch: &str, review: &Review) -> Result<i64>;
 }
@@ -0,0 +1,245 @@
+//! External reviewers (`panko review --with <command>`): any local program
+//! that reads the branch diff on stdin and prints findings as JSON lines.
+//!
+//! ```json
+//! {"file": "src/db.rs", "line": 10, "end_line": 12, "body": "...", "severity": "warning"}
+//! ```
+//!
+//! Only `body` is required: without `line` a finding is about the whole file,
+//! and without `file` about the branch. Findings become comments authored by
+//! the reviewer, as an agent; on re-runs, findings it already left (same
+//! place, same text) are skipped.
+
+use crate::batch;
+use crate::domain::{self, AuthorKind, Severity};
+use crate::output;
+use crate::ports::{NewComment, StateStore};
+use anyhow::{bail, Context, Result};
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::io::Write;
+use std::path::Path;
+use std::process::{Command, Stdio};
+
+/// One line of reviewer output.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+pub struct Finding {
+    /// Path relative to the repository root
+    pub file: Option<String>,
+    /// 1-based, in the new version of the file
+    #[serde(alias = "start", alias = "start_line")]
+    pub line: Option<usize>,
+    /// Defaults to `line`
+    #[serde(alias = "end")]
+    pub end_line: Option<usize>,
+    #[serde(alias = "message")]
+    pub body: String,
+    /// A panko severity, or a linter level (error, warning, note, ...)
+    pub severity: Option<String>,
+    #[serde(default)]
+    pub labels: Vec<String>,
+}
+
+/// How many findings were added, and how many the reviewer had already left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Ingested {
+    pub added: usize,
+    pub duplicates: usize,
+}
+
+/// Where a finding is and what it says, to recognize it on re-runs.
+type FindingKey = (Option<String>, Option<(usize, usize)>, String);
+
+/// Default author for a reviewer command: the program's file name.
+pub fn default_name(command: &str) -> String {
+    command
+        .split_whitespace()
+        .next()
+        .and_then(|program| Path::new(program).file_name())
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_else(|| "reviewer".to_string())
+}
+
+/// Run `command` through the shell in `workdir` with `input` on stdin and
+/// `env` added to its environment, returning its stdout. Its stderr passes
+/// through. A failing command is an error only when it printed nothing, since
+/// linters often exit non-zero when they have findings.
+pub fn run(command: &str, input: &[u8], workdir: &Path, env: &[(&str, &str)]) -> Result<String> {
+    let mut child = Command::new("sh")
+        .arg("-c")
+        .arg(command)
+        .current_dir(workdir)
+        .envs(env.iter().copied())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .spawn()
+        .with_context(|| format!("Failed to run reviewer '{}'", command))?;
+
+    // Write from another thread so a reviewer that prints before reading all
+    // of its input can't deadlock against us
+    let mut stdin = child.stdin.take().context("Reviewer stdin unavailable")?;
+    let output = std::thread::scope(|scope| {
+        scope.spawn(move || {
+            // A reviewer may exit without reading everything
+            let _ = stdin.write_all(input);
+        });
+        child.wait_with_output()
+    })
+    .with_context(|| format!("Failed to run reviewer '{}'", command))?;
+
+    let stdout = String::from_utf8(output.stdout).context("Reviewer output is not UTF-8")?;
+    if !output.status.success() && stdout.trim().is_empty() {
+        bail!("Reviewer '{}' failed ({})", command, output.status);
+    }
+    Ok(stdout)
+}
+
+/// Parse reviewer output, one JSON finding per non-blank line.
+pub fn parse(output: &str) -> Result<Vec<Finding>> {
+    output
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            serde_json::from_str(line)
+                .map_err(|e| output::usage(format!("Reviewer output line {}: {}", i + 1, e)))
+        })
+        .collect()
+}
+
+/// Map a finding's severity: panko's own names, or common linter levels.
+pub fn severity_from_level(level: &str) -> Option<Severity> {
+    Severity::parse(level).or(match level.to_ascii_lowercase().as_str() {
+        "error" | "fatal" | "critical" => Some(Severity::Blocking),
+        "warning" | "warn" => Some(Severity::Suggestion),
+        "note" | "info" | "hint" | "help" | "style" => Some(Severity::Nit),
+        _ => None,
+    })
+}
+
+/// Add findings as comments by `reviewer` in one transaction, skipping ones
+/// it already left. Anchors are read from the working tree at `repo_path`.
+pub fn ingest(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    reviewer: &str,
+    findings: &[Finding],
+) -> Result<Ingested> {
+    let mut comments = Vec::with_capacity(findings.len());
+    for (i, finding) in findings.iter().enumerate() {
+        comments.push(new_comment(repo_path, reviewer, AuthorKind::Agent, finding).with_context(|| format!("Finding {}", i + 1))?);
+    }
+
+    let mut ingested = Ingested { added: 0, duplicates: 0 };
+    store.transaction(&mut |tx| {
+        ingested = Ingested { added: 0, duplicates: 0 };
+        // A person who shares the reviewer's name isn't the reviewer
+        let mut seen: HashSet<FindingKey> = tx
+            .get_comments(repo_path, branch)?
+            .into_iter()
+            .filter(|c| c.author == reviewer && c.author_kind == AuthorKind::Agent)
+            .map(|c| (c.file_path, c.line_range, c.body))
+            .collect();
+        for comment in &comments {
+            if !seen.insert((comment.file_path.clone(), comment.line_range, comment.body.clone())) {
+                ingested.duplicates += 1;
+                continue;
+            }
+            tx.add_comment(repo_path, branch, comment.clone())?;
+            ingested.added += 1;
+        }
+        Ok(true)
+    })?;
+    Ok(ingested)
+}
+
+/// The comment `reviewer` leaves for a finding.
+pub fn new_comment(repo_path: &str, reviewer: &str, kind: AuthorKind, finding: &Finding) -> Result<NewComment> {
+    let line_range = match (finding.line, finding.end_line) {
+        (Some(start), end) => Some((start, end.unwrap_or(start))),
+        (None, Some(_)) => return Err(output::usage("`end_line` needs a `line`")),
+        (None, None) => None,
+    };
+    let anchor = match (&finding.file, line_range) {
+        (Some(file), Some(range)) => batch::line_anchor(repo_path, file, range)?,
+        (None, Some(_)) => return Err(output::usage("A line finding needs a `file`")),
+        _ => None,
+    };
+    let severity = match &finding.severity {
+        Some(level) => Some(
+            severity_from_level(level).ok_or_else(|| output::usage(format!("Unknown severity '{}'", level)))?,
+        ),
+        None => None,
+    };
+
+    Ok(NewComment {
+        file_path: finding.file.clone(),
+        line_range,
+        anchor,
+        body: finding.body.clone(),
+        author: reviewer.to_string(),
+        author_kind: kind,
+        severity,
+        labels: finding.labels.clone(),
+        review_id: None,
+        uuid: domain::new_uuid(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SqliteStateStore;
+
+    const OUTPUT: &str = r#"
+{"file": "src/db.rs", "line": 3, "body": "unwrap on a fallible query", "severity": "error", "rule": "L001"}
+{"file": "src/db.rs", "message": "module is getting long", "severity": "note"}
+
+{"body": "no tests for the new module"}
+{"file": "src/db.rs", "line": 3, "body": "unwrap on a fallible query", "severity": "error"}
+"#;
+
+    #[test]
+    fn findings_become_comments_once() {
+        let findings = parse(OUTPUT).unwrap();
+        assert_eq!(findings.len(), 4);
+        assert_eq!(findings[1].body, "module is getting long");
+
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        // A person whose git user shares the reviewer's name said the same thing first
+        let said = new_comment("/repo", "lint.sh", AuthorKind::Human, &findings[2]).unwrap();
+        store.add_comment("/repo", "main", said).unwrap();
+        let ingested = ingest(&store, "/repo", "main", "lint.sh", &findings).unwrap();
+        assert_eq!(ingested, Ingested { added: 3, duplicates: 1 });
+
+        let comments = store.get_comments("/repo", "main").unwrap();
+        assert_eq!(comments.iter().filter(|c| c.author_kind == AuthorKind::Agent).count(), 3);
+        let line = comments.iter().find(|c| c.line_range.is_some()).unwrap();
+        assert_eq!(line.line_range, Some((3, 3)));
+        assert_eq!(line.severity, Some(Severity::Blocking));
+        assert_eq!(line.author, "lint.sh");
+
+        // Re-running adds nothing, but another reviewer's findings are its own
+        let again = ingest(&store, "/repo", "main", "lint.sh", &findings).unwrap();
+        assert_eq!(again, Ingested { added: 0, duplicates: 4 });
+        let other = ingest(&store, "/repo", "main", "llm", &findings[..1]).unwrap();
+        assert_eq!(other.added, 1);
+
+        assert!(parse("{\"line\": 2}").is_err());
+        let bad = parse(r#"{"line": 2, "body": "where?"}"#).unwrap();
+        assert!(ingest(&store, "/repo", "main", "lint.sh", &bad).is_err());
+        assert_eq!(default_name("./scripts/review.sh --strict"), "review.sh");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn runs_commands_with_the_diff_on_stdin() {
+        let dir = std::env::temp_dir();
+        let echoed = run("cat; printf ' %s' \"$PANKO_BRANCH\"", b"diff", &dir, &[("PANKO_BRANCH", "feat")]).unwrap();
+        assert_eq!(echoed, "diff feat");
+        assert!(run("exit 3", b"", &dir, &[]).is_err());
+        assert_eq!(run("echo '{}'; exit 1", b"", &dir, &[]).unwrap(), "{}\n");
+    }
+}
@@ -21,7 +21,6 @@ pub struct SearchableEntry {
 pub struct SearchResult {
     pub entry: SearchableEntry,
     pub score: u32,
-    pub match_indices: Vec<u32>,
 }
 
 /// State for fuzzy search modal.
@@ -131,18 +130,16 @@ pub fn fuzzy_search(query: &str, index: &[SearchableEntry], max_results: usize)
     for entry in index {
         let mut buf = Vec::new();
         let haystack = Utf32Str::new(&entry.content, &mut buf);
-        let mut indices = Vec::new();
-        if let Some(score) = pattern.indices(haystack, &mut matcher, &mut indices) {
+        if let Some(score) = pattern.score(haystack, &mut matcher) {
             results.push(SearchResult {
                 entry: entry.clone(),
                 score,
-                match_indices: indices,
             });
         }
     }
 
     // Sort by score descending
-    results.sort_by(|a, b| b.score.cmp(&a.score));
+    results.sort_by_key(|r| std::cmp::Reverse(r.score));
 
     // Limit results
     results.truncate(max_results);
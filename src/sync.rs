@@ -0,0 +1,279 @@
+//! Sharing comments between machines: snapshot a branch's comments and merge
+//! snapshots from elsewhere into the local state store.
+//!
+//! Merging is keyed on comment/reply UUIDs, so applying the same snapshot twice
+//! changes nothing:
+//! - comments and replies are unioned
+//! - status, assignee and position follow whichever copy changed last
+//!   (`updated_at`)
+//! - deletions win over edits and are remembered as tombstones
+
+use crate::domain::snapshot::BranchSnapshot;
+use crate::ports::{NotesTransport, StateStore};
+use anyhow::Result;
+use std::collections::HashSet;
+
+/// What a merge changed locally.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MergeStats {
+    pub added: usize,
+    pub updated: usize,
+    pub deleted: usize,
+    pub replies_added: usize,
+}
+
+impl MergeStats {
+    pub fn summary(&self) -> String {
+        format!(
+            "{} new, {} updated, {} deleted comments; {} new replies",
+            self.added, self.updated, self.deleted, self.replies_added
+        )
+    }
+}
+
+/// Snapshot a branch's published comments and tombstones.
+pub fn snapshot(store: &dyn StateStore, repo_path: &str, branch: &str) -> Result<BranchSnapshot> {
+    Ok(BranchSnapshot {
+        comments: store.get_comments(repo_path, branch)?,
+        deleted: store.get_deleted_comments(repo_path, branch)?,
+    })
+}
+
+/// Merge an incoming snapshot into the local store.
+pub fn merge(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    incoming: &BranchSnapshot,
+) -> Result<MergeStats> {
+    let mut stats = MergeStats::default();
+    let local = store.get_comments(repo_path, branch)?;
+    let tombstones: HashSet<String> = store
+        .get_deleted_comments(repo_path, branch)?
+        .into_iter()
+        .chain(incoming.deleted.iter().cloned())
+        .collect();
+
+    for uuid in &incoming.deleted {
+        match local.iter().find(|c| &c.uuid == uuid) {
+            Some(comment) => {
+                store.delete_comment(comment.id)?;
+                stats.deleted += 1;
+            }
+            None => store.record_deleted_comment(repo_path, branch, uuid)?,
+        }
+    }
+
+    for comment in &incoming.comments {
+        if tombstones.contains(&comment.uuid) {
+            continue;
+        }
+        let Some(existing) = local.iter().find(|c| c.uuid == comment.uuid) else {
+            store.import_comment(repo_path, branch, comment)?;
+            stats.added += 1;
+            continue;
+        };
+
+        let changed = (comment.status, &comment.assignee, comment.resolved_at, comment.line_range, &comment.anchor)
+            != (existing.status, &existing.assignee, existing.resolved_at, existing.line_range, &existing.anchor);
+        if changed && comment.updated_at > existing.updated_at {
+            store.update_comment_state(existing.id, comment)?;
+            stats.updated += 1;
+        }
+
+        for reply in &comment.replies {
+            if !existing.replies.iter().any(|r| r.uuid == reply.uuid) {
+                store.import_reply(existing.id, reply)?;
+                stats.replies_added += 1;
+            }
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Fetch the remote's notes and merge this branch's snapshot, if there is one.
+pub fn pull_notes(
+    store: &dyn StateStore,
+    notes: &dyn NotesTransport,
+    repo_path: &str,
+    branch: &str,
+    remote: &str,
+) -> Result<MergeStats> {
+    notes.fetch_notes(remote)?;
+    match notes.read_note(branch)? {
+        Some(json) => merge(store, repo_path, branch, &BranchSnapshot::from_json(&json)?),
+        None => Ok(MergeStats::default()),
+    }
+}
+
+/// Publish this branch's comments to the remote's notes.
+///
+/// Pulls first so the pushed snapshot includes everyone else's comments and
+/// the push is a fast-forward. Notes for other branches that were written
+/// locally but never pushed are dropped by the fetch; they are rewritten from
+/// the state store the next time those branches are pushed.
+pub fn push_notes(
+    store: &dyn StateStore,
+    notes: &dyn NotesTransport,
+    repo_path: &str,
+    branch: &str,
+    remote: &str,
+) -> Result<MergeStats> {
+    let stats = pull_notes(store, notes, repo_path, branch, remote)?;
+    notes.write_note(branch, &snapshot(store, repo_path, branch)?.to_json()?)?;
+    notes.push_notes(remote)?;
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SqliteStateStore;
+    use crate::domain::{new_uuid, AuthorKind, CommentStatus};
+    use crate::ports::{NewComment, NewReply};
+
+    fn add_comment(store: &dyn StateStore, body: &str) -> i64 {
+        store
+            .add_comment(
+                "/repo",
+                "main",
+                NewComment {
+                    file_path: Some("src/lib.rs".to_string()),
+                    line_range: Some((1, 1)),
+                    anchor: None,
+                    body: body.to_string(),
+                    author: "alice".to_string(),
+                    author_kind: AuthorKind::Human,
+                    severity: None,
+                    labels: vec![],
+                    review_id: None,
+                    uuid: new_uuid(),
+                },
+            )
+            .unwrap()
+    }
+
+    fn share(from: &dyn StateStore, to: &dyn StateStore) -> MergeStats {
+        let json = snapshot(from, "/repo", "main").unwrap().to_json().unwrap();
+        merge(to, "/repo", "main", &BranchSnapshot::from_json(&json).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn merging_is_idempotent() {
+        let alice = SqliteStateStore::open_in_memory().unwrap();
+        let bob = SqliteStateStore::open_in_memory().unwrap();
+        let id = add_comment(&alice, "rename this");
+        alice
+            .add_reply(NewReply {
+                comment_id: id,
+                body: "why?".to_string(),
+                author: "alice".to_string(),
+                author_kind: AuthorKind::Human,
+                uuid: new_uuid(),
+            })
+            .unwrap();
+
+        let first = share(&alice, &bob);
+        assert_eq!((first.added, first.replies_added), (1, 0));
+        assert_eq!(share(&alice, &bob), MergeStats::default());
+        assert_eq!(share(&bob, &alice), MergeStats::default());
+
+        let comments = bob.get_comments("/repo", "main").unwrap();
+        assert_eq!(comments.len(), 1);
+        assert_eq!(comments[0].replies.len(), 1);
+    }
+
+    #[test]
+    fn replies_are_unioned_and_latest_status_wins() {
+        let alice = SqliteStateStore::open_in_memory().unwrap();
+        let bob = SqliteStateStore::open_in_memory().unwrap();
+        add_comment(&alice, "rename this");
+        share(&alice, &bob);
+
+        let bob_id = bob.get_comments("/repo", "main").unwrap()[0].id;
+        bob.add_reply(NewReply {
+            comment_id: bob_id,
+            body: "done".to_string(),
+            author: "bob".to_string(),
+            author_kind: AuthorKind::Human,
+            uuid: new_uuid(),
+        })
+        .unwrap();
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        bob.set_comment_status(bob_id, CommentStatus::Resolved).unwrap();
+
+        let stats = share(&bob, &alice);
+        assert_eq!((stats.updated, stats.replies_added), (1, 1));
+        let comment = &alice.get_comments("/repo", "main").unwrap()[0];
+        assert_eq!(comment.status, CommentStatus::Resolved);
+        assert_eq!(comment.replies[0].body, "done");
+
+        // Alice's older copy must not reopen it on Bob's side
+        assert_eq!(share(&alice, &bob).updated, 0);
+    }
+
+    #[test]
+    fn moved_comments_follow_the_latest_move() {
+        let alice = SqliteStateStore::open_in_memory().unwrap();
+        let bob = SqliteStateStore::open_in_memory().unwrap();
+        let id = add_comment(&alice, "rename this");
+        share(&alice, &bob);
+
+        std::thread::sleep(std::time::Duration::from_millis(2));
+        alice.move_comment(id, (4, 5), Some("fn rename()")).unwrap();
+        assert_eq!(share(&alice, &bob).updated, 1);
+        let comment = &bob.get_comments("/repo", "main").unwrap()[0];
+        assert_eq!(comment.line_range, Some((4, 5)));
+        assert_eq!(comment.anchor.as_deref(), Some("fn rename()"));
+
+        // Both copies agree now, so nothing moves back
+        assert_eq!(share(&bob, &alice).updated, 0);
+    }
+
+    #[test]
+    fn deletions_propagate_and_stick() {
+        let alice = SqliteStateStore::open_in_memory().unwrap();
+        let bob = SqliteStateStore::open_in_memory().unwrap();
+        let id = add_comment(&alice, "typo");
+        share(&alice, &bob);
+        let stale = snapshot(&bob, "/repo", "main").unwrap();
+
+        alice.delete_comment(id).unwrap();
+        assert_eq!(share(&alice, &bob).deleted, 1);
+        assert!(bob.get_comments("/repo", "main").unwrap().is_empty());
+
+        // An old snapshot still containing the comment does not resurrect it
+        merge(&alice, "/repo", "main", &stale).unwrap();
+        assert!(alice.get_comments("/repo", "main").unwrap().is_empty());
+    }
+
+    #[test]
+    fn second_checkout_can_share_the_same_database() {
+        // Two clones of one remote on one machine share the state store
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let id = add_comment(&store, "rename this");
+        store
+            .add_reply(NewReply {
+                comment_id: id,
+                body: "why?".to_string(),
+                author: "alice".to_string(),
+                author_kind: AuthorKind::Human,
+                uuid: new_uuid(),
+            })
+            .unwrap();
+
+        let shared = snapshot(&store, "/repo", "main").unwrap();
+        let stats = merge(&store, "/clone", "main", &shared).unwrap();
+        assert_eq!(stats.added, 1);
+        assert_eq!(merge(&store, "/clone", "main", &shared).unwrap(), MergeStats::default());
+        assert_eq!(store.get_comments("/clone", "main").unwrap()[0].replies.len(), 1);
+
+        // Deleting the clone's copy leaves the original and its tombstones alone
+        let copy = store.get_comments("/clone", "main").unwrap()[0].id;
+        store.delete_comment(copy).unwrap();
+        assert_eq!(store.get_comments("/repo", "main").unwrap().len(), 1);
+        assert!(store.get_deleted_comments("/repo", "main").unwrap().is_empty());
+        assert_eq!(store.get_deleted_comments("/clone", "main").unwrap(), vec![shared.comments[0].uuid.clone()]);
+    }
+}
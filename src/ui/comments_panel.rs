@@ -0,0 +1,349 @@
+//! Comments overview panel: every comment on the branch, grouped by file.
+
+use crate::domain::{Comment, CommentScope};
+use crate::ui::styles;
+use ratatui::{
+    layout::{Constraint, Layout, Rect},
+    style::{Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, List, ListItem, ListState, Padding, Paragraph},
+    Frame,
+};
+
+/// Which comments the panel lists, by status.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StatusFilter {
+    /// Anything not yet resolved or declined
+    #[default]
+    Open,
+    /// Resolved or won't fix
+    Resolved,
+    All,
+}
+
+impl StatusFilter {
+    pub fn next(self) -> Self {
+        match self {
+            StatusFilter::Open => StatusFilter::Resolved,
+            StatusFilter::Resolved => StatusFilter::All,
+            StatusFilter::All => StatusFilter::Open,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            StatusFilter::Open => "Open",
+            StatusFilter::Resolved => "Resolved",
+            StatusFilter::All => "All",
+        }
+    }
+
+    fn matches(self, comment: &Comment) -> bool {
+        match self {
+            StatusFilter::Open => !comment.status.is_closed(),
+            StatusFilter::Resolved => comment.status.is_closed(),
+            StatusFilter::All => true,
+        }
+    }
+}
+
+/// Panel filters: status plus an optional author.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PanelFilter {
+    pub status: StatusFilter,
+    /// Only show comments by this author (None = everyone)
+    pub author: Option<String>,
+}
+
+impl PanelFilter {
+    pub fn matches(&self, comment: &Comment) -> bool {
+        self.status.matches(comment)
+            && self.author.as_ref().is_none_or(|author| &comment.author == author)
+    }
+
+    /// Step the author filter through everyone who has commented, then back to all.
+    pub fn cycle_author(&mut self, comments: &[Comment]) {
+        let mut authors: Vec<&str> = comments.iter().map(|c| c.author.as_str()).collect();
+        authors.sort_unstable();
+        authors.dedup();
+
+        let next = match &self.author {
+            None => authors.first(),
+            Some(current) => match authors.iter().position(|a| a == current) {
+                Some(i) => authors.get(i + 1),
+                None => authors.first(),
+            },
+        };
+        self.author = next.map(|a| a.to_string());
+    }
+
+    pub fn label(&self) -> String {
+        match &self.author {
+            Some(author) => format!("{} · {}", self.status.label(), author),
+            None => self.status.label().to_string(),
+        }
+    }
+}
+
+/// A row in the panel.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PanelItem {
+    /// Group header: a file path, or None for branch-level comments
+    Group(Option<String>),
+    Comment(i64),
+}
+
+/// Build the panel rows: matching comments grouped by file, branch-level first.
+pub fn build_items(comments: &[Comment], filter: &PanelFilter) -> Vec<PanelItem> {
+    let mut matching: Vec<&Comment> = comments.iter().filter(|c| filter.matches(c)).collect();
+    matching.sort_by(|a, b| {
+        (a.file_path.is_some(), &a.file_path, a.line_range, a.created_at)
+            .cmp(&(b.file_path.is_some(), &b.file_path, b.line_range, b.created_at))
+    });
+
+    let mut items = Vec::new();
+    let mut current_group: Option<Option<&str>> = None;
+    for comment in matching {
+        let group = comment.file_path.as_deref();
+        if current_group != Some(group) {
+            items.push(PanelItem::Group(group.map(String::from)));
+            current_group = Some(group);
+        }
+        items.push(PanelItem::Comment(comment.id));
+    }
+    items
+}
+
+/// Panel state needed for rendering.
+pub struct PanelView<'a> {
+    /// All comments (unaffected by the inline comment filter)
+    pub comments: &'a [Comment],
+    pub items: &'a [PanelItem],
+    pub filter: &'a PanelFilter,
+    pub selected: usize,
+    pub list_state: &'a mut ListState,
+}
+
+/// Render the comments panel.
+pub fn render(frame: &mut Frame, area: Rect, panel: PanelView<'_>, focused: bool) {
+    // Split area to have right border run full height
+    let h_chunks = Layout::default()
+        .direction(ratatui::layout::Direction::Horizontal)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(area);
+
+    let border_color = if focused {
+        styles::fg_hunk()
+    } else {
+        styles::fg_border()
+    };
+
+    let chunks = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints([
+            Constraint::Length(3), // Title with active filters
+            Constraint::Min(1),    // Comment list
+            Constraint::Length(2), // Bottom bar with hints
+        ])
+        .split(h_chunks[0]);
+
+    // Right border with a junction where the bottom bar starts
+    let junction_row = area.height.saturating_sub(2);
+    let mut border_chars = String::new();
+    for i in 0..area.height {
+        border_chars.push(if i == junction_row { '┤' } else { '│' });
+        border_chars.push('\n');
+    }
+    let border_widget = Paragraph::new(border_chars)
+        .style(Style::default().fg(border_color).bg(styles::bg_sidebar()));
+    frame.render_widget(border_widget, h_chunks[1]);
+
+    // Title block showing the active filters
+    let count = panel.items.iter().filter(|i| matches!(i, PanelItem::Comment(_))).count();
+    let title_block = Block::default()
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(styles::fg_border()))
+        .title(Span::styled(" Comments ", Style::default().fg(styles::fg_default())))
+        .style(Style::default().bg(styles::bg_sidebar()));
+    let title = Paragraph::new(format!(" {} ({})", panel.filter.label(), count))
+        .style(Style::default().fg(styles::fg_muted()).bg(styles::bg_sidebar()))
+        .block(title_block);
+    frame.render_widget(title, chunks[0]);
+
+    let items: Vec<ListItem> = panel
+        .items
+        .iter()
+        .enumerate()
+        .map(|(i, item)| {
+            let line = match item {
+                PanelItem::Group(path) => Line::from(Span::styled(
+                    path.as_deref().unwrap_or("Conversation").to_string(),
+                    Style::default().fg(styles::fg_directory()).add_modifier(Modifier::BOLD),
+                )),
+                PanelItem::Comment(id) => match panel.comments.iter().find(|c| c.id == *id) {
+                    Some(comment) => comment_row(comment),
+                    None => Line::default(),
+                },
+            };
+            let style = if i == panel.selected {
+                Style::default().bg(styles::bg_selected())
+            } else {
+                Style::default()
+            };
+            ListItem::new(line).style(style)
+        })
+        .collect();
+
+    panel.list_state.select(Some(panel.selected));
+
+    let list_block = Block::default()
+        .padding(Padding::new(1, 0, 0, 0))
+        .style(Style::default().bg(styles::bg_sidebar()));
+
+    let list = List::new(items)
+        .block(list_block)
+        .style(Style::default().bg(styles::bg_sidebar()))
+        .highlight_style(Style::default().bg(styles::bg_selected()));
+
+    frame.render_stateful_widget(list, chunks[1], panel.list_state);
+
+    if panel.items.is_empty() {
+        let empty = Paragraph::new("  No comments")
+            .style(Style::default().fg(styles::fg_muted()).bg(styles::bg_sidebar()));
+        frame.render_widget(empty, chunks[1]);
+    }
+
+    // Bottom bar with hints
+    let hint_style = Style::default().fg(styles::fg_muted());
+    let key_style = Style::default().fg(if focused { styles::fg_default() } else { styles::fg_muted() });
+    let focus_style = if focused {
+        Style::default().fg(styles::fg_hunk()).add_modifier(Modifier::BOLD)
+    } else {
+        Style::default().fg(styles::fg_muted())
+    };
+
+    let hints = Line::from(vec![
+        Span::styled(" 3", focus_style),
+        Span::styled(" │ ", Style::default().fg(styles::fg_border())),
+        Span::styled("⏎", key_style),
+        Span::styled(" Jump ", hint_style),
+        Span::styled("f", key_style),
+        Span::styled(" Status ", hint_style),
+        Span::styled("a", key_style),
+        Span::styled(" Author ", hint_style),
+    ]);
+
+    let border_line = Line::from(Span::styled(
+        "─".repeat(chunks[2].width as usize),
+        Style::default().fg(border_color).bg(styles::bg_header()),
+    ));
+
+    let hints_widget = Paragraph::new(vec![border_line, hints])
+        .style(Style::default().bg(styles::bg_header()));
+    frame.render_widget(hints_widget, chunks[2]);
+}
+
+/// One comment row: "  ◐ L5-L7 alice  Rename this… ↳2".
+fn comment_row(comment: &Comment) -> Line<'static> {
+    let closed = comment.status.is_closed();
+    let text_style = if closed {
+        Style::default().fg(styles::fg_muted())
+    } else {
+        Style::default().fg(styles::fg_default())
+    };
+    let location = match comment.scope() {
+        CommentScope::Lines => comment.line_range_display(),
+        CommentScope::File => "file".to_string(),
+        CommentScope::Branch => String::new(),
+    };
+    let first_line = comment.body.lines().next().unwrap_or_default().to_string();
+
+    let mut spans = vec![
+        Span::raw("  "),
+        Span::styled(
+            format!("{} ", comment.status.icon()),
+            Style::default().fg(styles::fg_status(comment.status)),
+        ),
+    ];
+    if comment.draft {
+        spans.push(Span::styled("◆ ", Style::default().fg(styles::fg_hunk())));
+    }
+    if !location.is_empty() {
+        spans.push(Span::styled(format!("{} ", location), Style::default().fg(styles::fg_muted())));
+    }
+    spans.push(Span::styled(format!("{} ", comment.author), Style::default().fg(styles::fg_path())));
+    if !comment.author_kind.is_human() {
+        spans.push(Span::styled(
+            format!("{} ", comment.author_kind.as_str()),
+            Style::default().fg(styles::fg_author_kind(comment.author_kind)).add_modifier(Modifier::ITALIC),
+        ));
+    }
+    spans.push(Span::styled(first_line, text_style));
+    if !comment.replies.is_empty() {
+        spans.push(Span::styled(
+            format!(" ↳{}", comment.replies.len()),
+            Style::default().fg(styles::fg_muted()),
+        ));
+    }
+    Line::from(spans)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::CommentStatus;
+
+    fn comment(id: i64, file: Option<&str>, line: Option<usize>, author: &str) -> Comment {
+        Comment {
+            id,
+            uuid: id.to_string(),
+            body: format!("comment {}", id),
+            author: author.to_string(),
+            created_at: id,
+            updated_at: id,
+            ..Comment::test(file, line)
+        }
+    }
+
+    #[test]
+    fn groups_by_file_with_branch_comments_first() {
+        let comments = vec![
+            comment(1, Some("b.rs"), Some(3), "alice"),
+            comment(2, Some("a.rs"), Some(9), "bob"),
+            comment(3, None, None, "alice"),
+            comment(4, Some("a.rs"), None, "alice"),
+        ];
+        let items = build_items(&comments, &PanelFilter::default());
+        assert_eq!(
+            items,
+            vec![
+                PanelItem::Group(None),
+                PanelItem::Comment(3),
+                PanelItem::Group(Some("a.rs".to_string())),
+                PanelItem::Comment(4),
+                PanelItem::Comment(2),
+                PanelItem::Group(Some("b.rs".to_string())),
+                PanelItem::Comment(1),
+            ]
+        );
+    }
+
+    #[test]
+    fn filters_by_status_and_author() {
+        let mut resolved = comment(2, Some("a.rs"), Some(1), "bob");
+        resolved.status = CommentStatus::Resolved;
+        let comments = vec![comment(1, Some("a.rs"), Some(5), "alice"), resolved];
+
+        let mut filter = PanelFilter::default();
+        assert_eq!(build_items(&comments, &filter).len(), 2);
+
+        filter.status = StatusFilter::All;
+        filter.cycle_author(&comments);
+        assert_eq!(filter.author.as_deref(), Some("alice"));
+        filter.cycle_author(&comments);
+        assert_eq!(filter.author.as_deref(), Some("bob"));
+        assert_eq!(build_items(&comments, &filter)[1], PanelItem::Comment(2));
+        filter.cycle_author(&comments);
+        assert_eq!(filter.author, None);
+    }
+}
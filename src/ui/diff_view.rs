@@ -4,8 +4,9 @@
 #![allow(dead_code)]
 
 use crate::app::DiffSource;
-use crate::domain::{Comment, Diff, DiffLine, DiffStats};
-use crate::ui::{styles, syntax};
+use crate::domain::coverage::{DiffCoverage, FileCoverage, LineCoverage};
+use crate::domain::{AuthorKind, Comment, CommentScope, CommentStatus, Diff, DiffLine, DiffStats, Severity};
+use crate::ui::{markdown, styles, syntax};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Color, Modifier, Style},
@@ -33,6 +34,16 @@ pub struct HighlightedSegment {
     pub is_changed: bool, // For word-level diff overlay
 }
 
+/// A comment being written, rendered inline as an input box.
+#[derive(Debug, Clone)]
+pub struct DraftComment {
+    pub file_path: String,
+    /// Diff view line indices of the selection (None = whole-file comment)
+    pub line_range: Option<(usize, usize)>,
+    pub body: String,
+    pub severity: Option<Severity>,
+}
+
 /// A rendered line in the diff view.
 #[derive(Debug, Clone)]
 pub struct DiffViewLine {
@@ -642,7 +653,7 @@ fn apply_visual_selection_highlight(mut line: Line<'static>) -> Line<'static> {
 /// Render a GitHub-style inline comment box.
 /// Returns multiple lines for the comment display.
 /// If `reply_input` is Some, renders an input box for replying to this comment.
-fn render_comment_box(
+pub fn render_comment_box(
     comment: &Comment,
     width: u16,
     focused: bool,
@@ -654,7 +665,7 @@ fn render_comment_box(
     // Use accent color when focused or replying
     let border_color = if focused || reply_input.is_some() {
         styles::fg_hunk() // Accent color when selected or replying
-    } else if comment.resolved {
+    } else if comment.status.is_closed() {
         styles::fg_muted()
     } else {
         styles::fg_border()
@@ -664,8 +675,11 @@ fn render_comment_box(
     let mut lines = Vec::new();
 
     // ── Header line: "┌─ Comment on lines L69 to L75 ─────────────────┐"
-    let line_range = comment.line_range_display();
-    let header_text = format!(" Comment on lines {} ", line_range);
+    let header_text = match comment.scope() {
+        CommentScope::Lines => format!(" Comment on lines {} ", comment.line_range_display()),
+        CommentScope::File => " Comment on file ".to_string(),
+        CommentScope::Branch => " Conversation ".to_string(),
+    };
     let header_fill_len = inner_w.saturating_sub(header_text.len() + 2);
     let header_fill = "─".repeat(header_fill_len);
 
@@ -676,23 +690,38 @@ fn render_comment_box(
         Span::styled("┐", Style::default().fg(border_color)),
     ]));
 
-    // ── Author line: "│ synoet • 2h ago"
-    let resolved_badge = if comment.resolved { " ✓ Resolved" } else { "" };
-    let author_line = format!(" {} • {}{}", comment.author, comment.relative_time(), resolved_badge);
-    let author_pad = inner_w.saturating_sub(author_line.chars().count());
+    // ── Author line: "│ synoet • 2h ago  nit  #perf" (agents: "│ claude agent • 2h ago")
+    let mut status_badge = match comment.status {
+        CommentStatus::Open => String::new(),
+        status => format!(" {}", status.label()),
+    };
+    if let Some(assignee) = &comment.assignee {
+        status_badge.push_str(&format!(" → {}", assignee));
+    }
+    let draft_badge = if comment.draft { " ◆ Pending" } else { "" };
 
-    let author_style = if comment.resolved {
+    let author_style = if comment.status.is_closed() {
         Style::default().fg(styles::fg_muted()).bg(bg_color)
     } else {
         Style::default().fg(styles::fg_default()).bg(bg_color)
     };
 
-    lines.push(Line::from(vec![
-        Span::styled("  │", Style::default().fg(border_color)),
-        Span::styled(author_line, author_style),
-        Span::styled(" ".repeat(author_pad), Style::default().bg(bg_color)),
-        Span::styled("│", Style::default().fg(border_color)),
-    ]));
+    let mut author_spans = vec![Span::styled(format!(" {} {}", comment.status.icon(), comment.author), author_style)];
+    author_spans.extend(author_kind_badge(comment.author_kind, bg_color));
+    author_spans.extend([
+        Span::styled(format!(" • {}", comment.relative_time()), author_style),
+        Span::styled(status_badge, Style::default().fg(styles::fg_status(comment.status)).bg(bg_color)),
+        Span::styled(draft_badge, Style::default().fg(styles::fg_hunk()).bg(bg_color)),
+    ]);
+    author_spans.extend(tag_badges(comment.severity, &comment.labels, bg_color));
+    let author_width: usize = author_spans.iter().map(|s| s.width()).sum();
+    let author_pad = inner_w.saturating_sub(author_width);
+
+    let mut spans = vec![Span::styled("  │", Style::default().fg(border_color))];
+    spans.extend(author_spans);
+    spans.push(Span::styled(" ".repeat(author_pad), Style::default().bg(bg_color)));
+    spans.push(Span::styled("│", Style::default().fg(border_color)));
+    lines.push(Line::from(spans));
 
     // ── Empty line
     let empty_fill = " ".repeat(inner_w);
@@ -703,22 +732,23 @@ fn render_comment_box(
     ]));
 
     // ── Comment body (may wrap to multiple lines)
-    let body_style = if comment.resolved {
+    let body_style = if comment.status.is_closed() {
         Style::default().fg(styles::fg_muted()).bg(bg_color).add_modifier(Modifier::ITALIC)
     } else {
         Style::default().fg(styles::fg_default()).bg(bg_color)
     };
 
-    // Simple word wrap for comment body
-    for body_line in wrap_text(&comment.body, inner_w.saturating_sub(2)) {
-        let line_text = format!(" {}", body_line);
-        let pad_len = inner_w.saturating_sub(line_text.chars().count());
-        lines.push(Line::from(vec![
+    // Markdown-rendered body, wrapped to the box width
+    for body_line in markdown::render(&comment.body, inner_w.saturating_sub(2), body_style) {
+        let pad_len = inner_w.saturating_sub(1 + body_line.width());
+        let mut spans = vec![
             Span::styled("  │", Style::default().fg(border_color)),
-            Span::styled(line_text, body_style),
-            Span::styled(" ".repeat(pad_len), Style::default().bg(bg_color)),
-            Span::styled("│", Style::default().fg(border_color)),
-        ]));
+            Span::styled(" ", Style::default().bg(bg_color)),
+        ];
+        spans.extend(body_line.spans);
+        spans.push(Span::styled(" ".repeat(pad_len), Style::default().bg(bg_color)));
+        spans.push(Span::styled("│", Style::default().fg(border_color)));
+        lines.push(Line::from(spans));
     }
 
     // ── Replies
@@ -731,25 +761,29 @@ fn render_comment_box(
         ]));
 
         // Reply header
-        let reply_header = format!(" ↳ {} • {}", reply.author, reply.relative_time());
-        let reply_pad = inner_w.saturating_sub(reply_header.chars().count());
-        lines.push(Line::from(vec![
-            Span::styled("  │", Style::default().fg(border_color)),
-            Span::styled(reply_header, Style::default().fg(styles::fg_muted()).bg(bg_color)),
-            Span::styled(" ".repeat(reply_pad), Style::default().bg(bg_color)),
-            Span::styled("│", Style::default().fg(border_color)),
-        ]));
+        let header_style = Style::default().fg(styles::fg_muted()).bg(bg_color);
+        let mut header_spans = vec![Span::styled(format!(" ↳ {}", reply.author), header_style)];
+        header_spans.extend(author_kind_badge(reply.author_kind, bg_color));
+        header_spans.push(Span::styled(format!(" • {}", reply.relative_time()), header_style));
+        let reply_pad = inner_w.saturating_sub(header_spans.iter().map(|s| s.width()).sum());
+        let mut spans = vec![Span::styled("  │", Style::default().fg(border_color))];
+        spans.extend(header_spans);
+        spans.push(Span::styled(" ".repeat(reply_pad), Style::default().bg(bg_color)));
+        spans.push(Span::styled("│", Style::default().fg(border_color)));
+        lines.push(Line::from(spans));
 
         // Reply body
-        for reply_line in wrap_text(&reply.body, inner_w.saturating_sub(4)) {
-            let line_text = format!("   {}", reply_line);
-            let pad_len = inner_w.saturating_sub(line_text.chars().count());
-            lines.push(Line::from(vec![
+        let reply_style = Style::default().fg(styles::fg_default()).bg(bg_color);
+        for reply_line in markdown::render(&reply.body, inner_w.saturating_sub(4), reply_style) {
+            let pad_len = inner_w.saturating_sub(3 + reply_line.width());
+            let mut spans = vec![
                 Span::styled("  │", Style::default().fg(border_color)),
-                Span::styled(line_text, Style::default().fg(styles::fg_default()).bg(bg_color)),
-                Span::styled(" ".repeat(pad_len), Style::default().bg(bg_color)),
-                Span::styled("│", Style::default().fg(border_color)),
-            ]));
+                Span::styled("   ", Style::default().bg(bg_color)),
+            ];
+            spans.extend(reply_line.spans);
+            spans.push(Span::styled(" ".repeat(pad_len), Style::default().bg(bg_color)));
+            spans.push(Span::styled("│", Style::default().fg(border_color)));
+            lines.push(Line::from(spans));
         }
     }
 
@@ -822,7 +856,7 @@ fn render_comment_box(
             Span::styled("│", Style::default().fg(border_color)),
         ]));
 
-        let hints = if comment.resolved {
+        let hints = if comment.status.is_closed() {
             " R unresolve │ r reply │ D delete"
         } else {
             " R resolve │ r reply │ D delete"
@@ -847,11 +881,45 @@ fn render_comment_box(
     lines
 }
 
+/// Severity badge and label tags shown next to a comment's author.
+/// " agent" / " tool" after the name of a non-human author.
+fn author_kind_badge(kind: AuthorKind, bg_color: Color) -> Option<Span<'static>> {
+    (!kind.is_human()).then(|| {
+        Span::styled(
+            format!(" {}", kind.as_str()),
+            Style::default()
+                .fg(styles::fg_author_kind(kind))
+                .bg(bg_color)
+                .add_modifier(Modifier::ITALIC),
+        )
+    })
+}
+
+fn tag_badges(severity: Option<Severity>, labels: &[String], bg_color: Color) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    if let Some(severity) = severity {
+        spans.push(Span::styled("  ", Style::default().bg(bg_color)));
+        spans.push(Span::styled(
+            format!(" {} ", severity.as_str()),
+            Style::default()
+                .fg(bg_color)
+                .bg(styles::fg_severity(severity))
+                .add_modifier(Modifier::BOLD),
+        ));
+    }
+    for label in labels {
+        spans.push(Span::styled(
+            format!(" #{}", label),
+            Style::default().fg(styles::fg_path()).bg(bg_color),
+        ));
+    }
+    spans
+}
+
 /// Render a draft comment box (for comment input mode).
-fn render_draft_comment_box(
-    file_path: &str,
-    start_line: usize,
-    end_line: usize,
+pub fn render_draft_comment_box(
+    title: &str,
+    severity: Option<Severity>,
     body: &str,
     width: u16,
 ) -> Vec<Line<'static>> {
@@ -864,24 +932,26 @@ fn render_draft_comment_box(
     let mut lines = Vec::new();
 
     // Header
-    let line_range = if start_line == end_line {
-        format!("L{}", start_line + 1)
-    } else {
-        format!("L{}-L{}", start_line + 1, end_line + 1)
-    };
-    let header_text = format!(" New comment on {} ", line_range);
-    let header_fill_len = inner_w.saturating_sub(header_text.len() + 2);
-    let header_fill = "─".repeat(header_fill_len);
-
-    lines.push(Line::from(vec![
+    let header_text = format!(" {} ", title);
+    let mut header_spans = vec![
         Span::styled("  ┌─", Style::default().fg(border_color)),
         Span::styled(header_text, Style::default().fg(styles::fg_hunk())),
-        Span::styled(header_fill, Style::default().fg(border_color)),
-        Span::styled("┐", Style::default().fg(border_color)),
-    ]));
-
-    // File path (truncated)
-    let _file_display: String = file_path.chars().rev().take(inner_w.saturating_sub(2)).collect::<String>().chars().rev().collect();
+    ];
+    if let Some(severity) = severity {
+        header_spans.push(Span::styled(
+            format!(" {} ", severity.as_str()),
+            Style::default()
+                .fg(bg_color)
+                .bg(styles::fg_severity(severity))
+                .add_modifier(Modifier::BOLD),
+        ));
+        header_spans.push(Span::styled(" ", Style::default()));
+    }
+    let header_width: usize = header_spans.iter().map(|s| s.width()).sum();
+    let header_fill_len = (inner_w + 2).saturating_sub(header_width);
+    header_spans.push(Span::styled("─".repeat(header_fill_len), Style::default().fg(border_color)));
+    header_spans.push(Span::styled("┐", Style::default().fg(border_color)));
+    lines.push(Line::from(header_spans));
 
     // Empty line
     let empty_fill = " ".repeat(inner_w);
@@ -957,7 +1027,7 @@ fn render_draft_comment_box(
     ]));
 
     // Hints
-    let hints = " Enter submit │ Esc cancel";
+    let hints = " Enter submit │ Tab severity │ Esc cancel";
     let hints_pad = inner_w.saturating_sub(hints.len());
     lines.push(Line::from(vec![
         Span::styled("  │", Style::default().fg(border_color)),
@@ -977,33 +1047,61 @@ fn render_draft_comment_box(
     lines
 }
 
-/// Simple word wrapping for text.
-fn wrap_text(text: &str, max_width: usize) -> Vec<String> {
-    if max_width == 0 {
-        return vec![text.to_string()];
-    }
-
+/// Comment boxes rendered below a diff line: whole-file comments under the
+/// file header, line comments under the last line of their range, plus the
+/// draft being written if it is anchored here.
+#[allow(clippy::too_many_arguments)]
+fn inline_comment_lines(
+    diff: &Diff,
+    line: &DiffViewLine,
+    line_idx: usize,
+    comments: &[Comment],
+    focused_comment: Option<i64>,
+    draft_comment: Option<&DraftComment>,
+    reply_info: Option<(i64, &str)>, // (comment_id, input_text)
+    width: u16,
+) -> Vec<Line<'static>> {
     let mut lines = Vec::new();
-    let mut current_line = String::new();
-
-    for word in text.split_whitespace() {
-        if current_line.is_empty() {
-            current_line = word.to_string();
-        } else if current_line.len() + 1 + word.len() <= max_width {
-            current_line.push(' ');
-            current_line.push_str(word);
-        } else {
-            lines.push(current_line);
-            current_line = word.to_string();
-        }
-    }
+    let Some(path) = diff.files.get(line.file_index).map(|f| f.path.as_str()) else {
+        return lines;
+    };
 
-    if !current_line.is_empty() {
-        lines.push(current_line);
+    let anchored: Vec<&Comment> = match &line.content {
+        LineContent::FileHeaderTop { .. } => comments.iter().filter(|c| c.is_on_file(path)).collect(),
+        content => match content.new_line_num() {
+            Some(line_num) => comments
+                .iter()
+                .filter(|c| c.ends_on_line(path, line_num as usize))
+                .collect(),
+            None => Vec::new(),
+        },
+    };
+
+    for comment in anchored {
+        let is_focused = focused_comment == Some(comment.id);
+        // Check if we're replying to this specific comment
+        let this_reply_input = reply_info
+            .filter(|(reply_id, _)| *reply_id == comment.id)
+            .map(|(_, text)| text);
+        lines.extend(render_comment_box(comment, width, is_focused, this_reply_input));
     }
 
-    if lines.is_empty() {
-        lines.push(String::new());
+    // Render draft comment if it is anchored to this line
+    if let Some(draft) = draft_comment.filter(|d| d.file_path == path) {
+        let title = match draft.line_range {
+            Some((start, end)) if line_idx == end => Some(if start == end {
+                format!("New comment on L{}", start + 1)
+            } else {
+                format!("New comment on L{}-L{}", start + 1, end + 1)
+            }),
+            None if matches!(line.content, LineContent::FileHeaderTop { .. }) => {
+                Some("New comment on file".to_string())
+            }
+            _ => None,
+        };
+        if let Some(title) = title {
+            lines.extend(render_draft_comment_box(&title, draft.severity, &draft.body, width));
+        }
     }
 
     lines
@@ -1024,11 +1122,12 @@ pub fn render_unified(
     stale_viewed: &HashSet<usize>,
     diff_source: DiffSource,
     uncommitted_files: &HashSet<String>,
+    coverage: Option<&DiffCoverage>,
     comments: &[Comment],
     show_comments: bool,
     visual_selection: Option<(usize, usize)>,
     focused_comment: Option<i64>,
-    draft_comment: Option<&(String, usize, usize, String)>,
+    draft_comment: Option<&DraftComment>,
     reply_info: Option<(i64, &str)>, // (comment_id, input_text)
 ) {
     // Gutter width: always 2 chars for consistent layout
@@ -1054,7 +1153,7 @@ pub fn render_unified(
         let is_viewed = viewed.contains(&file_idx);
         let is_stale = stale_viewed.contains(&file_idx);
         // Use content_width (same as regular file headers) to ensure alignment
-        let mut sticky_line = render_sticky_header(&path, &stats, file_idx, current_file, collapsed, is_viewed, is_stale, content_width);
+        let mut sticky_line = render_sticky_header(&path, &stats, file_idx, current_file, collapsed, is_viewed, is_stale, file_coverage(coverage, file_idx), content_width);
 
         // Build full line: gutter (2 chars) + header content (same as regular lines)
         let mut spans = vec![Span::styled("  ", Style::default())];
@@ -1083,7 +1182,7 @@ pub fn render_unified(
             .map(|(start, end)| absolute_line_idx >= start && absolute_line_idx <= end)
             .unwrap_or(false);
 
-        let mut rendered = render_unified_line(line, current_file, collapsed, viewed, stale_viewed, content_width);
+        let mut rendered = render_unified_line(line, current_file, collapsed, viewed, stale_viewed, coverage, content_width);
 
         // Apply visual selection highlighting
         if is_selected {
@@ -1101,9 +1200,7 @@ pub fn render_unified(
             let source_line = line.content.new_line_num().map(|n| n as usize);
             let path = diff.files.get(line.file_index).map(|f| f.path.as_str());
             match (path, source_line) {
-                (Some(p), Some(ln)) => comments.iter().any(|c| {
-                    c.file_path == p && ln >= c.start_line && ln <= c.end_line
-                }),
+                (Some(p), Some(ln)) => comments.iter().any(|c| c.covers_line(p, ln)),
                 _ => false,
             }
         } else {
@@ -1123,67 +1220,40 @@ pub fn render_unified(
         };
 
         let gutter_char = if is_cursor_line {
-            "▶ " // Cursor line indicator (arrow)
+            "▶" // Cursor line indicator (arrow)
         } else if diff_source == DiffSource::All && is_uncommitted {
-            "▎ " // Orange bar for uncommitted
+            "▎" // Orange bar for uncommitted
         } else if is_selected {
-            "▌ " // Visual selection indicator
+            "▌" // Visual selection indicator
         } else if is_in_comment_range {
-            "┃ " // Comment range indicator (blue bar)
+            "┃" // Comment range indicator (blue bar)
         } else {
-            "  " // Empty gutter
+            " " // Empty gutter
         };
 
-        let mut spans = vec![Span::styled(gutter_char, gutter_style)];
+        let mut spans = vec![Span::styled(gutter_char, gutter_style), coverage_mark(coverage, line)];
         spans.append(&mut rendered.spans);
         visible_lines.push(Line::from(spans));
         rendered_count += 1;
 
-        // Render inline comments for this line if enabled
+        // Render inline comments (and the draft being written) below this line
         if show_comments {
-            let file_path = diff.files.get(line.file_index).map(|f| f.path.as_str());
-            let source_line_num = line.content.new_line_num().map(|n| n as usize);
-            if let (Some(path), Some(line_num)) = (file_path, source_line_num) {
-                for comment in comments.iter().filter(|c| {
-                    c.file_path == path && line_num >= c.start_line && line_num <= c.end_line
-                }) {
-                    // Only render comment after the last line of its range
-                    if line_num == comment.end_line {
-                        let is_focused = focused_comment == Some(comment.id);
-                        // Check if we're replying to this specific comment
-                        let this_reply_input = reply_info
-                            .filter(|(reply_id, _)| *reply_id == comment.id)
-                            .map(|(_, text)| text);
-                        let comment_lines = render_comment_box(comment, content_width + gutter_width, is_focused, this_reply_input);
-                        for comment_line in comment_lines {
-                            if rendered_count >= visible_height {
-                                break;
-                            }
-                            visible_lines.push(comment_line);
-                            rendered_count += 1;
-                        }
-                    }
-                }
-
-                // Render draft comment if this is the end line of the draft
-                if let Some((draft_path, draft_start, draft_end, draft_body)) = draft_comment {
-                    if draft_path == path && absolute_line_idx == *draft_end {
-                        let draft_lines = render_draft_comment_box(
-                            draft_path,
-                            *draft_start,
-                            *draft_end,
-                            draft_body,
-                            content_width + gutter_width,
-                        );
-                        for draft_line in draft_lines {
-                            if rendered_count >= visible_height {
-                                break;
-                            }
-                            visible_lines.push(draft_line);
-                            rendered_count += 1;
-                        }
-                    }
+            let comment_lines = inline_comment_lines(
+                diff,
+                line,
+                absolute_line_idx,
+                comments,
+                focused_comment,
+                draft_comment,
+                reply_info,
+                content_width + gutter_width,
+            );
+            for comment_line in comment_lines {
+                if rendered_count >= visible_height {
+                    break;
                 }
+                visible_lines.push(comment_line);
+                rendered_count += 1;
             }
         }
 
@@ -1214,11 +1284,12 @@ pub fn render_split(
     stale_viewed: &HashSet<usize>,
     diff_source: DiffSource,
     uncommitted_files: &HashSet<String>,
+    coverage: Option<&DiffCoverage>,
     comments: &[Comment],
     show_comments: bool,
     visual_selection: Option<(usize, usize)>,
     focused_comment: Option<i64>,
-    draft_comment: Option<&(String, usize, usize, String)>,
+    draft_comment: Option<&DraftComment>,
     reply_info: Option<(i64, &str)>, // (comment_id, input_text)
 ) {
     // Gutter width: always 2 chars for consistent layout
@@ -1242,7 +1313,7 @@ pub fn render_split(
     if let (Some(area), Some((path, stats, file_idx))) = (sticky_area, sticky_header) {
         let is_viewed = viewed.contains(&file_idx);
         let is_stale = stale_viewed.contains(&file_idx);
-        let sticky_line = render_sticky_header(&path, &stats, file_idx, current_file, collapsed, is_viewed, is_stale, area.width);
+        let sticky_line = render_sticky_header(&path, &stats, file_idx, current_file, collapsed, is_viewed, is_stale, file_coverage(coverage, file_idx), area.width);
         let sticky_para = Paragraph::new(vec![sticky_line]);
         frame.render_widget(sticky_para, area);
     }
@@ -1267,7 +1338,7 @@ pub fn render_split(
             .map(|(start, end)| absolute_line_idx >= start && absolute_line_idx <= end)
             .unwrap_or(false);
 
-        let mut rendered = render_split_line(line, current_file, collapsed, viewed, stale_viewed, half_width as usize, content_area.width.saturating_sub(gutter_width));
+        let mut rendered = render_split_line(line, current_file, collapsed, viewed, stale_viewed, coverage, half_width as usize, content_area.width.saturating_sub(gutter_width));
 
         // Apply visual selection highlighting
         if is_selected {
@@ -1285,9 +1356,7 @@ pub fn render_split(
             let source_line = line.content.new_line_num().map(|n| n as usize);
             let path = diff.files.get(line.file_index).map(|f| f.path.as_str());
             match (path, source_line) {
-                (Some(p), Some(ln)) => comments.iter().any(|c| {
-                    c.file_path == p && ln >= c.start_line && ln <= c.end_line
-                }),
+                (Some(p), Some(ln)) => comments.iter().any(|c| c.covers_line(p, ln)),
                 _ => false,
             }
         } else {
@@ -1307,67 +1376,40 @@ pub fn render_split(
         };
 
         let gutter_char = if is_cursor_line {
-            "▶ " // Cursor line indicator (arrow)
+            "▶" // Cursor line indicator (arrow)
         } else if diff_source == DiffSource::All && is_uncommitted {
-            "▎ " // Orange bar for uncommitted
+            "▎" // Orange bar for uncommitted
         } else if is_selected {
-            "▌ " // Visual selection indicator
+            "▌" // Visual selection indicator
         } else if is_in_comment_range {
-            "┃ " // Comment range indicator (blue bar)
+            "┃" // Comment range indicator (blue bar)
         } else {
-            "  " // Empty gutter
+            " " // Empty gutter
         };
 
-        let mut spans = vec![Span::styled(gutter_char, gutter_style)];
+        let mut spans = vec![Span::styled(gutter_char, gutter_style), coverage_mark(coverage, line)];
         spans.append(&mut rendered.spans);
         visible_lines.push(Line::from(spans));
         rendered_count += 1;
 
-        // Render inline comments for this line if enabled
+        // Render inline comments (and the draft being written) below this line
         if show_comments {
-            let file_path = diff.files.get(line.file_index).map(|f| f.path.as_str());
-            let source_line_num = line.content.new_line_num().map(|n| n as usize);
-            if let (Some(path), Some(line_num)) = (file_path, source_line_num) {
-                for comment in comments.iter().filter(|c| {
-                    c.file_path == path && line_num >= c.start_line && line_num <= c.end_line
-                }) {
-                    // Only render comment after the last line of its range
-                    if line_num == comment.end_line {
-                        let is_focused = focused_comment == Some(comment.id);
-                        // Check if we're replying to this specific comment
-                        let this_reply_input = reply_info
-                            .filter(|(reply_id, _)| *reply_id == comment.id)
-                            .map(|(_, text)| text);
-                        let comment_lines = render_comment_box(comment, content_area.width, is_focused, this_reply_input);
-                        for comment_line in comment_lines {
-                            if rendered_count >= visible_height {
-                                break;
-                            }
-                            visible_lines.push(comment_line);
-                            rendered_count += 1;
-                        }
-                    }
-                }
-
-                // Render draft comment if this is the end line of the draft
-                if let Some((draft_path, draft_start, draft_end, draft_body)) = draft_comment {
-                    if draft_path == path && absolute_line_idx == *draft_end {
-                        let draft_lines = render_draft_comment_box(
-                            draft_path,
-                            *draft_start,
-                            *draft_end,
-                            draft_body,
-                            content_area.width,
-                        );
-                        for draft_line in draft_lines {
-                            if rendered_count >= visible_height {
-                                break;
-                            }
-                            visible_lines.push(draft_line);
-                            rendered_count += 1;
-                        }
-                    }
+            let comment_lines = inline_comment_lines(
+                diff,
+                line,
+                absolute_line_idx,
+                comments,
+                focused_comment,
+                draft_comment,
+                reply_info,
+                content_area.width,
+            );
+            for comment_line in comment_lines {
+                if rendered_count >= visible_height {
+                    break;
                 }
+                visible_lines.push(comment_line);
+                rendered_count += 1;
             }
         }
 
@@ -1406,6 +1448,31 @@ fn find_sticky_header(lines: &[DiffViewLine], scroll: usize, _current_file: usiz
     None
 }
 
+/// Second gutter column: whether tests cover an added line, when a coverage
+/// report is loaded.
+fn coverage_mark(coverage: Option<&DiffCoverage>, line: &DiffViewLine) -> Span<'static> {
+    let line_coverage = coverage
+        .zip(line.content.new_line_num())
+        .and_then(|(coverage, num)| coverage.line(line.file_index, num as usize));
+    match line_coverage {
+        Some(c @ LineCoverage::NotInstrumented) => Span::styled("·", Style::default().fg(styles::fg_line_coverage(c))),
+        Some(c) => Span::styled("▐", Style::default().fg(styles::fg_line_coverage(c))),
+        None => Span::raw(" "),
+    }
+}
+
+fn file_coverage(coverage: Option<&DiffCoverage>, file_index: usize) -> Option<FileCoverage> {
+    coverage.and_then(|coverage| coverage.file(file_index))
+}
+
+/// "N% covered" for a file header, with its color.
+fn coverage_label(coverage: Option<FileCoverage>) -> (String, Color) {
+    match coverage {
+        Some(c) => (format!("{}% covered  ", c.percent()), styles::fg_file_coverage(c)),
+        None => (String::new(), styles::fg_muted()),
+    }
+}
+
 /// Render a sticky file header (matches render_file_header_top style).
 #[allow(clippy::too_many_arguments)]
 fn render_sticky_header(
@@ -1416,6 +1483,7 @@ fn render_sticky_header(
     collapsed: &HashSet<usize>,
     is_viewed: bool,
     is_stale: bool,
+    coverage: Option<FileCoverage>,
     width: u16,
 ) -> Line<'static> {
     let w = width as usize;
@@ -1425,6 +1493,7 @@ fn render_sticky_header(
     let toggle = if is_collapsed { "›" } else { "⌄" };
     let viewed_icon = if is_viewed && !is_stale { " ✓" } else { "" };
     let stale_indicator = if is_stale { " ● new" } else { "" };
+    let (coverage_str, coverage_color) = coverage_label(coverage);
     let border_color = if is_current { styles::fg_hunk() } else { styles::fg_border() };
     let path_color = if is_current { styles::fg_default() } else { styles::fg_path() };
 
@@ -1434,8 +1503,8 @@ fn render_sticky_header(
     // Calculate exact widths for alignment
     // Left: space + toggle + space + path
     let left_len = 1 + toggle.chars().count() + 1 + path.chars().count();
-    // Right: +N + 2 spaces + -M + viewed + stale + trailing space
-    let right_len = add_str.chars().count() + 2 + del_str.chars().count() + viewed_icon.chars().count() + stale_indicator.chars().count() + 1;
+    // Right: coverage + +N + 2 spaces + -M + viewed + stale + trailing space
+    let right_len = coverage_str.chars().count() + add_str.chars().count() + 2 + del_str.chars().count() + viewed_icon.chars().count() + stale_indicator.chars().count() + 1;
 
     // Content lines are w-1 wide, so header should also be w-1
     let inner_width = w.saturating_sub(3);
@@ -1448,6 +1517,7 @@ fn render_sticky_header(
             Style::default().fg(path_color).add_modifier(if is_current { Modifier::BOLD } else { Modifier::empty() }),
         ),
         Span::styled(" ".repeat(padding_len), Style::default()),
+        Span::styled(coverage_str, Style::default().fg(coverage_color)),
         Span::styled(add_str, Style::default().fg(styles::fg_addition())),
         Span::styled("  ", Style::default()),
         Span::styled(del_str, Style::default().fg(styles::fg_deletion())),
@@ -1465,6 +1535,7 @@ fn render_unified_line(
     collapsed: &HashSet<usize>,
     viewed: &HashSet<usize>,
     stale_viewed: &HashSet<usize>,
+    coverage: Option<&DiffCoverage>,
     width: u16,
 ) -> Line<'static> {
     let w = width as usize;
@@ -1475,7 +1546,7 @@ fn render_unified_line(
         LineContent::FileHeaderTop { path, stats } => {
             let is_viewed = viewed.contains(&line.file_index);
             let is_stale = stale_viewed.contains(&line.file_index);
-            render_file_header_top(path, stats, line.file_index, current_file, collapsed, is_viewed, is_stale, width)
+            render_file_header_top(path, stats, line.file_index, current_file, collapsed, is_viewed, is_stale, file_coverage(coverage, line.file_index), width)
         }
         LineContent::FileHeaderBottom => {
             render_file_header_bottom(line.file_index, current_file, width)
@@ -1592,6 +1663,7 @@ fn render_file_header_top(
     collapsed: &HashSet<usize>,
     is_viewed: bool,
     is_stale: bool,
+    coverage: Option<FileCoverage>,
     width: u16,
 ) -> Line<'static> {
     let w = width as usize;
@@ -1607,12 +1679,13 @@ fn render_file_header_top(
     let del_str = format!("-{}", stats.deletions);
     let viewed_icon = if is_viewed && !is_stale { " ✓" } else { "" };
     let stale_indicator = if is_stale { " ● new" } else { "" };
+    let (coverage_str, coverage_color) = coverage_label(coverage);
 
     // Calculate exact widths for alignment
     // Left: space + toggle + space + path
     let left_len = 1 + toggle.chars().count() + 1 + path.chars().count();
-    // Right: +N + 2 spaces + -M + viewed + stale + trailing space
-    let right_len = add_str.chars().count() + 2 + del_str.chars().count() + viewed_icon.chars().count() + stale_indicator.chars().count() + 1;
+    // Right: coverage + +N + 2 spaces + -M + viewed + stale + trailing space
+    let right_len = coverage_str.chars().count() + add_str.chars().count() + 2 + del_str.chars().count() + viewed_icon.chars().count() + stale_indicator.chars().count() + 1;
 
     // Content lines are w-1 wide, so header should also be w-1
     let inner_width = w.saturating_sub(3);
@@ -1625,6 +1698,7 @@ fn render_file_header_top(
             Style::default().fg(path_color).add_modifier(if is_current { Modifier::BOLD } else { Modifier::empty() }),
         ),
         Span::styled(" ".repeat(padding_len), Style::default()),
+        Span::styled(coverage_str, Style::default().fg(coverage_color)),
         Span::styled(add_str, Style::default().fg(styles::fg_addition())),
         Span::styled("  ", Style::default()),
         Span::styled(del_str, Style::default().fg(styles::fg_deletion())),
@@ -1652,12 +1726,14 @@ fn render_file_header_bottom(file_index: usize, current_file: usize, width: u16)
 }
 
 #[inline]
+#[allow(clippy::too_many_arguments)]
 fn render_split_line(
     line: &DiffViewLine,
     current_file: usize,
     collapsed: &HashSet<usize>,
     viewed: &HashSet<usize>,
     stale_viewed: &HashSet<usize>,
+    coverage: Option<&DiffCoverage>,
     half_width: usize,
     full_width: u16,
 ) -> Line<'static> {
@@ -1669,7 +1745,7 @@ fn render_split_line(
         LineContent::FileHeaderTop { path, stats } => {
             let is_viewed = viewed.contains(&line.file_index);
             let is_stale = stale_viewed.contains(&line.file_index);
-            render_file_header_top(path, stats, line.file_index, current_file, collapsed, is_viewed, is_stale, full_width)
+            render_file_header_top(path, stats, line.file_index, current_file, collapsed, is_viewed, is_stale, file_coverage(coverage, line.file_index), full_width)
         }
         LineContent::FileHeaderBottom => {
             render_file_header_bottom(line.file_index, current_file, full_width)
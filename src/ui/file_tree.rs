@@ -2,6 +2,7 @@
 
 #![allow(dead_code)]
 
+use crate::domain::coverage::DiffCoverage;
 use crate::domain::{Diff, DiffStats};
 use crate::ui::styles;
 use ratatui::{
@@ -316,6 +317,7 @@ pub fn render(
     selected: usize,
     current_file: usize,
     viewed: &std::collections::HashSet<usize>,
+    coverage: Option<&DiffCoverage>,
     filter: &str,
     filter_focused: bool,
     list_state: &mut ListState,
@@ -441,6 +443,14 @@ pub fn render(
                         Style::default().fg(styles::fg_muted()),
                     ));
                 }
+
+                // Share of added lines covered by tests
+                if let Some(file_coverage) = coverage.zip(item.file_index).and_then(|(c, idx)| c.file(idx)) {
+                    spans.push(Span::styled(
+                        format!(" {}%", file_coverage.percent()),
+                        Style::default().fg(styles::fg_file_coverage(file_coverage)),
+                    ));
+                }
             }
 
             let style = if is_selected {
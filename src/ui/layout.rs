@@ -1,9 +1,10 @@
 //! Main layout orchestrating file tree and diff view.
 
-use crate::app::{DiffSource, Focus, ViewMode};
-use crate::domain::{Comment, Diff};
+use crate::app::{CommentFilter, DiffSource, Focus, ViewMode};
+use crate::domain::coverage::DiffCoverage;
+use crate::domain::{Comment, Diff, ReviewVerdict, Severity};
 use crate::keymap::Keymap;
-use crate::ui::{diff_view, file_tree, styles};
+use crate::ui::{comments_panel, diff_view, file_tree, styles};
 use ratatui::{
     layout::{Constraint, Layout, Rect},
     style::{Modifier, Style},
@@ -38,15 +39,21 @@ pub fn render_main(
     has_pending_changes: bool,
     diff_source: DiffSource,
     uncommitted_files: &HashSet<String>,
+    coverage: Option<&DiffCoverage>,
     comments: &[Comment],
-    show_comments: bool,
+    comment_filter: CommentFilter,
     visual_selection: Option<(usize, usize)>,
     focused_comment: Option<i64>,
-    draft_comment: Option<&(String, usize, usize, String)>, // (file_path, start, end, body)
+    draft_comment: Option<&diff_view::DraftComment>,
     reply_info: Option<(i64, &str)>, // (comment_id, input_text) for reply input
+    pending_review_drafts: Option<usize>, // draft count while a review is pending
+    comments_panel: Option<comments_panel::PanelView<'_>>, // None when the panel is closed
+    status_message: Option<&str>,
     focus: Focus,
     mode: ViewMode,
 ) {
+    let show_comments = comment_filter != CommentFilter::Hidden;
+
     // Fill the full background so theme colors apply consistently.
     frame.render_widget(
         Block::default().style(Style::default().bg(styles::bg_default())),
@@ -63,7 +70,7 @@ pub fn render_main(
         .split(area);
 
     // Render full-width header
-    render_global_header(frame, vertical_chunks[0], diff, branch, base, current_file_index, viewed, sidebar_collapsed, has_pending_changes, diff_source);
+    render_global_header(frame, vertical_chunks[0], diff, branch, base, current_file_index, viewed, sidebar_collapsed, has_pending_changes, diff_source, pending_review_drafts);
 
     if sidebar_collapsed {
         // Full-width diff view when sidebar is collapsed
@@ -96,6 +103,7 @@ pub fn render_main(
                     stale_viewed,
                     diff_source,
                     uncommitted_files,
+                    coverage,
                     comments,
                     show_comments,
                     visual_selection,
@@ -118,6 +126,7 @@ pub fn render_main(
                     stale_viewed,
                     diff_source,
                     uncommitted_files,
+                    coverage,
                     comments,
                     show_comments,
                     visual_selection,
@@ -129,7 +138,7 @@ pub fn render_main(
         }
 
         // Render diff hints bar at bottom
-        render_diff_hints(frame, diff_chunks[1], true, view_mode, show_comments, mode, visual_selection);
+        render_diff_hints(frame, diff_chunks[1], true, view_mode, comment_filter, mode, visual_selection, status_message);
     } else {
         // Split main area into sidebar and content
         let sidebar_width = 40.min(vertical_chunks[1].width / 3);
@@ -139,20 +148,36 @@ pub fn render_main(
             .constraints([Constraint::Length(sidebar_width), Constraint::Min(1)])
             .split(vertical_chunks[1]);
 
+        // Comments panel shares the sidebar with the file tree
+        let (tree_area, panel_area) = if comments_panel.is_some() {
+            let sidebar_chunks = Layout::default()
+                .direction(ratatui::layout::Direction::Vertical)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(horizontal_chunks[0]);
+            (sidebar_chunks[0], Some(sidebar_chunks[1]))
+        } else {
+            (horizontal_chunks[0], None)
+        };
+
         // Render file tree sidebar
         file_tree::render(
             frame,
-            horizontal_chunks[0],
+            tree_area,
             flat_items,
             selected_tree_item,
             current_file_index,
             viewed,
+            coverage,
             filter,
             filter_focused,
             tree_state,
             focus == Focus::FileTree,
         );
 
+        if let (Some(panel), Some(panel_area)) = (comments_panel, panel_area) {
+            comments_panel::render(frame, panel_area, panel, focus == Focus::CommentsPanel);
+        }
+
         // Split diff area to include panel bottom bar
         let diff_chunks = Layout::default()
             .direction(ratatui::layout::Direction::Vertical)
@@ -183,6 +208,7 @@ pub fn render_main(
                     stale_viewed,
                     diff_source,
                     uncommitted_files,
+                    coverage,
                     comments,
                     show_comments,
                     visual_selection,
@@ -205,6 +231,7 @@ pub fn render_main(
                     stale_viewed,
                     diff_source,
                     uncommitted_files,
+                    coverage,
                     comments,
                     show_comments,
                     visual_selection,
@@ -216,7 +243,16 @@ pub fn render_main(
         }
 
         // Render diff hints bar at bottom
-        render_diff_hints(frame, diff_chunks[1], focus == Focus::DiffView, view_mode, show_comments, mode, visual_selection);
+        render_diff_hints(
+            frame,
+            diff_chunks[1],
+            focus == Focus::DiffView,
+            view_mode,
+            comment_filter,
+            mode,
+            visual_selection,
+            status_message,
+        );
     }
 }
 
@@ -227,9 +263,10 @@ fn render_diff_hints(
     area: Rect,
     focused: bool,
     view_mode: diff_view::DiffViewMode,
-    show_comments: bool,
+    comment_filter: CommentFilter,
     mode: ViewMode,
     visual_selection: Option<(usize, usize)>,
+    status_message: Option<&str>,
 ) {
     let border_color = if focused {
         styles::fg_hunk()
@@ -248,7 +285,8 @@ fn render_diff_hints(
     let split_active = view_mode == diff_view::DiffViewMode::Split;
     let split_fg = if split_active { styles::fg_default() } else { styles::fg_muted() };
 
-    // Comments toggle
+    // Comments toggle (label reflects the active filter)
+    let show_comments = comment_filter != CommentFilter::Hidden;
     let comments_fg = if show_comments { styles::fg_default() } else { styles::fg_muted() };
 
     // Build left spans
@@ -260,12 +298,15 @@ fn render_diff_hints(
         Span::styled("s", Style::default().fg(styles::fg_border())),
         Span::styled(" │ ", Style::default().fg(styles::fg_border())),
         Span::styled("◇", Style::default().fg(comments_fg)),
-        Span::styled(" Comments ", Style::default().fg(comments_fg)),
+        Span::styled(format!(" {} ", comment_filter.label()), Style::default().fg(comments_fg)),
         Span::styled("C", Style::default().fg(styles::fg_border())),
     ];
 
-    // Show visual mode indicator if active
-    if mode == ViewMode::Visual || mode == ViewMode::CommentInput {
+    // An error from the last action takes the place of the hints
+    if let Some(message) = status_message {
+        left_spans.push(Span::styled(" │ ", Style::default().fg(styles::fg_border())));
+        left_spans.push(Span::styled(format!("✗ {}", message), Style::default().fg(styles::fg_deletion())));
+    } else if mode == ViewMode::Visual || mode == ViewMode::CommentInput {
         let selection_info = visual_selection
             .map(|(start, end)| {
                 if start == end {
@@ -341,6 +382,7 @@ fn render_global_header(
     _sidebar_collapsed: bool,
     has_pending_changes: bool,
     diff_source: DiffSource,
+    pending_review_drafts: Option<usize>,
 ) {
     let stats = diff.total_stats();
     let file_count = diff.file_count();
@@ -370,6 +412,18 @@ fn render_global_header(
         left_spans.push(Span::styled(" changed", Style::default().fg(styles::fg_warning())));
     }
 
+    // Show pending review indicator with its draft count
+    if let Some(drafts) = pending_review_drafts {
+        let noun = if drafts == 1 { "draft" } else { "drafts" };
+        left_spans.push(Span::styled("  ", Style::default()));
+        left_spans.push(Span::styled("◆", Style::default().fg(styles::fg_hunk())));
+        left_spans.push(Span::styled(
+            format!(" Review · {} {} ", drafts, noun),
+            Style::default().fg(styles::fg_hunk()),
+        ));
+        left_spans.push(Span::styled("S", Style::default().fg(styles::fg_border())));
+    }
+
     // Right side: diff source toggle
     let mut right_spans = Vec::new();
 
@@ -540,6 +594,151 @@ pub fn render_theme_picker(
     frame.render_widget(hint, hint_area);
 }
 
+/// Render the branch conversation panel overlay.
+pub fn render_conversation(
+    frame: &mut Frame,
+    area: Rect,
+    comments: &[Comment],
+    focused_comment: Option<i64>,
+    draft: Option<(&str, Option<Severity>)>, // (body, severity)
+    reply_info: Option<(i64, &str)>, // (comment_id, input_text)
+) {
+    let popup_area = centered_rect(70, 80, area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Conversation ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(styles::fg_muted()))
+        .style(Style::default().bg(styles::bg_default()));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints([Constraint::Min(1), Constraint::Length(1)])
+        .split(inner);
+    let body_area = chunks[0];
+
+    // Render every thread, remembering where the focused one starts
+    let mut lines: Vec<Line> = vec![Line::from("")];
+    let mut focus_start = None;
+    for comment in comments.iter().filter(|c| c.file_path.is_none()) {
+        let is_focused = focused_comment == Some(comment.id);
+        if is_focused {
+            focus_start = Some(lines.len());
+        }
+        let this_reply_input = reply_info
+            .filter(|(reply_id, _)| *reply_id == comment.id)
+            .map(|(_, text)| text);
+        lines.extend(diff_view::render_comment_box(comment, body_area.width, is_focused, this_reply_input));
+        lines.push(Line::from(""));
+    }
+
+    if let Some((body, severity)) = draft {
+        focus_start = Some(lines.len());
+        lines.extend(diff_view::render_draft_comment_box(
+            "New conversation comment",
+            severity,
+            body,
+            body_area.width,
+        ));
+    } else if lines.len() == 1 {
+        lines.push(Line::from(Span::styled(
+            "  No conversation yet. Press c to start one.",
+            styles::style_muted(),
+        )));
+    }
+
+    // Scroll so the focused thread (or the draft) is visible
+    let height = body_area.height as usize;
+    let scroll = match focus_start {
+        Some(start) if lines.len() > height => start.saturating_sub(1).min(lines.len() - height),
+        _ => 0,
+    };
+    let visible: Vec<Line> = lines.into_iter().skip(scroll).take(height).collect();
+    frame.render_widget(Paragraph::new(visible), body_area);
+
+    let hint = Paragraph::new(Line::from(Span::styled(
+        " c comment │ r reply │ R resolve │ D delete │ j/k select │ Esc close",
+        styles::style_muted(),
+    )));
+    frame.render_widget(hint, chunks[1]);
+}
+
+/// Render the submit review dialog overlay.
+pub fn render_review_dialog(
+    frame: &mut Frame,
+    area: Rect,
+    input: &str,
+    verdict: ReviewVerdict,
+    draft_count: usize,
+) {
+    let popup_area = centered_rect(50, 40, area);
+    frame.render_widget(Clear, popup_area);
+
+    let block = Block::default()
+        .title(" Submit review ")
+        .borders(Borders::ALL)
+        .border_style(Style::default().fg(styles::fg_muted()))
+        .padding(Padding::horizontal(1))
+        .style(Style::default().bg(styles::bg_sidebar()));
+    let inner = block.inner(popup_area);
+    frame.render_widget(block, popup_area);
+
+    let chunks = Layout::default()
+        .direction(ratatui::layout::Direction::Vertical)
+        .constraints([
+            Constraint::Length(2), // Draft count
+            Constraint::Min(1),    // Summary input
+            Constraint::Length(2), // Verdict selector
+            Constraint::Length(1), // Hints
+        ])
+        .split(inner);
+
+    let noun = if draft_count == 1 { "comment" } else { "comments" };
+    let summary = Paragraph::new(Line::from(Span::styled(
+        format!("{} draft {} will be published", draft_count, noun),
+        styles::style_muted(),
+    )));
+    frame.render_widget(summary, chunks[0]);
+
+    let input_line = Line::from(vec![
+        Span::styled(input, Style::default().fg(styles::fg_default())),
+        Span::styled("▏", Style::default().fg(styles::fg_hunk())),
+    ]);
+    let input_widget = Paragraph::new(input_line)
+        .wrap(ratatui::widgets::Wrap { trim: false })
+        .block(
+            Block::default()
+                .title(" Summary ")
+                .borders(Borders::ALL)
+                .border_style(Style::default().fg(styles::fg_border())),
+        );
+    frame.render_widget(input_widget, chunks[1]);
+
+    let mut verdict_spans = vec![Span::raw(" ")];
+    for (i, option) in ReviewVerdict::ALL.into_iter().enumerate() {
+        if i > 0 {
+            verdict_spans.push(Span::styled(" │ ", Style::default().fg(styles::fg_border())));
+        }
+        let style = if option == verdict {
+            Style::default().fg(styles::fg_default()).add_modifier(Modifier::BOLD)
+        } else {
+            Style::default().fg(styles::fg_muted())
+        };
+        let marker = if option == verdict { "● " } else { "○ " };
+        verdict_spans.push(Span::styled(format!("{}{}", marker, option.label()), style));
+    }
+    frame.render_widget(Paragraph::new(vec![Line::from(""), Line::from(verdict_spans)]), chunks[2]);
+
+    let hint = Paragraph::new(Line::from(Span::styled(
+        " Tab verdict │ Enter submit │ Ctrl+d discard │ Esc cancel",
+        styles::style_muted(),
+    )));
+    frame.render_widget(hint, chunks[3]);
+}
+
 /// Render the fuzzy search as a bottom drawer above the diff bottom bar.
 pub fn render_fuzzy_search(
     frame: &mut Frame,
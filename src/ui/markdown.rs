@@ -0,0 +1,560 @@
+//! Markdown rendering for comment bodies.
+//!
+//! Supports the subset that shows up in review comments: headings, emphasis,
+//! inline code, links, bulleted/numbered lists, block quotes and fenced code
+//! blocks. Output lines are pre-wrapped to a display width (via unicode-width)
+//! so callers can pad them into bordered boxes.
+
+use crate::ui::{styles, syntax};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+/// A block-level Markdown element.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Block {
+    Paragraph(String),
+    Heading(String),
+    Quote(String),
+    ListItem {
+        depth: usize,
+        marker: String,
+        text: String,
+    },
+    Code {
+        lang: String,
+        lines: Vec<String>,
+    },
+    Rule,
+    Blank,
+}
+
+/// Render Markdown text into lines no wider than `width` display columns.
+/// `base` is the style for plain text (including the background to fill with).
+pub fn render(text: &str, width: usize, base: Style) -> Vec<Line<'static>> {
+    let width = width.max(1);
+    let mut lines: Vec<Line<'static>> = Vec::new();
+
+    for block in parse_blocks(text) {
+        match block {
+            Block::Paragraph(text) => {
+                lines.extend(wrap_spans(parse_inline(&text, base), width, base));
+            }
+            Block::Heading(text) => {
+                let style = base.fg(styles::fg_hunk()).add_modifier(Modifier::BOLD);
+                lines.extend(wrap_spans(parse_inline(&text, style), width, base));
+            }
+            Block::Quote(text) => {
+                let style = base.fg(styles::fg_muted()).add_modifier(Modifier::ITALIC);
+                let bar = base.fg(styles::fg_border());
+                for mut line in wrap_spans(parse_inline(&text, style), width.saturating_sub(2), base) {
+                    line.spans.insert(0, Span::styled("▎ ", bar));
+                    lines.push(line);
+                }
+            }
+            Block::ListItem { depth, marker, text } => {
+                let first = format!("{}{} ", "  ".repeat(depth), marker);
+                let rest = " ".repeat(first.width());
+                let marker_style = base.fg(styles::fg_hunk());
+                let content_width = width.saturating_sub(first.width());
+                let wrapped = wrap_spans(parse_inline(&text, base), content_width, base);
+                for (i, mut line) in wrapped.into_iter().enumerate() {
+                    let prefix = if i == 0 { first.clone() } else { rest.clone() };
+                    line.spans.insert(0, Span::styled(prefix, marker_style));
+                    lines.push(line);
+                }
+            }
+            Block::Code { lang, lines: code } => {
+                let ext = if lang.is_empty() { "txt" } else { lang.as_str() };
+                let code_bg = base.bg(styles::bg_hunk_header());
+                for code_line in code {
+                    lines.push(render_code_line(&code_line, ext, width, code_bg));
+                }
+            }
+            Block::Rule => {
+                lines.push(Line::from(Span::styled("─".repeat(width), base.fg(styles::fg_border()))));
+            }
+            Block::Blank => {
+                if !lines.is_empty() {
+                    lines.push(Line::default());
+                }
+            }
+        }
+    }
+
+    // Drop trailing blank lines
+    while lines.last().is_some_and(|l| l.width() == 0) {
+        lines.pop();
+    }
+    if lines.is_empty() {
+        lines.push(Line::default());
+    }
+
+    lines
+}
+
+/// Split text into block-level elements.
+fn parse_blocks(text: &str) -> Vec<Block> {
+    let mut blocks: Vec<Block> = Vec::new();
+    let mut source = text.lines();
+
+    while let Some(line) = source.next() {
+        let trimmed = line.trim_start();
+
+        // Fenced code block
+        if let Some(fence) = ["```", "~~~"].into_iter().find(|f| trimmed.starts_with(f)) {
+            let lang = trimmed[fence.len()..]
+                .split_whitespace()
+                .next()
+                .unwrap_or("")
+                .to_string();
+            let mut code = Vec::new();
+            for code_line in source.by_ref() {
+                if code_line.trim_start().starts_with(fence) {
+                    break;
+                }
+                code.push(code_line.replace('\t', "    "));
+            }
+            blocks.push(Block::Code { lang, lines: code });
+            continue;
+        }
+
+        if trimmed.is_empty() {
+            if !matches!(blocks.last(), None | Some(Block::Blank)) {
+                blocks.push(Block::Blank);
+            }
+            continue;
+        }
+
+        if is_rule(trimmed) {
+            blocks.push(Block::Rule);
+            continue;
+        }
+
+        if let Some(heading) = parse_heading(trimmed) {
+            blocks.push(Block::Heading(heading.to_string()));
+            continue;
+        }
+
+        if let Some(quoted) = trimmed.strip_prefix('>') {
+            let quoted = quoted.strip_prefix(' ').unwrap_or(quoted);
+            if let Some(Block::Quote(text)) = blocks.last_mut() {
+                text.push(' ');
+                text.push_str(quoted);
+            } else {
+                blocks.push(Block::Quote(quoted.to_string()));
+            }
+            continue;
+        }
+
+        if let Some((marker, text)) = parse_list_marker(trimmed) {
+            let indent = line.len() - trimmed.len();
+            blocks.push(Block::ListItem {
+                depth: indent / 2,
+                marker,
+                text: text.to_string(),
+            });
+            continue;
+        }
+
+        // Continuation of the previous paragraph, list item or quote
+        match blocks.last_mut() {
+            Some(Block::Paragraph(text))
+            | Some(Block::Quote(text))
+            | Some(Block::ListItem { text, .. }) => {
+                text.push(' ');
+                text.push_str(trimmed);
+            }
+            _ => blocks.push(Block::Paragraph(trimmed.to_string())),
+        }
+    }
+
+    blocks
+}
+
+fn is_rule(line: &str) -> bool {
+    let compact: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+    compact.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|&c| compact.chars().all(|x| x == c))
+}
+
+fn parse_heading(line: &str) -> Option<&str> {
+    let level = line.chars().take_while(|&c| c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+    let rest = &line[level..];
+    if rest.is_empty() {
+        return Some("");
+    }
+    rest.strip_prefix(' ').map(|r| r.trim().trim_end_matches('#').trim_end())
+}
+
+/// Parse a list marker, returning the display marker and the item text.
+fn parse_list_marker(line: &str) -> Option<(String, &str)> {
+    for bullet in ["- ", "* ", "+ "] {
+        if let Some(text) = line.strip_prefix(bullet) {
+            return Some(("•".to_string(), text.trim_start()));
+        }
+    }
+
+    let digits = line.chars().take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 || digits > 9 {
+        return None;
+    }
+    let rest = &line[digits..];
+    let text = rest.strip_prefix(". ").or_else(|| rest.strip_prefix(") "))?;
+    Some((format!("{}.", &line[..digits]), text.trim_start()))
+}
+
+/// Parse inline markup into styled spans.
+fn parse_inline(text: &str, style: Style) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    parse_inline_into(text, style, &mut spans);
+    spans
+}
+
+fn parse_inline_into(text: &str, style: Style, spans: &mut Vec<Span<'static>>) {
+    let mut buf = String::new();
+    let mut prev: Option<char> = None;
+    let mut rest = text;
+
+    let flush = |buf: &mut String, spans: &mut Vec<Span<'static>>| {
+        if !buf.is_empty() {
+            spans.push(Span::styled(std::mem::take(buf), style));
+        }
+    };
+
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '\\' => {
+                let mut chars = rest.chars();
+                chars.next();
+                if let Some(next) = chars.next().filter(|n| n.is_ascii_punctuation()) {
+                    buf.push(next);
+                    prev = Some(next);
+                    rest = &rest[1 + next.len_utf8()..];
+                    continue;
+                }
+            }
+            '`' => {
+                if let Some(end) = rest[1..].find('`') {
+                    flush(&mut buf, spans);
+                    let code_style = style
+                        .fg(styles::fg_hunk())
+                        .bg(styles::bg_hunk_header())
+                        .remove_modifier(Modifier::ITALIC);
+                    spans.push(Span::styled(rest[1..1 + end].to_string(), code_style));
+                    prev = Some('`');
+                    rest = &rest[end + 2..];
+                    continue;
+                }
+            }
+            '*' | '_' => {
+                let double = rest.starts_with("**") || rest.starts_with("__");
+                let delim = if double { &rest[..2] } else { &rest[..1] };
+                let after = &rest[delim.len()..];
+                let opens = after.chars().next().is_some_and(|n| !n.is_whitespace())
+                    && (c == '*' || !prev.is_some_and(|p| p.is_alphanumeric()));
+                if opens {
+                    if let Some(end) = find_closing(after, delim) {
+                        flush(&mut buf, spans);
+                        let modifier = if double { Modifier::BOLD } else { Modifier::ITALIC };
+                        parse_inline_into(&after[..end], style.add_modifier(modifier), spans);
+                        prev = Some(c);
+                        rest = &after[end + delim.len()..];
+                        continue;
+                    }
+                }
+            }
+            '[' => {
+                if let Some((label, url, consumed)) = parse_link(rest) {
+                    flush(&mut buf, spans);
+                    let link_style = style.fg(styles::fg_path()).add_modifier(Modifier::UNDERLINED);
+                    parse_inline_into(label, link_style, spans);
+                    if !url.is_empty() && url != label {
+                        spans.push(Span::styled(format!(" ({})", url), style.fg(styles::fg_muted())));
+                    }
+                    prev = Some(')');
+                    rest = &rest[consumed..];
+                    continue;
+                }
+            }
+            '<' => {
+                if let Some(end) = rest.find('>') {
+                    let inner = &rest[1..end];
+                    if inner.starts_with("http://") || inner.starts_with("https://") {
+                        flush(&mut buf, spans);
+                        let link_style = style.fg(styles::fg_path()).add_modifier(Modifier::UNDERLINED);
+                        spans.push(Span::styled(inner.to_string(), link_style));
+                        prev = Some('>');
+                        rest = &rest[end + 1..];
+                        continue;
+                    }
+                }
+            }
+            _ => {}
+        }
+
+        buf.push(c);
+        prev = Some(c);
+        rest = &rest[c.len_utf8()..];
+    }
+
+    flush(&mut buf, spans);
+}
+
+/// Find the byte offset of a closing emphasis delimiter.
+fn find_closing(text: &str, delim: &str) -> Option<usize> {
+    let underscore = delim.starts_with('_');
+    text.match_indices(delim).map(|(i, _)| i).find(|&i| {
+        if i == 0 {
+            return false;
+        }
+        let before = text[..i].chars().next_back();
+        let after = text[i + delim.len()..].chars().next();
+        // Skip the first half of a longer run (e.g. `*` inside `**`)
+        let part_of_longer = delim.len() == 1 && after == delim.chars().next();
+        let intraword = underscore && after.is_some_and(|a| a.is_alphanumeric());
+        !(before.is_some_and(char::is_whitespace) || part_of_longer || intraword)
+    })
+}
+
+/// Parse `[label](url)`, returning (label, url, bytes consumed).
+fn parse_link(text: &str) -> Option<(&str, &str, usize)> {
+    let close = text.find("](")?;
+    let label = &text[1..close];
+    if label.contains(']') || label.is_empty() {
+        return None;
+    }
+    let url_start = close + 2;
+    let url_len = text[url_start..].find(')')?;
+    let url = text[url_start..url_start + url_len].trim();
+    Some((label, url, url_start + url_len + 1))
+}
+
+/// Render one line of a fenced code block: syntax-highlighted, truncated, and
+/// padded so the block reads as a shaded rectangle.
+fn render_code_line(code: &str, ext: &str, width: usize, code_bg: Style) -> Line<'static> {
+    let mut spans = vec![Span::styled(" ", code_bg)];
+    let mut used = 1;
+    let limit = width.saturating_sub(1);
+
+    for span in syntax::highlight_line(code, ext) {
+        if used >= limit {
+            break;
+        }
+        let mut style = code_bg;
+        if let Some(fg) = span.style.fg {
+            style = style.fg(fg);
+        }
+        style = style.add_modifier(span.style.add_modifier);
+
+        let text = take_width(&span.content, limit - used);
+        used += text.width();
+        spans.push(Span::styled(text, style));
+    }
+
+    if used < width {
+        spans.push(Span::styled(" ".repeat(width - used), code_bg));
+    }
+    Line::from(spans)
+}
+
+/// Take the longest prefix of `text` that fits in `max` display columns.
+fn take_width(text: &str, max: usize) -> String {
+    let mut out = String::new();
+    let mut used = 0;
+    for c in text.chars() {
+        let w = c.width().unwrap_or(0);
+        if used + w > max {
+            break;
+        }
+        out.push(c);
+        used += w;
+    }
+    out
+}
+
+/// A word made of one or more styled pieces (e.g. `**bold**,` is two pieces).
+struct Word {
+    pieces: Vec<(String, Style)>,
+    width: usize,
+}
+
+/// Greedy word-wrap styled spans to `width` display columns.
+/// Runs of whitespace collapse to a single space; over-long words are split.
+fn wrap_spans(spans: Vec<Span<'static>>, width: usize, base: Style) -> Vec<Line<'static>> {
+    let width = width.max(1);
+
+    // Split spans into words, tracking the style of the preceding space
+    let mut words: Vec<(Option<Style>, Word)> = Vec::new();
+    let mut pending_space: Option<Style> = None;
+    let mut current: Option<Word> = None;
+
+    for span in &spans {
+        let style = span.style;
+        let mut piece = String::new();
+        for c in span.content.chars() {
+            if c.is_whitespace() {
+                if !piece.is_empty() {
+                    let word = current.get_or_insert_with(|| Word { pieces: Vec::new(), width: 0 });
+                    word.width += piece.width();
+                    word.pieces.push((std::mem::take(&mut piece), style));
+                }
+                if let Some(word) = current.take() {
+                    words.push((pending_space.take(), word));
+                }
+                if !words.is_empty() {
+                    pending_space.get_or_insert(style);
+                }
+            } else {
+                piece.push(c);
+            }
+        }
+        if !piece.is_empty() {
+            let word = current.get_or_insert_with(|| Word { pieces: Vec::new(), width: 0 });
+            word.width += piece.width();
+            word.pieces.push((piece, style));
+        }
+    }
+    if let Some(word) = current.take() {
+        words.push((pending_space.take(), word));
+    }
+
+    let mut lines: Vec<Line<'static>> = Vec::new();
+    let mut line: Vec<Span<'static>> = Vec::new();
+    let mut line_width = 0;
+    let mut line_has_words = false;
+
+    for (space_style, word) in words {
+        let space_width = usize::from(line_has_words);
+        if line_has_words && line_width + space_width + word.width > width {
+            lines.push(Line::from(std::mem::take(&mut line)));
+            line_width = 0;
+            line_has_words = false;
+        }
+
+        if line_has_words {
+            line.push(Span::styled(" ", space_style.unwrap_or(base)));
+            line_width += 1;
+        }
+
+        if line_width + word.width <= width {
+            for (text, style) in word.pieces {
+                line.push(Span::styled(text, style));
+            }
+            line_width += word.width;
+        } else {
+            // Hard-split a word that doesn't fit on an empty line
+            for (text, style) in word.pieces {
+                let mut chunk = String::new();
+                for c in text.chars() {
+                    let w = c.width().unwrap_or(0);
+                    if line_width + w > width && line_width > 0 {
+                        if !chunk.is_empty() {
+                            line.push(Span::styled(std::mem::take(&mut chunk), style));
+                        }
+                        lines.push(Line::from(std::mem::take(&mut line)));
+                        line_width = 0;
+                    }
+                    chunk.push(c);
+                    line_width += w;
+                }
+                if !chunk.is_empty() {
+                    line.push(Span::styled(chunk, style));
+                }
+            }
+        }
+        line_has_words = true;
+    }
+
+    if line_has_words || lines.is_empty() {
+        lines.push(Line::from(line));
+    }
+
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: &[Line]) -> Vec<String> {
+        lines
+            .iter()
+            .map(|l| l.spans.iter().map(|s| s.content.as_ref()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn wraps_paragraph_to_width() {
+        let lines = render("one two three four five", 9, Style::default());
+        assert_eq!(plain(&lines), vec!["one two", "three", "four five"]);
+        assert!(lines.iter().all(|l| l.width() <= 9));
+    }
+
+    #[test]
+    fn wraps_by_display_width() {
+        let lines = render("日本語 日本語", 8, Style::default());
+        assert_eq!(plain(&lines), vec!["日本語", "日本語"]);
+    }
+
+    #[test]
+    fn emphasis_and_code_are_styled() {
+        let lines = render("a **bold** and *it* `x_y`", 80, Style::default());
+        let spans = &lines[0].spans;
+        let bold = spans.iter().find(|s| s.content == "bold").unwrap();
+        assert!(bold.style.add_modifier.contains(Modifier::BOLD));
+        let italic = spans.iter().find(|s| s.content == "it").unwrap();
+        assert!(italic.style.add_modifier.contains(Modifier::ITALIC));
+        assert!(spans.iter().any(|s| s.content == "x_y"));
+    }
+
+    #[test]
+    fn snake_case_is_not_emphasis() {
+        let lines = render("use snake_case_name here", 80, Style::default());
+        assert_eq!(plain(&lines), vec!["use snake_case_name here"]);
+    }
+
+    #[test]
+    fn lists_use_hanging_indent() {
+        let lines = render("- first item wraps\n2. second", 12, Style::default());
+        assert_eq!(plain(&lines), vec!["• first item", "  wraps", "2. second"]);
+    }
+
+    #[test]
+    fn fenced_code_is_not_wrapped() {
+        let text = "before\n```rust\nlet x = 1;\n```\nafter";
+        let lines = render(text, 20, Style::default());
+        let text = plain(&lines);
+        assert_eq!(text.len(), 3);
+        assert_eq!(text[1].trim_end(), " let x = 1;");
+        assert_eq!(lines[1].width(), 20);
+    }
+
+    #[test]
+    fn fences_name_their_language() {
+        let colors = |fence: &str, code: &str| {
+            let lines = render(&format!("```{}\n{}\n```", fence, code), 80, Style::default());
+            let mut colors: Vec<_> = lines[0].spans.iter().filter_map(|s| s.style.fg).collect();
+            colors.dedup();
+            colors.len()
+        };
+        assert!(colors("rust", "fn main() { let x = \"s\"; }") > 1);
+        assert!(colors("python", "def main(): return \"s\"") > 1);
+        assert!(colors("typescript", "const x: string = \"s\";") > 1);
+        assert!(colors("javascript", "function f() { return \"s\"; }") > 1);
+    }
+
+    #[test]
+    fn headings_quotes_and_links() {
+        let lines = render("## Title\n> quoted\n\n[docs](https://x.dev)", 80, Style::default());
+        assert_eq!(
+            plain(&lines),
+            vec!["Title", "▎ quoted", "", "docs (https://x.dev)"]
+        );
+    }
+}
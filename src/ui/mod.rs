@@ -1,6 +1,8 @@
+pub mod comments_panel;
 pub mod diff_view;
 pub mod file_tree;
 pub mod layout;
+pub mod markdown;
 pub mod styles;
 pub mod syntax;
 pub mod theme;
@@ -4,6 +4,8 @@
 
 use ratatui::style::{Color, Modifier, Style};
 
+use crate::domain::coverage::{FileCoverage, LineCoverage};
+use crate::domain::{AuthorKind, CommentStatus, Severity};
 use crate::ui::theme;
 
 pub fn ui() -> theme::UiTheme {
@@ -132,6 +134,57 @@ pub fn fg_cursor() -> Color {
     theme::current_ui().fg_cursor
 }
 
+/// Badge color for a comment severity.
+pub fn fg_severity(severity: Severity) -> Color {
+    match severity {
+        Severity::Blocking => fg_deletion(),
+        Severity::Suggestion => fg_hunk(),
+        Severity::Nit => fg_muted(),
+        Severity::Question => fg_warning(),
+        Severity::Praise => fg_addition(),
+    }
+}
+
+/// Color for a comment's workflow status badge.
+pub fn fg_status(status: CommentStatus) -> Color {
+    match status {
+        CommentStatus::Open => fg_default(),
+        CommentStatus::InProgress => fg_warning(),
+        CommentStatus::Addressed => fg_hunk(),
+        CommentStatus::WontFix => fg_muted(),
+        CommentStatus::Resolved => fg_addition(),
+    }
+}
+
+/// Color for the badge that marks agent and tool messages.
+pub fn fg_author_kind(kind: AuthorKind) -> Color {
+    match kind {
+        AuthorKind::Human => fg_default(),
+        AuthorKind::Agent => fg_hunk(),
+        AuthorKind::Tool => fg_warning(),
+    }
+}
+
+/// Gutter mark color for an added line's test coverage.
+pub fn fg_line_coverage(coverage: LineCoverage) -> Color {
+    match coverage {
+        LineCoverage::Covered => fg_addition(),
+        LineCoverage::Uncovered => fg_deletion(),
+        LineCoverage::NotInstrumented => fg_border(),
+    }
+}
+
+/// Color for a file's "new lines covered" percentage.
+pub fn fg_file_coverage(coverage: FileCoverage) -> Color {
+    if coverage.covered == coverage.instrumented {
+        fg_addition()
+    } else if coverage.covered == 0 {
+        fg_deletion()
+    } else {
+        fg_warning()
+    }
+}
+
 // Border characters
 pub fn border_top_left() -> &'static str {
     theme::current_ui().border_top_left
@@ -13,10 +13,12 @@ use crate::ui::theme;
 
 static SYNTAX_SET: Lazy<SyntaxSet> = Lazy::new(two_face::syntax::extra_newlines);
 
-/// Get syntax-highlighted spans for a line of code.
+/// Get syntax-highlighted spans for a line of code. `extension` may also be
+/// a language name, as in a ```` ```rust ```` fence.
 pub fn highlight_line(content: &str, extension: &str) -> Vec<Span<'static>> {
     let syntax = SYNTAX_SET
-        .find_syntax_by_extension(extension)
+        .find_syntax_by_token(extension)
+        .or_else(|| SYNTAX_SET.find_syntax_by_extension(extension))
         .or_else(|| SYNTAX_SET.find_syntax_by_extension("txt"))
         .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
 
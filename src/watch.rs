@@ -0,0 +1,113 @@
+//! `panko watch`: comment events as newline-delimited JSON.
+//!
+//! Polls the state store's change log, so changes made by any process (the
+//! TUI, other CLI calls, pulled notes) are reported. Each event carries a
+//! cursor; passing the last one seen to `--since` resumes without gaps.
+
+use crate::domain::Comment;
+use crate::output;
+use crate::ports::StateStore;
+use anyhow::Result;
+use std::io::Write;
+use std::thread;
+use std::time::Duration;
+
+#[derive(Debug, Clone, Copy)]
+pub struct WatchOptions {
+    /// Report events after this cursor (None = only new events)
+    pub since: Option<i64>,
+    /// Return once no comments are open or in progress
+    pub until_open_zero: bool,
+    /// Delay between polls of the change log
+    pub interval: Duration,
+}
+
+/// Write events for a repo/branch to `out`, one JSON document per line,
+/// until the output closes or (with `until_open_zero`) the review is done.
+pub fn watch(
+    store: &dyn StateStore,
+    repo_path: &str,
+    branch: &str,
+    options: WatchOptions,
+    mut out: impl Write,
+) -> Result<()> {
+    let mut cursor = match options.since {
+        Some(cursor) => cursor,
+        None => store.latest_event_cursor()?,
+    };
+    let mut comments: Option<Vec<Comment>> = None;
+
+    loop {
+        let events = store.get_comment_events(repo_path, branch, cursor)?;
+        if !events.is_empty() || comments.is_none() {
+            comments = Some(store.get_comments(repo_path, branch)?);
+        }
+        let current = comments.as_deref().unwrap_or_default();
+
+        for event in &events {
+            let line = output::WatchEvent {
+                event,
+                comment: current.iter().find(|c| c.id == event.comment_id),
+            };
+            writeln!(out, "{}", output::document(&line)?)?;
+            cursor = event.cursor;
+        }
+        out.flush()?;
+
+        if options.until_open_zero && current.iter().all(|c| c.status.is_closed()) {
+            return Ok(());
+        }
+        thread::sleep(options.interval);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::SqliteStateStore;
+    use crate::domain::AuthorKind;
+    use crate::ports::NewComment;
+    use serde_json::Value;
+
+    #[test]
+    fn replays_events_until_nothing_is_open() {
+        let store = SqliteStateStore::open_in_memory().unwrap();
+        let id = store
+            .add_comment("/repo", "main", NewComment {
+                file_path: Some("src/lib.rs".to_string()),
+                line_range: Some((3, 3)),
+                anchor: None,
+                body: "use a pool".to_string(),
+                author: "alice".to_string(),
+                author_kind: AuthorKind::Human,
+                severity: None,
+                labels: vec![],
+                review_id: None,
+                uuid: crate::domain::new_uuid(),
+            })
+            .unwrap();
+        store.resolve_comment(id).unwrap();
+
+        let options = WatchOptions { since: Some(0), until_open_zero: true, interval: Duration::ZERO };
+        let mut out = Vec::new();
+        watch(&store, "/repo", "main", options, &mut out).unwrap();
+
+        let lines: Vec<Value> = String::from_utf8(out)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[0]["schema_version"], 1);
+        assert_eq!(lines[0]["event"], "comment_added");
+        assert_eq!(lines[1]["event"], "resolved");
+        assert_eq!(lines[1]["comment"]["status"], "resolved");
+        assert!(lines[0]["cursor"].as_i64().unwrap() < lines[1]["cursor"].as_i64().unwrap());
+
+        // Resuming after the last cursor reports nothing new
+        let options = WatchOptions { since: lines[1]["cursor"].as_i64(), ..options };
+        let mut out = Vec::new();
+        watch(&store, "/repo", "main", options, &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+}